@@ -0,0 +1,99 @@
+//! Filters an in-memory SQLite table using a react-admin style querystring
+//!
+//! Run with `cargo run --example sqlx_sqlite --features sqlx-sqlite`
+
+use sea_query::{ColumnDef, Cond, Iden, Query, SqliteQueryBuilder, Table};
+use seaqs::{
+    filters::{NumberFilterSet, StringFilterSet},
+    ApplyFilters, Filter, QueryFilter, SqlxStatement, ToCond, ToFieldCond,
+};
+use serde::Deserialize;
+use serde_querystring::de::ParseMode;
+use sqlx::{sqlite::SqlitePoolOptions, FromRow};
+
+#[derive(Iden)]
+enum User {
+    Table,
+    Id,
+    Name,
+    Age,
+}
+
+#[derive(FromRow)]
+struct UserRow {
+    id: i64,
+    name: String,
+    age: i64,
+}
+
+#[derive(Deserialize)]
+struct UserFilters<'a> {
+    name: Option<StringFilterSet<'a>>,
+    age: Option<NumberFilterSet>,
+}
+
+impl<'a> ToCond for UserFilters<'a> {
+    fn to_cond(&self) -> Cond {
+        let mut cond = Cond::all();
+        if let Some(name) = self.name.to_cond(User::Name) {
+            cond = cond.add(name)
+        }
+        if let Some(age) = self.age.to_cond(User::Age) {
+            cond = cond.add(age)
+        }
+        cond
+    }
+}
+
+impl<'a> Filter for UserFilters<'a> {
+    const SORTABLE_FIELDS: &'static [&'static str] = &["id", "name", "age"];
+}
+
+#[tokio::main]
+async fn main() -> Result<(), sqlx::Error> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    let create = Table::create()
+        .table(User::Table)
+        .col(ColumnDef::new(User::Id).integer().primary_key())
+        .col(ColumnDef::new(User::Name).string().not_null())
+        .col(ColumnDef::new(User::Age).integer().not_null())
+        .to_string(SqliteQueryBuilder);
+    sqlx::query(&create).execute(&pool).await?;
+
+    let mut insert = Query::insert();
+    insert
+        .into_table(User::Table)
+        .columns([User::Name, User::Age]);
+    for (name, age) in [("John", 25), ("Johnny", 45), ("Jane", 30), ("Joe", 60)] {
+        insert.values_panic([name.into(), age.into()]);
+    }
+    SqlxStatement::sqlite(&insert)
+        .query()
+        .execute(&pool)
+        .await?;
+
+    // This would normally be the querystring of the request
+    let filters = serde_querystring::from_str::<QueryFilter<UserFilters>>(
+        "filter[age][gte]=20&filter[name][startswith]=J&start=0&end=3&sort=age&order=DESC",
+        ParseMode::Brackets,
+    )
+    .expect("a valid querystring");
+
+    let statement = SqlxStatement::sqlite(
+        Query::select()
+            .columns([User::Id, User::Name, User::Age])
+            .from(User::Table)
+            .apply_filters(&filters),
+    );
+
+    println!("{}", statement.sql());
+    for user in statement.query_as::<_, UserRow>().fetch_all(&pool).await? {
+        println!("#{}: {} ({})", user.id, user.name, user.age);
+    }
+
+    Ok(())
+}
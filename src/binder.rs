@@ -0,0 +1,183 @@
+//! Glue for running filtered sea-query statements through sqlx
+
+use sea_query_binder::{SqlxBinder, SqlxValues};
+use sqlx::{query::QueryAs, Database, FromRow, IntoArguments};
+
+/// A statement built for one of sqlx's drivers, holding the SQL and the values to bind to it
+///
+/// Build the statement with [`ApplyFilters`](crate::ApplyFilters) as usual, then pass it to the
+/// constructor matching your database and run [`SqlxStatement::query_as`] on the pool.
+#[derive(Debug, Clone)]
+pub struct SqlxStatement {
+    sql: String,
+    values: SqlxValues,
+}
+
+impl SqlxStatement {
+    #[cfg(feature = "sqlx-postgres")]
+    pub fn postgres<S: SqlxBinder>(statement: &S) -> Self {
+        let (sql, values) = statement.build_sqlx(sea_query::PostgresQueryBuilder);
+        Self { sql, values }
+    }
+
+    #[cfg(feature = "sqlx-mysql")]
+    pub fn mysql<S: SqlxBinder>(statement: &S) -> Self {
+        let (sql, values) = statement.build_sqlx(sea_query::MysqlQueryBuilder);
+        Self { sql, values }
+    }
+
+    #[cfg(feature = "sqlx-sqlite")]
+    pub fn sqlite<S: SqlxBinder>(statement: &S) -> Self {
+        let (sql, values) = statement.build_sqlx(sea_query::SqliteQueryBuilder);
+        Self { sql, values }
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn values(&self) -> &SqlxValues {
+        &self.values
+    }
+
+    /// A `sqlx::query_with` with the values bound, for statements returning no rows
+    pub fn query<'q, DB>(&'q self) -> sqlx::query::Query<'q, DB, SqlxValues>
+    where
+        DB: Database,
+        SqlxValues: IntoArguments<'q, DB>,
+    {
+        sqlx::query_with(&self.sql, self.values.clone())
+    }
+
+    /// A `sqlx::query_as_with` with the values bound, mapping each row into `O`
+    pub fn query_as<'q, DB, O>(&'q self) -> QueryAs<'q, DB, O, SqlxValues>
+    where
+        DB: Database,
+        O: for<'r> FromRow<'r, DB::Row>,
+        SqlxValues: IntoArguments<'q, DB>,
+    {
+        sqlx::query_as_with(&self.sql, self.values.clone())
+    }
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+#[cfg(test)]
+mod tests {
+    use sea_query::{ColumnDef, Iden, Query, SqliteQueryBuilder, Table};
+    use serde::Deserialize;
+    use serde_querystring::de::ParseMode;
+    use sqlx::{sqlite::SqlitePoolOptions, FromRow, SqlitePool};
+
+    use super::SqlxStatement;
+    use crate::{
+        filters::{NumberFilterSet, StringFilterSet},
+        ApplyFilters, Filter, QueryFilter, ToCond, ToFieldCond,
+    };
+
+    #[derive(Iden)]
+    enum User {
+        Table,
+        Id,
+        Name,
+        Age,
+    }
+
+    #[derive(Debug, FromRow, PartialEq)]
+    struct UserRow {
+        name: String,
+        age: i64,
+    }
+
+    #[derive(Deserialize)]
+    struct UserFilters<'a> {
+        name: Option<StringFilterSet<'a>>,
+        age: Option<NumberFilterSet>,
+    }
+
+    impl<'a> ToCond for UserFilters<'a> {
+        fn to_cond(&self) -> sea_query::Cond {
+            let mut cond = sea_query::Cond::all();
+            if let Some(name) = self.name.to_cond(User::Name) {
+                cond = cond.add(name)
+            }
+            if let Some(age) = self.age.to_cond(User::Age) {
+                cond = cond.add(age)
+            }
+            cond
+        }
+    }
+
+    impl<'a> Filter for UserFilters<'a> {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["name", "age"];
+    }
+
+    async fn pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let create = Table::create()
+            .table(User::Table)
+            .col(ColumnDef::new(User::Id).integer().primary_key())
+            .col(ColumnDef::new(User::Name).string().not_null())
+            .col(ColumnDef::new(User::Age).integer().not_null())
+            .to_string(SqliteQueryBuilder);
+        sqlx::query(&create).execute(&pool).await.unwrap();
+
+        let mut insert = Query::insert();
+        insert
+            .into_table(User::Table)
+            .columns([User::Name, User::Age]);
+        for (name, age) in [("John", 25), ("Johnny", 45), ("Jane", 30), ("Joe", 60)] {
+            insert.values_panic([name.into(), age.into()]);
+        }
+        SqlxStatement::sqlite(&insert)
+            .query()
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_query_as() {
+        let pool = pool().await;
+
+        let filters = serde_querystring::from_str::<QueryFilter<UserFilters>>(
+            "filter[age][lt]=50&filter[name][contains]=John&sort=age&order=DESC",
+            ParseMode::Brackets,
+        )
+        .unwrap();
+
+        let statement = SqlxStatement::sqlite(
+            Query::select()
+                .columns([User::Name, User::Age])
+                .from(User::Table)
+                .apply_filters(&filters),
+        );
+
+        assert_eq!(
+            statement.sql(),
+            r#"SELECT "name", "age" FROM "user" WHERE "name" LIKE ? AND "age" < ? ORDER BY "age" DESC LIMIT ? OFFSET ?"#
+        );
+
+        let users: Vec<UserRow> = statement.query_as().fetch_all(&pool).await.unwrap();
+
+        assert_eq!(
+            users,
+            vec![
+                UserRow {
+                    name: "Johnny".into(),
+                    age: 45
+                },
+                UserRow {
+                    name: "John".into(),
+                    age: 25
+                },
+            ]
+        );
+    }
+}
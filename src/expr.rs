@@ -0,0 +1,412 @@
+//! A boolean tree of typed filters, what the textual filter syntaxes parse into
+//!
+//! Filter structs deserialized from bracket querystrings can only AND their fields together.
+//! Syntaxes like RSQL also have OR and NOT, so they produce a [`FilterExpr`] instead, whose
+//! leaves are the same `*Filter` types and lower to the same conditions.
+
+use std::borrow::Cow;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::filters::{
+    DateFilter, DateTimeFilter, DateTimeTzFilter, NumberFilter, StringFilter, UuidFilter,
+};
+
+/// How deeply the textual syntaxes may nest groups and negations
+///
+/// Their parsers recurse once per level, so without a limit a few thousand `(` would overflow the
+/// stack.
+pub const MAX_DEPTH: usize = 32;
+
+/// The `*Filter` type values of a field are parsed into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    String,
+    Number,
+    Uuid,
+    Date,
+    DateTime,
+    DateTimeTz,
+}
+
+/// A field clients may filter on, and the column it's stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub column: &'static str,
+    pub kind: FieldKind,
+}
+
+impl Field {
+    pub const fn new(name: &'static str, kind: FieldKind) -> Self {
+        Self {
+            name,
+            column: name,
+            kind,
+        }
+    }
+
+    /// Read the field from a column named differently than the field
+    pub const fn column(mut self, column: &'static str) -> Self {
+        self.column = column;
+        self
+    }
+}
+
+/// The whitelist of fields a filter expression may refer to, anything else fails to parse
+pub trait ExprFields {
+    const FIELDS: &'static [Field];
+
+    fn field(name: &str) -> Option<&'static Field> {
+        Self::FIELDS.iter().find(|field| field.name == name)
+    }
+}
+
+#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug)]
+pub enum FieldFilter<'a> {
+    String(StringFilter<'a>),
+    Number(NumberFilter),
+    Uuid(UuidFilter),
+    Date(DateFilter),
+    DateTime(DateTimeFilter),
+    DateTimeTz(DateTimeTzFilter),
+}
+
+#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug)]
+pub enum FilterExpr<'a> {
+    Field(&'static Field, FieldFilter<'a>),
+    And(Vec<FilterExpr<'a>>),
+    Or(Vec<FilterExpr<'a>>),
+    Not(Box<FilterExpr<'a>>),
+}
+
+/// The comparisons every syntax has in some form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "<>",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
+impl<'a> FilterExpr<'a> {
+    /// `field op value`, the value being parsed according to the field's kind
+    pub(crate) fn compare(
+        field: &'static Field,
+        op: CompareOp,
+        value: &str,
+    ) -> Result<Self, String> {
+        use CompareOp::*;
+
+        let leaf = |filter| Ok(FilterExpr::Field(field, filter));
+
+        match field.kind {
+            FieldKind::String => {
+                let value = Cow::Owned(value.to_owned());
+                match op {
+                    Eq => leaf(FieldFilter::String(StringFilter::Equals(value))),
+                    Ne => leaf(FieldFilter::String(StringFilter::NotEquals(value))),
+                    _ => Err(format!(
+                        "`{}` isn't supported on the text field `{}`",
+                        op.symbol(),
+                        field.name
+                    )),
+                }
+            }
+            FieldKind::Number => {
+                let value = parse_value::<i64>(field, value)?;
+                leaf(FieldFilter::Number(match op {
+                    Eq => NumberFilter::Equals(value),
+                    Ne => NumberFilter::NotEquals(value),
+                    Lt => NumberFilter::LesserThan(value),
+                    Le => NumberFilter::LesserThanEqual(value),
+                    Gt => NumberFilter::GreaterThan(value),
+                    Ge => NumberFilter::GreaterThanEqual(value),
+                }))
+            }
+            FieldKind::Uuid => {
+                let value = parse_value::<Uuid>(field, value)?;
+                leaf(FieldFilter::Uuid(match op {
+                    Eq => UuidFilter::Equals(value),
+                    Ne => UuidFilter::NotEquals(value),
+                    Lt => UuidFilter::LesserThan(value),
+                    Le => UuidFilter::LesserThanEqual(value),
+                    Gt => UuidFilter::GreaterThan(value),
+                    Ge => UuidFilter::GreaterThanEqual(value),
+                }))
+            }
+            FieldKind::Date => {
+                let value = parse_value::<NaiveDate>(field, value)?;
+                leaf(FieldFilter::Date(match op {
+                    Eq => DateFilter::Equals(value),
+                    Ne => DateFilter::NotEquals(value),
+                    Lt => DateFilter::Before(value),
+                    Le => DateFilter::LesserThanEqual(value),
+                    Gt => DateFilter::GreaterThan(value),
+                    Ge => DateFilter::After(value),
+                }))
+            }
+            FieldKind::DateTime => {
+                let value = parse_value::<NaiveDateTime>(field, value)?;
+                leaf(FieldFilter::DateTime(match op {
+                    Eq => DateTimeFilter::Equals(value),
+                    Ne => DateTimeFilter::NotEquals(value),
+                    Lt => DateTimeFilter::Before(value),
+                    Le => DateTimeFilter::LesserThanEqual(value),
+                    Gt => DateTimeFilter::GreaterThan(value),
+                    Ge => DateTimeFilter::After(value),
+                }))
+            }
+            FieldKind::DateTimeTz => {
+                let value =
+                    DateTime::parse_from_rfc3339(value).map_err(|_| invalid_value(field, value))?;
+                leaf(FieldFilter::DateTimeTz(match op {
+                    Eq => DateTimeTzFilter::Equals(value),
+                    Ne => DateTimeTzFilter::NotEquals(value),
+                    Lt => DateTimeTzFilter::Before(value),
+                    Le => DateTimeTzFilter::LesserThanEqual(value),
+                    Gt => DateTimeTzFilter::GreaterThan(value),
+                    Ge => DateTimeTzFilter::After(value),
+                }))
+            }
+        }
+    }
+
+    /// `field IN (values)`, or `NOT IN` when `negated`
+    ///
+    /// Only `UuidFilter` has an `in` of its own, other kinds OR equalities together, or AND
+    /// inequalities for `NOT IN`.
+    pub(crate) fn one_of<S: AsRef<str>>(
+        field: &'static Field,
+        values: &[S],
+        negated: bool,
+    ) -> Result<Self, String> {
+        if field.kind == FieldKind::Uuid {
+            let values = values
+                .iter()
+                .map(|value| parse_value::<Uuid>(field, value.as_ref()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let filter = match negated {
+                false => UuidFilter::In(values),
+                true => UuidFilter::NotIn(values),
+            };
+            return Ok(FilterExpr::Field(field, FieldFilter::Uuid(filter)));
+        }
+
+        let op = if negated {
+            CompareOp::Ne
+        } else {
+            CompareOp::Eq
+        };
+        let exprs = values
+            .iter()
+            .map(|value| Self::compare(field, op, value.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(match negated {
+            false => FilterExpr::Or(exprs),
+            true => FilterExpr::And(exprs),
+        })
+    }
+
+    /// `field IN (values)` kept as a single filter on the kinds which have one, `NOT IN` being
+    /// its negation, and [`one_of`](Self::one_of) on the others
+    pub(crate) fn in_list(
+        field: &'static Field,
+        values: Vec<String>,
+        negated: bool,
+    ) -> Result<Self, String> {
+        let filter = match field.kind {
+            FieldKind::String => FieldFilter::String(StringFilter::In(
+                values.into_iter().map(Cow::Owned).collect(),
+            )),
+            FieldKind::Number => {
+                let values = values
+                    .iter()
+                    .map(|value| parse_value(field, value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                FieldFilter::Number(NumberFilter::In(values))
+            }
+            _ => return Self::one_of(field, &values, negated),
+        };
+
+        let expr = FilterExpr::Field(field, filter);
+        Ok(match negated {
+            false => expr,
+            true => FilterExpr::Not(Box::new(expr)),
+        })
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(field: &Field, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| invalid_value(field, value))
+}
+
+fn invalid_value(field: &Field, value: &str) -> String {
+    let kind = match field.kind {
+        FieldKind::String => "a text",
+        FieldKind::Number => "an integer",
+        FieldKind::Uuid => "a UUID",
+        FieldKind::Date => "a date",
+        FieldKind::DateTime => "a date and time",
+        FieldKind::DateTimeTz => "an RFC 3339 date and time",
+    };
+    format!("`{}` is not {} for field `{}`", value, kind, field.name)
+}
+
+#[cfg(feature = "seaq")]
+mod seaq {
+    use sea_query::Cond;
+
+    use super::{FieldFilter, FilterExpr};
+    use crate::seaq::{IntoColumnRefStr, ToCond, ToFieldCond};
+
+    impl<'a> FieldFilter<'a> {
+        fn to_column_cond(&self, column: &'static str) -> Option<Cond> {
+            let column = IntoColumnRefStr(column);
+            match self {
+                FieldFilter::String(filter) => filter.to_cond(column),
+                FieldFilter::Number(filter) => filter.to_cond(column),
+                FieldFilter::Uuid(filter) => filter.to_cond(column),
+                FieldFilter::Date(filter) => filter.to_cond(column),
+                FieldFilter::DateTime(filter) => filter.to_cond(column),
+                FieldFilter::DateTimeTz(filter) => filter.to_cond(column),
+            }
+        }
+    }
+
+    impl<'a> ToCond for FilterExpr<'a> {
+        fn to_cond(&self) -> Cond {
+            match self {
+                FilterExpr::Field(field, filter) => filter
+                    .to_column_cond(field.column)
+                    .unwrap_or_else(Cond::all),
+                FilterExpr::And(exprs) => exprs
+                    .iter()
+                    .fold(Cond::all(), |cond, expr| cond.add(expr.to_cond())),
+                FilterExpr::Or(exprs) => exprs
+                    .iter()
+                    .fold(Cond::any(), |cond, expr| cond.add(expr.to_cond())),
+                FilterExpr::Not(expr) => expr.to_cond().not(),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use sea_query::{Alias, Iden, PostgresQueryBuilder, Query};
+
+        use super::super::{Field, FieldKind, FilterExpr};
+        use crate::{expr::CompareOp, seaq::ApplyConds};
+
+        #[derive(Iden)]
+        struct User;
+
+        const AGE: Field = Field::new("age", FieldKind::Number);
+        const NAME: Field = Field::new("name", FieldKind::String).column("full_name");
+        const BIRTHDAY: Field = Field::new("birthday", FieldKind::Date);
+
+        #[test]
+        fn test_to_cond() {
+            let expr = FilterExpr::Or(vec![
+                FilterExpr::And(vec![
+                    FilterExpr::compare(&AGE, CompareOp::Ge, "20").unwrap(),
+                    FilterExpr::compare(&BIRTHDAY, CompareOp::Le, "2000-01-01").unwrap(),
+                ]),
+                FilterExpr::Not(Box::new(
+                    FilterExpr::one_of(&NAME, &["john", "jane"], false).unwrap(),
+                )),
+            ]);
+
+            let q = Query::select()
+                .column(Alias::new("age"))
+                .from(User)
+                .apply_conds(&expr)
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(
+                q,
+                "SELECT \"age\" FROM \"user\" WHERE (\"age\" >= 20 \
+                 AND \"birthday\" <= '2000-01-01') \
+                 OR (NOT (\"full_name\" = 'john' OR \"full_name\" = 'jane'))"
+            );
+        }
+
+        #[test]
+        fn test_date_comparisons() {
+            let sql = |op| {
+                let expr = FilterExpr::compare(&BIRTHDAY, op, "2000-01-01").unwrap();
+                Query::select()
+                    .column(Alias::new("age"))
+                    .from(User)
+                    .apply_conds(&expr)
+                    .to_string(PostgresQueryBuilder)
+            };
+
+            let expected = |cond| format!("SELECT \"age\" FROM \"user\" WHERE {cond}");
+            assert_eq!(sql(CompareOp::Lt), expected("\"birthday\" < '2000-01-01'"));
+            assert_eq!(sql(CompareOp::Le), expected("\"birthday\" <= '2000-01-01'"));
+            assert_eq!(sql(CompareOp::Gt), expected("\"birthday\" > '2000-01-01'"));
+            assert_eq!(sql(CompareOp::Ge), expected("\"birthday\" >= '2000-01-01'"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompareOp, Field, FieldFilter, FieldKind, FilterExpr};
+    use crate::filters::{NumberFilter, UuidFilter};
+
+    const AGE: Field = Field::new("age", FieldKind::Number);
+    const ID: Field = Field::new("id", FieldKind::Uuid);
+    const NAME: Field = Field::new("name", FieldKind::String);
+
+    #[test]
+    fn compare() {
+        assert_eq!(
+            FilterExpr::compare(&AGE, CompareOp::Lt, "18").unwrap(),
+            FilterExpr::Field(&AGE, FieldFilter::Number(NumberFilter::LesserThan(18)))
+        );
+        assert_eq!(
+            FilterExpr::compare(&AGE, CompareOp::Eq, "eighteen").unwrap_err(),
+            "`eighteen` is not an integer for field `age`"
+        );
+        assert_eq!(
+            FilterExpr::compare(&NAME, CompareOp::Gt, "a").unwrap_err(),
+            "`>` isn't supported on the text field `name`"
+        );
+    }
+
+    #[test]
+    fn one_of() {
+        let id = uuid::uuid!("00000000-0000-0000-0000-ffff00000001");
+        assert_eq!(
+            FilterExpr::one_of(&ID, &[id.to_string()], true).unwrap(),
+            FilterExpr::Field(&ID, FieldFilter::Uuid(UuidFilter::NotIn(vec![id])))
+        );
+        assert_eq!(
+            FilterExpr::one_of(&AGE, &["1", "2"], false).unwrap(),
+            FilterExpr::Or(vec![
+                FilterExpr::Field(&AGE, FieldFilter::Number(NumberFilter::Equals(1))),
+                FilterExpr::Field(&AGE, FieldFilter::Number(NumberFilter::Equals(2))),
+            ])
+        );
+    }
+}
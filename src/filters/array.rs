@@ -0,0 +1,439 @@
+use serde::{Deserialize, Serialize};
+use serde_with::EnumMap;
+
+use super::NumberFilterSet;
+
+/// Filters on a Postgres array column like `text[]` or `int[]`, `T` being the element type
+#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ArrayFilter<T> {
+    /// The column has all of the values, `@>`
+    #[serde(rename = "contains")]
+    Contains(Vec<T>),
+    /// The values have all of the column's elements, `<@`
+    #[serde(rename = "containedby")]
+    ContainedBy(Vec<T>),
+    /// The column has at least one of the values, `&&`
+    #[serde(rename = "overlaps")]
+    Overlaps(Vec<T>),
+    /// The column has the value, `value = ANY(col)`
+    #[serde(rename = "any")]
+    Any(T),
+    /// The number of elements, `tags[length][gte]=2`
+    #[serde(rename = "length")]
+    Length(NumberFilterSet),
+}
+
+#[cfg_attr(test, derive(PartialEq))]
+#[serde_with::serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct ArrayFilterSet<T>(#[serde_as(as = "EnumMap")] pub(crate) Vec<ArrayFilter<T>>);
+
+impl<T> ArrayFilterSet<T> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn push(&mut self, value: ArrayFilter<T>) {
+        self.0.push(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ArrayFilter<T>> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[ArrayFilter<T>] {
+        &self.0
+    }
+
+    pub fn contains(mut self, values: impl IntoIterator<Item = T>) -> Self {
+        self.push(ArrayFilter::Contains(values.into_iter().collect()));
+        self
+    }
+
+    pub fn contained_by(mut self, values: impl IntoIterator<Item = T>) -> Self {
+        self.push(ArrayFilter::ContainedBy(values.into_iter().collect()));
+        self
+    }
+
+    pub fn overlaps(mut self, values: impl IntoIterator<Item = T>) -> Self {
+        self.push(ArrayFilter::Overlaps(values.into_iter().collect()));
+        self
+    }
+
+    pub fn any(mut self, value: T) -> Self {
+        self.push(ArrayFilter::Any(value));
+        self
+    }
+
+    pub fn length(mut self, filters: NumberFilterSet) -> Self {
+        self.push(ArrayFilter::Length(filters));
+        self
+    }
+}
+
+impl<T> Default for ArrayFilterSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<ArrayFilter<T>> for ArrayFilterSet<T> {
+    fn from_iter<I: IntoIterator<Item = ArrayFilter<T>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> Extend<ArrayFilter<T>> for ArrayFilterSet<T> {
+    fn extend<I: IntoIterator<Item = ArrayFilter<T>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<T> IntoIterator for ArrayFilterSet<T> {
+    type Item = ArrayFilter<T>;
+    type IntoIter = std::vec::IntoIter<ArrayFilter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s, T> IntoIterator for &'s ArrayFilterSet<T> {
+    type Item = &'s ArrayFilter<T>;
+    type IntoIter = std::slice::Iter<'s, ArrayFilter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl<'__s, T> utoipa::ToSchema<'__s> for ArrayFilterSet<T> {
+    fn schema() -> (
+        &'__s str,
+        utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
+    ) {
+        use utoipa::openapi::{ArrayBuilder, ObjectBuilder};
+
+        // The element type isn't known to utoipa, so values are left untyped
+        let values = || ArrayBuilder::new().items(ObjectBuilder::new());
+        (
+            "ArrayFilterSet",
+            ArrayBuilder::new()
+                .items(
+                    ObjectBuilder::new()
+                        .property("contains", values())
+                        .property("containedby", values())
+                        .property("overlaps", values())
+                        .property("any", ObjectBuilder::new())
+                        .property("length", NumberFilterSet::schema().1),
+                )
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::{ArrayFilter::*, ArrayFilterSet};
+    use crate::{filters::NumberFilterSet, to_querystring};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Sample {
+        tags: ArrayFilterSet<String>,
+        roles: ArrayFilterSet<i64>,
+    }
+
+    #[test]
+    fn deserialize() {
+        const QUERY: &str = "tags[contains]=a\
+                             &tags[contains]=b\
+                             &tags[length][gte]=2\
+                             &roles[overlaps]=1\
+                             &roles[overlaps]=2\
+                             &roles[containedby]=1\
+                             &roles[any]=3";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        assert_eq!(res.tags.len(), 2);
+        assert!(res
+            .tags
+            .iter()
+            .any(|f| f == &Contains(vec!["a".into(), "b".into()])));
+        assert!(res
+            .tags
+            .iter()
+            .any(|f| f == &Length(NumberFilterSet::new().gte(2))));
+
+        assert_eq!(res.roles.len(), 3);
+        for filter in [Overlaps(vec![1, 2]), ContainedBy(vec![1]), Any(3)] {
+            assert!(res.roles.iter().any(|f| f == &filter), "{filter:?}");
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let sample = Sample {
+            tags: ArrayFilterSet::new()
+                .contains(["a".to_string(), "b".to_string()])
+                .any("c".to_string()),
+            roles: ArrayFilterSet::new()
+                .length(NumberFilterSet::new().lt(3))
+                .overlaps([1]),
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "tags[contains]=a&tags[contains]=b&tags[any]=c\
+             &roles[length][lt]=3&roles[overlaps]=1"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(res.tags.len(), sample.tags.len());
+        assert!(sample
+            .tags
+            .iter()
+            .all(|filter| res.tags.iter().any(|f| f == filter)));
+        assert_eq!(res.roles, sample.roles);
+    }
+
+    #[test]
+    fn build() {
+        let set = ArrayFilterSet::new()
+            .contains([1])
+            .contained_by([1, 2])
+            .overlaps([3])
+            .any(4);
+        assert_eq!(
+            set.as_slice(),
+            &[
+                Contains(vec![1]),
+                ContainedBy(vec![1, 2]),
+                Overlaps(vec![3]),
+                Any(4)
+            ]
+        );
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, Any(_)))
+            .collect::<ArrayFilterSet<i64>>();
+        set.extend([Contains(vec![5])]);
+        assert_eq!(set.len(), 2);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&Any(4), &Contains(vec![5])]
+        );
+    }
+}
+
+mod seaq {
+    use sea_query::{
+        extension::postgres::{PgBinOper, PgFunc},
+        Alias, Cond, Expr, Func, IntoColumnRef, IntoCondition, SimpleExpr, Value, ValueType,
+    };
+
+    use super::{ArrayFilter, ArrayFilterSet};
+    use crate::seaq::ToFieldCond;
+
+    fn array<T>(values: &[T]) -> SimpleExpr
+    where
+        T: Clone + Into<Value> + ValueType,
+    {
+        let values = values.iter().cloned().map(Into::into).collect();
+        Expr::val(Value::Array(T::array_type(), Some(Box::new(values)))).into()
+    }
+
+    impl<T> ToFieldCond for ArrayFilter<T>
+    where
+        T: Clone + Into<Value> + ValueType,
+    {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            let col = Expr::col(iden);
+            Some(match self {
+                ArrayFilter::Contains(val) => {
+                    col.binary(PgBinOper::Contains, array(val)).into_condition()
+                }
+                ArrayFilter::ContainedBy(val) => col
+                    .binary(PgBinOper::Contained, array(val))
+                    .into_condition(),
+                ArrayFilter::Overlaps(val) => {
+                    Expr::cust_with_exprs("$1 && $2", [col.into(), array(val)]).into_condition()
+                }
+                ArrayFilter::Any(val) => {
+                    Expr::val(val.clone()).eq(PgFunc::any(col)).into_condition()
+                }
+                ArrayFilter::Length(filters) => {
+                    filters.to_expr_cond(Func::cust(Alias::new("cardinality")).arg(col).into())
+                }
+            })
+        }
+    }
+
+    impl<T> ToFieldCond for ArrayFilterSet<T>
+    where
+        T: Clone + Into<Value> + ValueType,
+    {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            let mut conds = Cond::all();
+            let col_ref = iden.into_column_ref();
+            for filter in self.0.iter() {
+                if let Some(filter) = filter.to_cond(col_ref.clone()) {
+                    conds = conds.add(filter);
+                }
+            }
+            Some(conds)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::borrow::Cow;
+
+        use super::ArrayFilter::*;
+        use crate::{
+            filters::{ArrayFilter, ArrayFilterSet, NumberFilterSet},
+            test_utils::check_query,
+        };
+
+        #[test]
+        fn test_contains() {
+            check_query(
+                Contains::<Cow<str>>(vec!["a".into(), "b".into()]),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" @> ARRAY ['a','b']"#,
+            );
+        }
+
+        #[test]
+        fn test_contained_by() {
+            check_query(
+                ContainedBy(vec![1i64, 2]),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" <@ ARRAY [1,2]"#,
+            );
+        }
+
+        #[test]
+        fn test_overlaps() {
+            check_query(
+                Overlaps(vec![1i64, 2]),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" && ARRAY [1,2]"#,
+            );
+        }
+
+        #[test]
+        fn test_any() {
+            check_query(
+                Any(String::from("admin")),
+                r#"SELECT "image" FROM "glyph" WHERE 'admin' = ANY("aspect")"#,
+            );
+        }
+
+        #[test]
+        fn test_length() {
+            check_query(
+                ArrayFilter::<i64>::Length(NumberFilterSet::new().gte(1).lt(5)),
+                r#"SELECT "image" FROM "glyph" WHERE cardinality("aspect") >= 1 AND cardinality("aspect") < 5"#,
+            );
+        }
+
+        #[test]
+        fn test_set() {
+            let set = ArrayFilterSet::new().contains([1i64]).any(2);
+
+            check_query(
+                set,
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" @> ARRAY [1] AND 2 = ANY("aspect")"#,
+            );
+        }
+    }
+}
+
+mod memory {
+    use super::{ArrayFilter, ArrayFilterSet};
+    use crate::memory::Matches;
+
+    impl<T, E> Matches<[E]> for ArrayFilter<T>
+    where
+        E: PartialEq<T>,
+    {
+        fn matches(&self, value: &[E]) -> bool {
+            match self {
+                ArrayFilter::Contains(val) => val.iter().all(|v| value.iter().any(|e| e == v)),
+                ArrayFilter::ContainedBy(val) => value.iter().all(|e| val.iter().any(|v| e == v)),
+                ArrayFilter::Overlaps(val) => val.iter().any(|v| value.iter().any(|e| e == v)),
+                ArrayFilter::Any(val) => value.iter().any(|e| e == val),
+                ArrayFilter::Length(filters) => filters.matches(&(value.len() as i64)),
+            }
+        }
+    }
+
+    impl<T, E> Matches<Vec<E>> for ArrayFilter<T>
+    where
+        E: PartialEq<T>,
+    {
+        fn matches(&self, value: &Vec<E>) -> bool {
+            self.matches(value.as_slice())
+        }
+    }
+
+    impl<T, V: ?Sized> Matches<V> for ArrayFilterSet<T>
+    where
+        ArrayFilter<T>: Matches<V>,
+    {
+        fn matches(&self, value: &V) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::borrow::Cow;
+
+        use super::ArrayFilter::*;
+        use crate::{
+            filters::{ArrayFilter, ArrayFilterSet, NumberFilterSet},
+            memory::Matches,
+        };
+
+        #[test]
+        fn test_matches() {
+            let tags = vec![String::from("a"), String::from("b")];
+
+            assert!(Contains::<Cow<str>>(vec!["a".into()]).matches(&tags));
+            assert!(!Contains::<Cow<str>>(vec!["a".into(), "c".into()]).matches(&tags));
+            assert!(
+                ContainedBy::<Cow<str>>(vec!["a".into(), "b".into(), "c".into()]).matches(&tags)
+            );
+            assert!(!ContainedBy::<Cow<str>>(vec!["a".into()]).matches(&tags));
+            assert!(Overlaps::<Cow<str>>(vec!["c".into(), "b".into()]).matches(&tags));
+            assert!(!Overlaps::<Cow<str>>(vec![]).matches(&tags));
+            assert!(Any::<Cow<str>>("b".into()).matches(&tags));
+            assert!(!Any::<Cow<str>>("c".into()).matches(&tags));
+            assert!(
+                ArrayFilter::<i64>::Length(NumberFilterSet::new().eq(2)).matches(&[1i64, 2][..])
+            );
+
+            let set = ArrayFilterSet::new()
+                .contains([1i64])
+                .length(NumberFilterSet::new().lt(3));
+            assert!(set.matches(&vec![1i64, 2]));
+            assert!(!set.matches(&vec![1i64, 2, 3]));
+            assert!(ArrayFilterSet::<i64>::new().matches(&Vec::<i64>::new()));
+        }
+    }
+}
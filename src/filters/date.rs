@@ -1,26 +1,43 @@
 use chrono::NaiveDate;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::EnumMap;
 
 #[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum DateFilter {
+    /// Strictly before, `<`
+    #[serde(alias = "lt")]
     Before(NaiveDate),
+    /// On or after, `>=`
     After(NaiveDate),
     #[serde(rename = "eq")]
     Equals(NaiveDate),
     #[serde(rename = "neq")]
     NotEquals(NaiveDate),
+    /// On or before, `<=`
+    #[serde(rename = "lte")]
+    LesserThanEqual(NaiveDate),
+    /// Strictly after, `>`
+    #[serde(rename = "gt")]
+    GreaterThan(NaiveDate),
+    /// On or after, `>=`, like `after`
+    #[serde(rename = "gte")]
+    GreaterThanEqual(NaiveDate),
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 #[serde_with::serde_as]
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct DateFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<DateFilter>);
 
 impl DateFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn push(&mut self, value: DateFilter) {
         self.0.push(value);
     }
@@ -28,6 +45,83 @@ impl DateFilterSet {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DateFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[DateFilter] {
+        &self.0
+    }
+
+    pub fn before(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::Before(value));
+        self
+    }
+
+    pub fn after(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::After(value));
+        self
+    }
+
+    pub fn lte(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::LesserThanEqual(value));
+        self
+    }
+
+    pub fn gt(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::GreaterThan(value));
+        self
+    }
+
+    pub fn gte(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::GreaterThanEqual(value));
+        self
+    }
+
+    pub fn eq(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::Equals(value));
+        self
+    }
+
+    pub fn neq(mut self, value: NaiveDate) -> Self {
+        self.push(DateFilter::NotEquals(value));
+        self
+    }
+}
+
+impl FromIterator<DateFilter> for DateFilterSet {
+    fn from_iter<I: IntoIterator<Item = DateFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<DateFilter> for DateFilterSet {
+    fn extend<I: IntoIterator<Item = DateFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for DateFilterSet {
+    type Item = DateFilter;
+    type IntoIter = std::vec::IntoIter<DateFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s DateFilterSet {
+    type Item = &'s DateFilter;
+    type IntoIter = std::slice::Iter<'s, DateFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(feature = "openapi")]
@@ -51,13 +145,13 @@ mod tests {
     use std::iter::FromIterator;
 
     use chrono::NaiveDate;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use serde_querystring::de::ParseMode;
 
     use super::DateFilter::*;
-    use crate::filters::DateFilterSet;
+    use crate::{filters::DateFilterSet, to_querystring};
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct Sample {
         birthday: DateFilterSet,
         register_date: DateFilterSet,
@@ -65,7 +159,7 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        const QUERY: &'static str = "birthday[before]=1993-10-15\
+        const QUERY: &str = "birthday[before]=1993-10-15\
                                     &birthday[eq]=1993-2-28\
                                     &register_date[after]=2022-10-15\
                                     &register_date[neq]=2022-10-15";
@@ -90,6 +184,87 @@ mod tests {
             BTreeSet::from_iter(register_date.0.iter())
         );
     }
+
+    #[test]
+    fn deserialize_aliases() {
+        const QUERY: &str = "birthday[lt]=1993-10-15&birthday[lte]=1993-10-16\
+                             &register_date[gt]=2022-10-15&register_date[gte]=2022-10-14";
+
+        let res = serde_querystring::from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let date = |day| NaiveDate::from_ymd_opt(2022, 10, day).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.birthday.0.iter()),
+            BTreeSet::from_iter(
+                DateFilterSet::new()
+                    .before(NaiveDate::from_ymd_opt(1993, 10, 15).unwrap())
+                    .lte(NaiveDate::from_ymd_opt(1993, 10, 16).unwrap())
+                    .0
+                    .iter()
+            )
+        );
+        assert_eq!(
+            BTreeSet::from_iter(res.register_date.0.iter()),
+            BTreeSet::from_iter([GreaterThan(date(15)), GreaterThanEqual(date(14))].iter())
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let mut birthday = DateFilterSet::default();
+        birthday.push(Before(NaiveDate::from_ymd_opt(1993, 10, 15).unwrap()));
+        birthday.push(After(NaiveDate::from_ymd_opt(1990, 1, 1).unwrap()));
+        let mut register_date = DateFilterSet::default();
+        register_date.push(Equals(NaiveDate::from_ymd_opt(2022, 10, 15).unwrap()));
+        register_date.push(NotEquals(NaiveDate::from_ymd_opt(2022, 2, 28).unwrap()));
+        let sample = Sample {
+            birthday,
+            register_date,
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "birthday[before]=1993-10-15\
+             &birthday[after]=1990-01-01\
+             &register_date[eq]=2022-10-15\
+             &register_date[neq]=2022-02-28"
+        );
+
+        let res = serde_querystring::from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.birthday.0.iter()),
+            BTreeSet::from_iter(sample.birthday.0.iter())
+        );
+        assert_eq!(
+            BTreeSet::from_iter(res.register_date.0.iter()),
+            BTreeSet::from_iter(sample.register_date.0.iter())
+        );
+    }
+
+    #[test]
+    fn build() {
+        let date = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let set = DateFilterSet::new()
+            .before(date)
+            .after(date)
+            .eq(date)
+            .neq(date);
+        assert_eq!(
+            set.as_slice(),
+            &[Before(date), After(date), Equals(date), NotEquals(date)]
+        );
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, Before(_)))
+            .collect::<DateFilterSet>();
+        set.extend([Equals(date)]);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&Before(date), &Equals(date)]
+        );
+    }
 }
 
 #[cfg(feature = "seaq")]
@@ -107,6 +282,9 @@ mod seaq {
                 DateFilter::NotEquals(val) => Expr::col(iden).ne(*val).into_condition(),
                 DateFilter::Before(val) => Expr::col(iden).lt(*val).into_condition(),
                 DateFilter::After(val) => Expr::col(iden).gte(*val).into_condition(),
+                DateFilter::LesserThanEqual(val) => Expr::col(iden).lte(*val).into_condition(),
+                DateFilter::GreaterThan(val) => Expr::col(iden).gt(*val).into_condition(),
+                DateFilter::GreaterThanEqual(val) => Expr::col(iden).gte(*val).into_condition(),
             })
         }
     }
@@ -147,6 +325,23 @@ mod seaq {
             );
         }
 
+        #[test]
+        fn test_comparisons() {
+            let date = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+            check_query(
+                LesserThanEqual(date),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" <= '2022-10-15'"#,
+            );
+            check_query(
+                GreaterThan(date),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" > '2022-10-15'"#,
+            );
+            check_query(
+                GreaterThanEqual(date),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" >= '2022-10-15'"#,
+            );
+        }
+
         #[test]
         fn test_eq() {
             check_query(
@@ -176,3 +371,60 @@ mod seaq {
         }
     }
 }
+
+mod memory {
+    use chrono::NaiveDate;
+
+    use super::{DateFilter, DateFilterSet};
+    use crate::memory::Matches;
+
+    impl Matches<NaiveDate> for DateFilter {
+        fn matches(&self, value: &NaiveDate) -> bool {
+            match self {
+                DateFilter::Equals(val) => value == val,
+                DateFilter::NotEquals(val) => value != val,
+                DateFilter::Before(val) => value < val,
+                DateFilter::After(val) => value >= val,
+                DateFilter::LesserThanEqual(val) => value <= val,
+                DateFilter::GreaterThan(val) => value > val,
+                DateFilter::GreaterThanEqual(val) => value >= val,
+            }
+        }
+    }
+
+    impl Matches<NaiveDate> for DateFilterSet {
+        fn matches(&self, value: &NaiveDate) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use chrono::NaiveDate;
+
+        use super::DateFilter::*;
+        use crate::{filters::DateFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            let day = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+            let next_day = NaiveDate::from_ymd_opt(2022, 10, 16).unwrap();
+
+            assert!(Equals(day).matches(&day));
+            assert!(NotEquals(day).matches(&next_day));
+            assert!(Before(next_day).matches(&day));
+            assert!(!Before(day).matches(&day));
+            assert!(After(day).matches(&day));
+            assert!(!After(next_day).matches(&day));
+            assert!(LesserThanEqual(day).matches(&day));
+            assert!(!LesserThanEqual(day).matches(&next_day));
+            assert!(GreaterThan(day).matches(&next_day));
+            assert!(!GreaterThan(day).matches(&day));
+            assert!(GreaterThanEqual(day).matches(&day));
+
+            let set = DateFilterSet::new().after(day).before(next_day);
+            assert!(set.matches(&day));
+            assert!(!set.matches(&next_day));
+        }
+    }
+}
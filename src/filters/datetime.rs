@@ -1,26 +1,43 @@
 use chrono::NaiveDateTime;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::EnumMap;
 
 #[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum DateTimeFilter {
+    /// Strictly before, `<`
+    #[serde(alias = "lt")]
     Before(NaiveDateTime),
+    /// On or after, `>=`
     After(NaiveDateTime),
     #[serde(rename = "eq")]
     Equals(NaiveDateTime),
     #[serde(rename = "neq")]
     NotEquals(NaiveDateTime),
+    /// On or before, `<=`
+    #[serde(rename = "lte")]
+    LesserThanEqual(NaiveDateTime),
+    /// Strictly after, `>`
+    #[serde(rename = "gt")]
+    GreaterThan(NaiveDateTime),
+    /// On or after, `>=`, like `after`
+    #[serde(rename = "gte")]
+    GreaterThanEqual(NaiveDateTime),
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 #[serde_with::serde_as]
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct DateTimeFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<DateTimeFilter>);
 
 impl DateTimeFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn push(&mut self, value: DateTimeFilter) {
         self.0.push(value);
     }
@@ -28,6 +45,83 @@ impl DateTimeFilterSet {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DateTimeFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[DateTimeFilter] {
+        &self.0
+    }
+
+    pub fn before(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::Before(value));
+        self
+    }
+
+    pub fn after(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::After(value));
+        self
+    }
+
+    pub fn lte(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::LesserThanEqual(value));
+        self
+    }
+
+    pub fn gt(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::GreaterThan(value));
+        self
+    }
+
+    pub fn gte(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::GreaterThanEqual(value));
+        self
+    }
+
+    pub fn eq(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::Equals(value));
+        self
+    }
+
+    pub fn neq(mut self, value: NaiveDateTime) -> Self {
+        self.push(DateTimeFilter::NotEquals(value));
+        self
+    }
+}
+
+impl FromIterator<DateTimeFilter> for DateTimeFilterSet {
+    fn from_iter<I: IntoIterator<Item = DateTimeFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<DateTimeFilter> for DateTimeFilterSet {
+    fn extend<I: IntoIterator<Item = DateTimeFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for DateTimeFilterSet {
+    type Item = DateTimeFilter;
+    type IntoIter = std::vec::IntoIter<DateTimeFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s DateTimeFilterSet {
+    type Item = &'s DateTimeFilter;
+    type IntoIter = std::slice::Iter<'s, DateTimeFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(feature = "openapi")]
@@ -51,13 +145,13 @@ mod tests {
     use std::iter::FromIterator;
 
     use chrono::NaiveDate;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use serde_querystring::de::{from_str, ParseMode};
 
     use super::DateTimeFilter::*;
-    use crate::filters::DateTimeFilterSet;
+    use crate::{filters::DateTimeFilterSet, to_querystring};
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct Sample {
         birthday: DateTimeFilterSet,
         register_date: DateTimeFilterSet,
@@ -65,7 +159,7 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        const QUERY: &'static str = "birthday[before]=1993-10-15T10:30:5\
+        const QUERY: &str = "birthday[before]=1993-10-15T10:30:5\
                                     &birthday[eq]=1993-2-28T10:30:05\
                                     &register_date[after]=2022-10-15T10:30:05\
                                     &register_date[neq]=2022-10-15T10:30:05";
@@ -110,6 +204,91 @@ mod tests {
             BTreeSet::from_iter(register_date.0.iter())
         );
     }
+
+    #[test]
+    fn serialize() {
+        let mut birthday = DateTimeFilterSet::default();
+        birthday.push(Before(
+            NaiveDate::from_ymd_opt(1993, 10, 15)
+                .unwrap()
+                .and_hms_opt(10, 30, 5)
+                .unwrap(),
+        ));
+        birthday.push(After(
+            NaiveDate::from_ymd_opt(1990, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        ));
+        let mut register_date = DateTimeFilterSet::default();
+        register_date.push(Equals(
+            NaiveDate::from_ymd_opt(2022, 10, 15)
+                .unwrap()
+                .and_hms_opt(10, 30, 5)
+                .unwrap(),
+        ));
+        register_date.push(NotEquals(
+            NaiveDate::from_ymd_opt(2022, 2, 28)
+                .unwrap()
+                .and_hms_milli_opt(23, 59, 59, 500)
+                .unwrap(),
+        ));
+        let sample = Sample {
+            birthday,
+            register_date,
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "birthday[before]=1993-10-15T10%3A30%3A05\
+             &birthday[after]=1990-01-01T00%3A00%3A00\
+             &register_date[eq]=2022-10-15T10%3A30%3A05\
+             &register_date[neq]=2022-02-28T23%3A59%3A59.500"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.birthday.0.iter()),
+            BTreeSet::from_iter(sample.birthday.0.iter())
+        );
+        assert_eq!(
+            BTreeSet::from_iter(res.register_date.0.iter()),
+            BTreeSet::from_iter(sample.register_date.0.iter())
+        );
+    }
+
+    #[test]
+    fn build() {
+        let datetime = NaiveDate::from_ymd_opt(2022, 10, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 5)
+            .unwrap();
+        let set = DateTimeFilterSet::new()
+            .before(datetime)
+            .after(datetime)
+            .eq(datetime)
+            .neq(datetime);
+        assert_eq!(
+            set.as_slice(),
+            &[
+                Before(datetime),
+                After(datetime),
+                Equals(datetime),
+                NotEquals(datetime)
+            ]
+        );
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, Before(_)))
+            .collect::<DateTimeFilterSet>();
+        set.extend([Equals(datetime)]);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&Before(datetime), &Equals(datetime)]
+        );
+    }
 }
 
 #[cfg(feature = "seaq")]
@@ -127,6 +306,9 @@ mod seaq {
                 DateTimeFilter::NotEquals(val) => Expr::col(iden).ne(*val).into_condition(),
                 DateTimeFilter::Before(val) => Expr::col(iden).lt(*val).into_condition(),
                 DateTimeFilter::After(val) => Expr::col(iden).gte(*val).into_condition(),
+                DateTimeFilter::LesserThanEqual(val) => Expr::col(iden).lte(*val).into_condition(),
+                DateTimeFilter::GreaterThan(val) => Expr::col(iden).gt(*val).into_condition(),
+                DateTimeFilter::GreaterThanEqual(val) => Expr::col(iden).gte(*val).into_condition(),
             })
         }
     }
@@ -227,3 +409,56 @@ mod seaq {
         }
     }
 }
+
+mod memory {
+    use chrono::NaiveDateTime;
+
+    use super::{DateTimeFilter, DateTimeFilterSet};
+    use crate::memory::Matches;
+
+    impl Matches<NaiveDateTime> for DateTimeFilter {
+        fn matches(&self, value: &NaiveDateTime) -> bool {
+            match self {
+                DateTimeFilter::Equals(val) => value == val,
+                DateTimeFilter::NotEquals(val) => value != val,
+                DateTimeFilter::Before(val) => value < val,
+                DateTimeFilter::After(val) => value >= val,
+                DateTimeFilter::LesserThanEqual(val) => value <= val,
+                DateTimeFilter::GreaterThan(val) => value > val,
+                DateTimeFilter::GreaterThanEqual(val) => value >= val,
+            }
+        }
+    }
+
+    impl Matches<NaiveDateTime> for DateTimeFilterSet {
+        fn matches(&self, value: &NaiveDateTime) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use chrono::NaiveDate;
+
+        use super::DateTimeFilter::*;
+        use crate::{filters::DateTimeFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            let day = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+            let time = day.and_hms_opt(10, 30, 5).unwrap();
+            let later = day.and_hms_opt(10, 30, 6).unwrap();
+
+            assert!(Equals(time).matches(&time));
+            assert!(NotEquals(time).matches(&later));
+            assert!(Before(later).matches(&time));
+            assert!(!Before(time).matches(&time));
+            assert!(After(time).matches(&time));
+            assert!(!After(later).matches(&time));
+
+            let set = DateTimeFilterSet::new().after(time).before(later);
+            assert!(set.matches(&time));
+            assert!(!set.matches(&later));
+        }
+    }
+}
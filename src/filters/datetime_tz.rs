@@ -1,26 +1,43 @@
 use chrono::{DateTime, FixedOffset};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::EnumMap;
 
 #[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum DateTimeTzFilter {
+    /// Strictly before, `<`
+    #[serde(alias = "lt")]
     Before(DateTime<FixedOffset>),
+    /// On or after, `>=`
     After(DateTime<FixedOffset>),
     #[serde(rename = "eq")]
     Equals(DateTime<FixedOffset>),
     #[serde(rename = "neq")]
     NotEquals(DateTime<FixedOffset>),
+    /// On or before, `<=`
+    #[serde(rename = "lte")]
+    LesserThanEqual(DateTime<FixedOffset>),
+    /// Strictly after, `>`
+    #[serde(rename = "gt")]
+    GreaterThan(DateTime<FixedOffset>),
+    /// On or after, `>=`, like `after`
+    #[serde(rename = "gte")]
+    GreaterThanEqual(DateTime<FixedOffset>),
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 #[serde_with::serde_as]
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct DateTimeTzFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<DateTimeTzFilter>);
 
 impl DateTimeTzFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn push(&mut self, value: DateTimeTzFilter) {
         self.0.push(value);
     }
@@ -28,6 +45,83 @@ impl DateTimeTzFilterSet {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DateTimeTzFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[DateTimeTzFilter] {
+        &self.0
+    }
+
+    pub fn before(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::Before(value));
+        self
+    }
+
+    pub fn after(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::After(value));
+        self
+    }
+
+    pub fn lte(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::LesserThanEqual(value));
+        self
+    }
+
+    pub fn gt(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::GreaterThan(value));
+        self
+    }
+
+    pub fn gte(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::GreaterThanEqual(value));
+        self
+    }
+
+    pub fn eq(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::Equals(value));
+        self
+    }
+
+    pub fn neq(mut self, value: DateTime<FixedOffset>) -> Self {
+        self.push(DateTimeTzFilter::NotEquals(value));
+        self
+    }
+}
+
+impl FromIterator<DateTimeTzFilter> for DateTimeTzFilterSet {
+    fn from_iter<I: IntoIterator<Item = DateTimeTzFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<DateTimeTzFilter> for DateTimeTzFilterSet {
+    fn extend<I: IntoIterator<Item = DateTimeTzFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for DateTimeTzFilterSet {
+    type Item = DateTimeTzFilter;
+    type IntoIter = std::vec::IntoIter<DateTimeTzFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s DateTimeTzFilterSet {
+    type Item = &'s DateTimeTzFilter;
+    type IntoIter = std::slice::Iter<'s, DateTimeTzFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(feature = "openapi")]
@@ -51,13 +145,13 @@ mod tests {
     use std::iter::FromIterator;
 
     use chrono::{DateTime, FixedOffset, NaiveDate};
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use serde_querystring::de::{from_str, ParseMode};
 
     use super::DateTimeTzFilter::*;
-    use crate::filters::DateTimeTzFilterSet;
+    use crate::{filters::DateTimeTzFilterSet, to_querystring};
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct Sample {
         birthday: DateTimeTzFilterSet,
         register_date: DateTimeTzFilterSet,
@@ -65,7 +159,7 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        const QUERY: &'static str = "birthday[before]=1993-10-15T10:30:5%2b00:00\
+        const QUERY: &str = "birthday[before]=1993-10-15T10:30:5%2b00:00\
                                     &birthday[eq]=1993-2-28T10:30:05%2b00:00\
                                     &register_date[after]=2022-10-15T10:30:05%2b00:00\
                                     &register_date[neq]=2022-10-15T10:30:05%2b00:00";
@@ -73,14 +167,14 @@ mod tests {
         let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
 
         let mut birthday = DateTimeTzFilterSet::default();
-        birthday.push(Before(DateTime::from_local(
+        birthday.push(Before(DateTime::from_naive_utc_and_offset(
             NaiveDate::from_ymd_opt(1993, 10, 15)
                 .unwrap()
                 .and_hms_opt(10, 30, 5)
                 .unwrap(),
             FixedOffset::east_opt(0).unwrap(),
         )));
-        birthday.push(Equals(DateTime::from_local(
+        birthday.push(Equals(DateTime::from_naive_utc_and_offset(
             NaiveDate::from_ymd_opt(1993, 2, 28)
                 .unwrap()
                 .and_hms_opt(10, 30, 5)
@@ -94,14 +188,14 @@ mod tests {
         );
 
         let mut register_date = DateTimeTzFilterSet::default();
-        register_date.push(After(DateTime::from_local(
+        register_date.push(After(DateTime::from_naive_utc_and_offset(
             NaiveDate::from_ymd_opt(2022, 10, 15)
                 .unwrap()
                 .and_hms_opt(10, 30, 5)
                 .unwrap(),
             FixedOffset::east_opt(0).unwrap(),
         )));
-        register_date.push(NotEquals(DateTime::from_local(
+        register_date.push(NotEquals(DateTime::from_naive_utc_and_offset(
             NaiveDate::from_ymd_opt(2022, 10, 15)
                 .unwrap()
                 .and_hms_opt(10, 30, 5)
@@ -114,6 +208,101 @@ mod tests {
             BTreeSet::from_iter(register_date.0.iter())
         );
     }
+
+    #[test]
+    fn serialize() {
+        let mut birthday = DateTimeTzFilterSet::default();
+        birthday.push(Before(
+            NaiveDate::from_ymd_opt(1993, 10, 15)
+                .unwrap()
+                .and_hms_opt(10, 30, 5)
+                .unwrap()
+                .and_local_timezone(FixedOffset::east_opt(3 * 3600 + 1800).unwrap())
+                .unwrap(),
+        ));
+        birthday.push(After(
+            NaiveDate::from_ymd_opt(1990, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_local_timezone(FixedOffset::west_opt(5 * 3600).unwrap())
+                .unwrap(),
+        ));
+        let mut register_date = DateTimeTzFilterSet::default();
+        register_date.push(Equals(
+            NaiveDate::from_ymd_opt(2022, 10, 15)
+                .unwrap()
+                .and_hms_opt(10, 30, 5)
+                .unwrap()
+                .and_local_timezone(FixedOffset::east_opt(0).unwrap())
+                .unwrap(),
+        ));
+        register_date.push(NotEquals(
+            NaiveDate::from_ymd_opt(2022, 2, 28)
+                .unwrap()
+                .and_hms_opt(23, 59, 59)
+                .unwrap()
+                .and_local_timezone(FixedOffset::east_opt(0).unwrap())
+                .unwrap(),
+        ));
+        let sample = Sample {
+            birthday,
+            register_date,
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "birthday[before]=1993-10-15T10%3A30%3A05%2B03%3A30\
+             &birthday[after]=1990-01-01T00%3A00%3A00-05%3A00\
+             &register_date[eq]=2022-10-15T10%3A30%3A05Z\
+             &register_date[neq]=2022-02-28T23%3A59%3A59Z"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.birthday.0.iter()),
+            BTreeSet::from_iter(sample.birthday.0.iter())
+        );
+        assert_eq!(
+            BTreeSet::from_iter(res.register_date.0.iter()),
+            BTreeSet::from_iter(sample.register_date.0.iter())
+        );
+    }
+
+    #[test]
+    fn build() {
+        let datetime = NaiveDate::from_ymd_opt(2022, 10, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 5)
+            .unwrap()
+            .and_local_timezone(FixedOffset::east_opt(0).unwrap())
+            .unwrap();
+        let set = DateTimeTzFilterSet::new()
+            .before(datetime)
+            .after(datetime)
+            .eq(datetime)
+            .neq(datetime);
+        assert_eq!(
+            set.as_slice(),
+            &[
+                Before(datetime),
+                After(datetime),
+                Equals(datetime),
+                NotEquals(datetime)
+            ]
+        );
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, Before(_)))
+            .collect::<DateTimeTzFilterSet>();
+        set.extend([Equals(datetime)]);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&Before(datetime), &Equals(datetime)]
+        );
+    }
 }
 
 #[cfg(feature = "seaq")]
@@ -131,6 +320,13 @@ mod seaq {
                 DateTimeTzFilter::NotEquals(val) => Expr::col(iden).ne(*val).into_condition(),
                 DateTimeTzFilter::Before(val) => Expr::col(iden).lt(*val).into_condition(),
                 DateTimeTzFilter::After(val) => Expr::col(iden).gte(*val).into_condition(),
+                DateTimeTzFilter::LesserThanEqual(val) => {
+                    Expr::col(iden).lte(*val).into_condition()
+                }
+                DateTimeTzFilter::GreaterThan(val) => Expr::col(iden).gt(*val).into_condition(),
+                DateTimeTzFilter::GreaterThanEqual(val) => {
+                    Expr::col(iden).gte(*val).into_condition()
+                }
             })
         }
     }
@@ -158,7 +354,7 @@ mod seaq {
         #[test]
         fn test_before() {
             check_query(
-                Before(DateTime::from_local(
+                Before(DateTime::from_naive_utc_and_offset(
                     NaiveDate::from_ymd_opt(2022, 10, 15)
                         .unwrap()
                         .and_hms_opt(10, 30, 5)
@@ -172,7 +368,7 @@ mod seaq {
         #[test]
         fn test_after() {
             check_query(
-                After(DateTime::from_local(
+                After(DateTime::from_naive_utc_and_offset(
                     NaiveDate::from_ymd_opt(2022, 10, 15)
                         .unwrap()
                         .and_hms_opt(10, 30, 5)
@@ -186,7 +382,7 @@ mod seaq {
         #[test]
         fn test_eq() {
             check_query(
-                Equals(DateTime::from_local(
+                Equals(DateTime::from_naive_utc_and_offset(
                     NaiveDate::from_ymd_opt(2022, 10, 15)
                         .unwrap()
                         .and_hms_opt(10, 30, 5)
@@ -200,7 +396,7 @@ mod seaq {
         #[test]
         fn test_neq() {
             check_query(
-                NotEquals(DateTime::from_local(
+                NotEquals(DateTime::from_naive_utc_and_offset(
                     NaiveDate::from_ymd_opt(2022, 10, 15)
                         .unwrap()
                         .and_hms_opt(10, 30, 5)
@@ -214,14 +410,14 @@ mod seaq {
         #[test]
         fn test_set() {
             let mut set = DateTimeTzFilterSet::default();
-            set.push(After(DateTime::from_local(
+            set.push(After(DateTime::from_naive_utc_and_offset(
                 NaiveDate::from_ymd_opt(2022, 10, 15)
                     .unwrap()
                     .and_hms_opt(10, 30, 5)
                     .unwrap(),
                 FixedOffset::east_opt(0).unwrap(),
             )));
-            set.push(Before(DateTime::from_local(
+            set.push(Before(DateTime::from_naive_utc_and_offset(
                 NaiveDate::from_ymd_opt(2022, 10, 15)
                     .unwrap()
                     .and_hms_opt(10, 30, 5)
@@ -237,3 +433,63 @@ mod seaq {
         }
     }
 }
+
+mod memory {
+    use chrono::{DateTime, TimeZone};
+
+    use super::{DateTimeTzFilter, DateTimeTzFilterSet};
+    use crate::memory::Matches;
+
+    impl<Tz: TimeZone> Matches<DateTime<Tz>> for DateTimeTzFilter {
+        fn matches(&self, value: &DateTime<Tz>) -> bool {
+            match self {
+                DateTimeTzFilter::Equals(val) => value == val,
+                DateTimeTzFilter::NotEquals(val) => value != val,
+                DateTimeTzFilter::Before(val) => value < val,
+                DateTimeTzFilter::After(val) => value >= val,
+                DateTimeTzFilter::LesserThanEqual(val) => value <= val,
+                DateTimeTzFilter::GreaterThan(val) => value > val,
+                DateTimeTzFilter::GreaterThanEqual(val) => value >= val,
+            }
+        }
+    }
+
+    impl<Tz: TimeZone> Matches<DateTime<Tz>> for DateTimeTzFilterSet {
+        fn matches(&self, value: &DateTime<Tz>) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use chrono::{FixedOffset, NaiveDate, Utc};
+
+        use super::DateTimeTzFilter::*;
+        use crate::{filters::DateTimeTzFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            let naive = NaiveDate::from_ymd_opt(2022, 10, 15)
+                .unwrap()
+                .and_hms_opt(10, 30, 5)
+                .unwrap();
+            let time = naive
+                .and_local_timezone(FixedOffset::east_opt(3600).unwrap())
+                .unwrap();
+            // The same instant in another timezone
+            let utc = naive.and_local_timezone(Utc).unwrap() - chrono::Duration::hours(1);
+            let later = utc + chrono::Duration::seconds(1);
+
+            assert!(Equals(time).matches(&utc));
+            assert!(NotEquals(time).matches(&later));
+            assert!(Before(later.into()).matches(&time));
+            assert!(!Before(time).matches(&utc));
+            assert!(After(time).matches(&utc));
+            assert!(!After(later.into()).matches(&time));
+
+            let set = DateTimeTzFilterSet::new().after(time).before(later.into());
+            assert!(set.matches(&utc));
+            assert!(!set.matches(&later));
+        }
+    }
+}
@@ -0,0 +1,602 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, EnumMap, SerializeDisplay};
+
+/// A WGS 84 coordinate, SRID 4326
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    lat: f64,
+    lng: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lng: f64) -> Result<Self, GeoError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(GeoError::Latitude(lat));
+        }
+        if !(-180.0..=180.0).contains(&lng) {
+            return Err(GeoError::Longitude(lng));
+        }
+        Ok(Self { lat, lng })
+    }
+
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    pub fn lng(&self) -> f64 {
+        self.lng
+    }
+}
+
+/// `lat,lng,radius`, the radius being in meters
+#[derive(Debug, Clone, Copy, PartialEq, SerializeDisplay, DeserializeFromStr)]
+pub struct Near {
+    point: GeoPoint,
+    radius: f64,
+}
+
+impl Near {
+    pub fn new(point: GeoPoint, radius: f64) -> Result<Self, GeoError> {
+        if !radius.is_finite() || radius < 0.0 {
+            return Err(GeoError::Radius(radius));
+        }
+        Ok(Self { point, radius })
+    }
+
+    pub fn point(&self) -> GeoPoint {
+        self.point
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+/// `minx,miny,maxx,maxy`, that is the south-west and north-east corners as `lng,lat`
+#[derive(Debug, Clone, Copy, PartialEq, SerializeDisplay, DeserializeFromStr)]
+pub struct BBox {
+    min: GeoPoint,
+    max: GeoPoint,
+}
+
+impl BBox {
+    pub fn new(min: GeoPoint, max: GeoPoint) -> Result<Self, GeoError> {
+        if min.lat > max.lat || min.lng > max.lng {
+            return Err(GeoError::Corners);
+        }
+        Ok(Self { min, max })
+    }
+
+    pub fn min(&self) -> GeoPoint {
+        self.min
+    }
+
+    pub fn max(&self) -> GeoPoint {
+        self.max
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoError {
+    /// Not the expected number of comma separated numbers
+    Format(&'static str),
+    Latitude(f64),
+    Longitude(f64),
+    Radius(f64),
+    /// The minimum corner of a bounding box is above or right of the maximum one
+    Corners,
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format(format) => write!(f, "expected `{}`", format),
+            Self::Latitude(lat) => write!(f, "latitude {} is out of -90..90", lat),
+            Self::Longitude(lng) => write!(f, "longitude {} is out of -180..180", lng),
+            Self::Radius(radius) => write!(f, "radius {} is not a positive number", radius),
+            Self::Corners => f.write_str("the minimum corner is past the maximum one"),
+        }
+    }
+}
+
+impl std::error::Error for GeoError {}
+
+fn numbers<const N: usize>(value: &str, format: &'static str) -> Result<[f64; N], GeoError> {
+    let mut numbers = [0.0; N];
+    let mut parts = value.split(',');
+    for number in numbers.iter_mut() {
+        *number = parts
+            .next()
+            .and_then(|part| part.trim().parse().ok())
+            .filter(|number: &f64| number.is_finite())
+            .ok_or(GeoError::Format(format))?;
+    }
+    match parts.next() {
+        Some(_) => Err(GeoError::Format(format)),
+        None => Ok(numbers),
+    }
+}
+
+impl FromStr for Near {
+    type Err = GeoError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let [lat, lng, radius] = numbers(value, "lat,lng,radius")?;
+        Self::new(GeoPoint::new(lat, lng)?, radius)
+    }
+}
+
+impl fmt::Display for Near {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.point.lat, self.point.lng, self.radius)
+    }
+}
+
+impl FromStr for BBox {
+    type Err = GeoError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let [min_lng, min_lat, max_lng, max_lat] = numbers(value, "minx,miny,maxx,maxy")?;
+        Self::new(
+            GeoPoint::new(min_lat, min_lng)?,
+            GeoPoint::new(max_lat, max_lng)?,
+        )
+    }
+}
+
+impl fmt::Display for BBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            self.min.lng, self.min.lat, self.max.lng, self.max.lat
+        )
+    }
+}
+
+/// Filters on a PostGIS point column
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub enum GeoFilter {
+    /// Within `radius` meters of the point, `ST_DWithin`
+    #[serde(rename = "near")]
+    Near(#[cfg_attr(feature = "openapi", schema(value_type = String))] Near),
+    /// Inside the box, `ST_MakeEnvelope`
+    #[serde(rename = "bbox")]
+    BBox(#[cfg_attr(feature = "openapi", schema(value_type = String))] BBox),
+}
+
+#[cfg_attr(test, derive(PartialEq))]
+#[serde_with::serde_as]
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct GeoFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<GeoFilter>);
+
+impl GeoFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: GeoFilter) {
+        self.0.push(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, GeoFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[GeoFilter] {
+        &self.0
+    }
+
+    pub fn near(mut self, near: Near) -> Self {
+        self.push(GeoFilter::Near(near));
+        self
+    }
+
+    pub fn bbox(mut self, bbox: BBox) -> Self {
+        self.push(GeoFilter::BBox(bbox));
+        self
+    }
+
+    /// The point of the first `near` filter, to sort the results by their distance to
+    pub fn origin(&self) -> Option<GeoPoint> {
+        self.0.iter().find_map(|filter| match filter {
+            GeoFilter::Near(near) => Some(near.point),
+            _ => None,
+        })
+    }
+}
+
+impl FromIterator<GeoFilter> for GeoFilterSet {
+    fn from_iter<I: IntoIterator<Item = GeoFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<GeoFilter> for GeoFilterSet {
+    fn extend<I: IntoIterator<Item = GeoFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for GeoFilterSet {
+    type Item = GeoFilter;
+    type IntoIter = std::vec::IntoIter<GeoFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s GeoFilterSet {
+    type Item = &'s GeoFilter;
+    type IntoIter = std::slice::Iter<'s, GeoFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl<'__s> utoipa::ToSchema<'__s> for GeoFilterSet {
+    fn schema() -> (
+        &'__s str,
+        utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
+    ) {
+        (
+            "GeoFilterSet",
+            utoipa::openapi::schema::ArrayBuilder::new()
+                .items(GeoFilter::schema().1)
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::{BBox, GeoError, GeoFilter::*, GeoFilterSet, GeoPoint, Near};
+    use crate::to_querystring;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Sample {
+        location: GeoFilterSet,
+    }
+
+    #[test]
+    fn deserialize() {
+        const QUERY: &str = "location[near]=52.52,13.405,500\
+                             &location[bbox]=13.0,52.0,14.0,53.0";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let near = Near::new(GeoPoint::new(52.52, 13.405).unwrap(), 500.0).unwrap();
+        let bbox = BBox::new(
+            GeoPoint::new(52.0, 13.0).unwrap(),
+            GeoPoint::new(53.0, 14.0).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(res.location.len(), 2);
+        assert!(res.location.iter().any(|f| f == &Near(near)));
+        assert!(res.location.iter().any(|f| f == &BBox(bbox)));
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert_eq!(
+            "1,2".parse::<Near>(),
+            Err(GeoError::Format("lat,lng,radius"))
+        );
+        assert_eq!(
+            "1,2,3,4".parse::<Near>(),
+            Err(GeoError::Format("lat,lng,radius"))
+        );
+        assert_eq!(
+            "1,x,3".parse::<Near>(),
+            Err(GeoError::Format("lat,lng,radius"))
+        );
+        assert_eq!("91,0,3".parse::<Near>(), Err(GeoError::Latitude(91.0)));
+        assert_eq!("0,-181,3".parse::<Near>(), Err(GeoError::Longitude(-181.0)));
+        assert_eq!("0,0,-1".parse::<Near>(), Err(GeoError::Radius(-1.0)));
+        assert_eq!(
+            "0,0,inf".parse::<Near>(),
+            Err(GeoError::Format("lat,lng,radius"))
+        );
+        assert_eq!("1,1,0,0".parse::<BBox>(), Err(GeoError::Corners));
+
+        let err = from_str::<Sample>("location[near]=100,0,1", ParseMode::Brackets).unwrap_err();
+        assert!(err.to_string().contains("latitude 100 is out of -90..90"));
+    }
+
+    #[test]
+    fn serialize() {
+        let sample = Sample {
+            location: GeoFilterSet::new()
+                .near("52.52,13.405,500".parse().unwrap())
+                .bbox("-1.5,-2,1.5,2".parse().unwrap()),
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "location[near]=52.52%2C13.405%2C500&location[bbox]=-1.5%2C-2%2C1.5%2C2"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(res.location.len(), 2);
+        assert!(sample
+            .location
+            .iter()
+            .all(|f| res.location.iter().any(|r| r == f)));
+    }
+}
+
+mod seaq {
+    use sea_query::{Alias, Cond, Expr, Func, IntoColumnRef, IntoCondition, SimpleExpr};
+
+    use super::{GeoFilter, GeoFilterSet, GeoPoint};
+    use crate::seaq::ToFieldCond;
+
+    fn call<I>(func: &str, args: I) -> SimpleExpr
+    where
+        I: IntoIterator<Item = SimpleExpr>,
+    {
+        Func::cust(Alias::new(func)).args(args).into()
+    }
+
+    fn geography(expr: SimpleExpr) -> SimpleExpr {
+        Expr::expr(expr).cast_as(Alias::new("geography"))
+    }
+
+    impl GeoPoint {
+        /// `ST_SetSRID(ST_MakePoint(lng, lat), 4326)`
+        pub fn to_expr(&self) -> SimpleExpr {
+            call(
+                "ST_SetSRID",
+                [
+                    call("ST_MakePoint", [self.lng.into(), self.lat.into()]),
+                    4326.into(),
+                ],
+            )
+        }
+
+        /// The distance in meters between the column and the point, for sorting by
+        ///
+        /// Return it from [`ToCond::sort_expr`](crate::ToCond::sort_expr) to make a sort field
+        /// like `distance` available to `ApplyFilters`.
+        pub fn distance<I: IntoColumnRef>(&self, iden: I) -> SimpleExpr {
+            call(
+                "ST_Distance",
+                [geography(Expr::col(iden).into()), geography(self.to_expr())],
+            )
+        }
+    }
+
+    /// The column is cast to `geography` to measure in meters and to `geometry` to compare with a
+    /// box, so a column of either type works
+    impl ToFieldCond for GeoFilter {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            let col: SimpleExpr = Expr::col(iden).into();
+            Some(match self {
+                GeoFilter::Near(near) => call(
+                    "ST_DWithin",
+                    [
+                        geography(col),
+                        geography(near.point.to_expr()),
+                        near.radius.into(),
+                    ],
+                )
+                .into_condition(),
+                GeoFilter::BBox(bbox) => Expr::cust_with_exprs(
+                    "$1 && $2",
+                    [
+                        Expr::expr(col).cast_as(Alias::new("geometry")),
+                        call(
+                            "ST_MakeEnvelope",
+                            [
+                                bbox.min.lng.into(),
+                                bbox.min.lat.into(),
+                                bbox.max.lng.into(),
+                                bbox.max.lat.into(),
+                                4326.into(),
+                            ],
+                        ),
+                    ],
+                )
+                .into_condition(),
+            })
+        }
+    }
+
+    impl ToFieldCond for GeoFilterSet {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            let mut conds = Cond::all();
+            let col_ref = iden.into_column_ref();
+            for filter in self.0.iter() {
+                if let Some(filter) = filter.to_cond(col_ref.clone()) {
+                    conds = conds.add(filter);
+                }
+            }
+            Some(conds)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use sea_query::{tests_cfg::*, Cond, PostgresQueryBuilder, Query, SimpleExpr};
+        use serde_querystring::de::ParseMode;
+
+        use super::GeoFilter::*;
+        use crate::{
+            filters::GeoFilterSet, test_utils::check_query, ApplyFilters, Filter, QueryFilter,
+            ToCond, ToFieldCond,
+        };
+
+        #[test]
+        fn test_near() {
+            check_query(
+                Near("52.52,13.405,500".parse().unwrap()),
+                r#"SELECT "image" FROM "glyph" WHERE ST_DWithin(CAST("aspect" AS geography), CAST(ST_SetSRID(ST_MakePoint(13.405, 52.52), 4326) AS geography), 500)"#,
+            );
+        }
+
+        #[test]
+        fn test_bbox() {
+            check_query(
+                BBox("13,52,14.5,53".parse().unwrap()),
+                r#"SELECT "image" FROM "glyph" WHERE CAST("aspect" AS geometry) && ST_MakeEnvelope(13, 52, 14.5, 53, 4326)"#,
+            );
+        }
+
+        #[test]
+        fn test_sort_by_distance() {
+            #[derive(serde::Deserialize)]
+            struct Filters {
+                location: Option<GeoFilterSet>,
+            }
+
+            impl ToCond for Filters {
+                const EXPR_SORT_FIELDS: &'static [&'static str] = &["distance"];
+
+                fn to_cond(&self) -> Cond {
+                    let mut cond = Cond::all();
+                    if let Some(location) = self.location.to_cond(Glyph::Aspect) {
+                        cond = cond.add(location)
+                    }
+                    cond
+                }
+
+                fn sort_expr(&self, field: &str) -> Option<SimpleExpr> {
+                    match field {
+                        "distance" => {
+                            Some(self.location.as_ref()?.origin()?.distance(Glyph::Aspect))
+                        }
+                        _ => None,
+                    }
+                }
+            }
+
+            impl Filter for Filters {
+                const SORTABLE_FIELDS: &'static [&'static str] = &["distance"];
+            }
+
+            let filters = serde_querystring::from_str::<QueryFilter<Filters>>(
+                "filter[location][near]=1.5,2,100&sort=distance",
+                ParseMode::Brackets,
+            )
+            .unwrap();
+
+            let query = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .apply_filters(&filters)
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(
+                query,
+                "SELECT \"image\" FROM \"glyph\" WHERE \
+                 ST_DWithin(CAST(\"aspect\" AS geography), CAST(ST_SetSRID(ST_MakePoint(2, 1.5), 4326) AS geography), 100) \
+                 ORDER BY ST_Distance(CAST(\"aspect\" AS geography), CAST(ST_SetSRID(ST_MakePoint(2, 1.5), 4326) AS geography)) ASC \
+                 LIMIT 10 OFFSET 0"
+            );
+
+            // Without `near` there's no point to measure from, nor a "distance" column
+            let filters = serde_querystring::from_str::<QueryFilter<Filters>>(
+                "filter[location][bbox]=13,52,14.5,53&sort=distance",
+                ParseMode::Brackets,
+            )
+            .unwrap();
+
+            let query = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .apply_filters(&filters)
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(
+                query,
+                "SELECT \"image\" FROM \"glyph\" WHERE \
+                 CAST(\"aspect\" AS geometry) && ST_MakeEnvelope(13, 52, 14.5, 53, 4326) \
+                 LIMIT 10 OFFSET 0"
+            );
+        }
+    }
+}
+
+mod memory {
+    use super::{GeoFilter, GeoFilterSet, GeoPoint};
+    use crate::memory::Matches;
+
+    impl GeoPoint {
+        /// The great-circle distance in meters, PostGIS measures on the spheroid so expect its
+        /// results to differ by up to half a percent
+        pub fn haversine(&self, other: &GeoPoint) -> f64 {
+            const EARTH_RADIUS: f64 = 6_371_008.8;
+
+            let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+            let dlat = lat2 - lat1;
+            let dlng = (other.lng - self.lng).to_radians();
+
+            let a =
+                (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+            2.0 * EARTH_RADIUS * a.sqrt().asin()
+        }
+    }
+
+    impl Matches<GeoPoint> for GeoFilter {
+        fn matches(&self, value: &GeoPoint) -> bool {
+            match self {
+                GeoFilter::Near(near) => near.point.haversine(value) <= near.radius,
+                GeoFilter::BBox(bbox) => {
+                    (bbox.min.lat..=bbox.max.lat).contains(&value.lat)
+                        && (bbox.min.lng..=bbox.max.lng).contains(&value.lng)
+                }
+            }
+        }
+    }
+
+    impl Matches<GeoPoint> for GeoFilterSet {
+        fn matches(&self, value: &GeoPoint) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::GeoFilter::*;
+        use crate::{
+            filters::{GeoFilterSet, GeoPoint},
+            memory::Matches,
+        };
+
+        #[test]
+        fn test_matches() {
+            let berlin = GeoPoint::new(52.52, 13.405).unwrap();
+            let potsdam = GeoPoint::new(52.3906, 13.0645).unwrap();
+
+            // About 27km apart
+            assert!(Near("52.52,13.405,28000".parse().unwrap()).matches(&potsdam));
+            assert!(!Near("52.52,13.405,26000".parse().unwrap()).matches(&potsdam));
+            assert!(BBox("13,52,14,53".parse().unwrap()).matches(&berlin));
+            assert!(!BBox("13.1,52,14,53".parse().unwrap()).matches(&potsdam));
+
+            let set = GeoFilterSet::new().bbox("13,52,14,53".parse().unwrap());
+            assert!(set.matches(&potsdam));
+            assert!(GeoFilterSet::new().matches(&berlin));
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use std::net::IpAddr;
+
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+use serde_with::EnumMap;
+
+/// Filters on an `inet` column
+#[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
+#[derive(Debug, Serialize, Deserialize)]
+pub enum IpFilter {
+    #[serde(rename = "eq")]
+    Equals(IpAddr),
+    #[serde(rename = "in")]
+    In(Vec<IpAddr>),
+    /// The address is in the subnet, `<<=`
+    #[serde(rename = "within")]
+    Within(IpNetwork),
+}
+
+#[cfg_attr(test, derive(PartialEq))]
+#[serde_with::serde_as]
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct IpFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<IpFilter>);
+
+impl IpFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: IpFilter) {
+        self.0.push(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, IpFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[IpFilter] {
+        &self.0
+    }
+
+    pub fn eq(mut self, value: IpAddr) -> Self {
+        self.push(IpFilter::Equals(value));
+        self
+    }
+
+    pub fn is_in(mut self, values: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.push(IpFilter::In(values.into_iter().collect()));
+        self
+    }
+
+    pub fn within(mut self, network: IpNetwork) -> Self {
+        self.push(IpFilter::Within(network));
+        self
+    }
+}
+
+impl FromIterator<IpFilter> for IpFilterSet {
+    fn from_iter<I: IntoIterator<Item = IpFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<IpFilter> for IpFilterSet {
+    fn extend<I: IntoIterator<Item = IpFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for IpFilterSet {
+    type Item = IpFilter;
+    type IntoIter = std::vec::IntoIter<IpFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s IpFilterSet {
+    type Item = &'s IpFilter;
+    type IntoIter = std::slice::Iter<'s, IpFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(feature = "openapi")]
+mod openapi {
+    use utoipa::openapi::{
+        schema::Schema, ArrayBuilder, ObjectBuilder, OneOfBuilder, RefOr, SchemaFormat, SchemaType,
+    };
+    use utoipa::ToSchema;
+
+    use super::{IpFilter, IpFilterSet};
+
+    fn string(format: &str) -> ObjectBuilder {
+        ObjectBuilder::new()
+            .schema_type(SchemaType::String)
+            .format(Some(SchemaFormat::Custom(format.into())))
+    }
+
+    fn ip() -> OneOfBuilder {
+        OneOfBuilder::new()
+            .item(string("ipv4"))
+            .item(string("ipv6"))
+    }
+
+    impl<'__s> ToSchema<'__s> for IpFilter {
+        fn schema() -> (&'__s str, RefOr<Schema>) {
+            (
+                "IpFilter",
+                ObjectBuilder::new()
+                    .property("eq", ip())
+                    .property("in", ArrayBuilder::new().items(ip()))
+                    .property(
+                        "within",
+                        ObjectBuilder::new()
+                            .schema_type(SchemaType::String)
+                            .description(Some("A subnet in CIDR notation, like 10.0.0.0/8")),
+                    )
+                    .into(),
+            )
+        }
+    }
+
+    impl<'__s> ToSchema<'__s> for IpFilterSet {
+        fn schema() -> (&'__s str, RefOr<Schema>) {
+            (
+                "IpFilterSet",
+                ArrayBuilder::new().items(IpFilter::schema().1).into(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::iter::FromIterator;
+
+    use serde::{Deserialize, Serialize};
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::IpFilterSet;
+    use crate::to_querystring;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Sample {
+        ip: IpFilterSet,
+    }
+
+    #[test]
+    fn deserialize() {
+        const QUERY: &str = "ip[eq]=10.0.0.1\
+                             &ip[in]=192.168.1.1\
+                             &ip[in]=::1\
+                             &ip[within]=10.0.0.0/8";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let ip = IpFilterSet::new()
+            .eq("10.0.0.1".parse().unwrap())
+            .is_in(["192.168.1.1".parse().unwrap(), "::1".parse().unwrap()])
+            .within("10.0.0.0/8".parse().unwrap());
+
+        assert_eq!(
+            BTreeSet::from_iter(res.ip.0.iter()),
+            BTreeSet::from_iter(ip.0.iter())
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(from_str::<Sample>("ip[eq]=10.0.0.256", ParseMode::Brackets).is_err());
+        assert!(from_str::<Sample>("ip[within]=10.0.0.0/33", ParseMode::Brackets).is_err());
+    }
+
+    #[test]
+    fn serialize() {
+        let sample = Sample {
+            ip: IpFilterSet::new()
+                .eq("10.0.0.1".parse().unwrap())
+                .is_in(["::1".parse().unwrap()])
+                .within("fd00::/8".parse().unwrap()),
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "ip[eq]=10.0.0.1&ip[in]=%3A%3A1&ip[within]=fd00%3A%3A%2F8"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.ip.0.iter()),
+            BTreeSet::from_iter(sample.ip.0.iter())
+        );
+    }
+}
+
+mod seaq {
+    use ipnetwork::IpNetwork;
+    use sea_query::{Cond, Expr, IntoColumnRef, IntoCondition};
+
+    use super::{IpFilter, IpFilterSet};
+    use crate::seaq::ToFieldCond;
+
+    impl ToFieldCond for IpFilter {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            Some(match self {
+                IpFilter::Equals(val) => Expr::col(iden).eq(IpNetwork::from(*val)).into_condition(),
+                IpFilter::In(val) => Expr::col(iden)
+                    .is_in(val.iter().map(|ip| IpNetwork::from(*ip)))
+                    .into_condition(),
+                IpFilter::Within(val) => Expr::cust_with_exprs(
+                    "$1 <<= $2",
+                    [Expr::col(iden).into(), Expr::val(*val).into()],
+                )
+                .into_condition(),
+            })
+        }
+    }
+
+    impl ToFieldCond for IpFilterSet {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            let mut conds = Cond::all();
+            let col_ref = iden.into_column_ref();
+            for filter in self.0.iter() {
+                if let Some(filter) = filter.to_cond(col_ref.clone()) {
+                    conds = conds.add(filter);
+                }
+            }
+            Some(conds)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::IpFilter::*;
+        use crate::{filters::IpFilterSet, test_utils::check_query};
+
+        #[test]
+        fn test_eq() {
+            check_query(
+                Equals("10.0.0.1".parse().unwrap()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" = '10.0.0.1/32'"#,
+            );
+        }
+
+        #[test]
+        fn test_in() {
+            check_query(
+                In(vec!["10.0.0.1".parse().unwrap(), "::1".parse().unwrap()]),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" IN ('10.0.0.1/32', '::1/128')"#,
+            );
+        }
+
+        #[test]
+        fn test_within() {
+            check_query(
+                Within("192.168.0.0/16".parse().unwrap()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" <<= '192.168.0.0/16'"#,
+            );
+        }
+
+        #[test]
+        fn test_set() {
+            let set = IpFilterSet::new()
+                .within("10.0.0.0/8".parse().unwrap())
+                .eq("10.1.2.3".parse().unwrap());
+
+            check_query(
+                set,
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" <<= '10.0.0.0/8' AND "aspect" = '10.1.2.3/32'"#,
+            );
+        }
+    }
+}
+
+mod memory {
+    use std::net::IpAddr;
+
+    use super::{IpFilter, IpFilterSet};
+    use crate::memory::Matches;
+
+    impl Matches<IpAddr> for IpFilter {
+        fn matches(&self, value: &IpAddr) -> bool {
+            match self {
+                IpFilter::Equals(val) => value == val,
+                IpFilter::In(val) => val.contains(value),
+                IpFilter::Within(val) => val.contains(*value),
+            }
+        }
+    }
+
+    impl Matches<IpAddr> for IpFilterSet {
+        fn matches(&self, value: &IpAddr) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::IpFilter::*;
+        use crate::{filters::IpFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            let ip = "10.1.2.3".parse().unwrap();
+
+            assert!(Equals("10.1.2.3".parse().unwrap()).matches(&ip));
+            assert!(!Equals("10.1.2.4".parse().unwrap()).matches(&ip));
+            assert!(In(vec!["::1".parse().unwrap(), ip]).matches(&ip));
+            assert!(!In(vec![]).matches(&ip));
+            assert!(Within("10.0.0.0/8".parse().unwrap()).matches(&ip));
+            assert!(!Within("10.1.3.0/24".parse().unwrap()).matches(&ip));
+            assert!(!Within("::/0".parse().unwrap()).matches(&ip));
+
+            let set = IpFilterSet::new().within("10.0.0.0/8".parse().unwrap());
+            assert!(set.matches(&ip));
+            assert!(!set.matches(&"11.0.0.1".parse().unwrap()));
+            assert!(IpFilterSet::new().matches(&ip));
+        }
+    }
+}
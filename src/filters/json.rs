@@ -0,0 +1,699 @@
+use std::{borrow::Cow, collections::BTreeMap, fmt, marker::PhantomData};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use serde_with::{json::JsonString, As, EnumMap};
+
+/// Filters on a `jsonb` column, paths are dot separated keys like `address.city`
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde_with::serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub enum JsonFilter<'a> {
+    /// The values at the paths, as text, are equal to the given ones
+    #[serde(rename = "path")]
+    #[serde(borrow)]
+    Path(BTreeMap<Cow<'a, str>, Cow<'a, str>>),
+    /// The key exists, `?`
+    #[serde(rename = "has")]
+    HasKey(Cow<'a, str>),
+    /// The document contains the given JSON, `@>`
+    #[serde(rename = "contains")]
+    Contains(
+        #[serde_as(as = "JsonString")]
+        #[cfg_attr(feature = "openapi", schema(value_type = String))]
+        Value,
+    ),
+    /// The values at the paths, as numbers, are lesser than the given ones
+    #[serde(rename = "lt")]
+    LesserThan(BTreeMap<Cow<'a, str>, f64>),
+    #[serde(rename = "lte")]
+    LesserThanEqual(BTreeMap<Cow<'a, str>, f64>),
+    #[serde(rename = "gt")]
+    GreaterThan(BTreeMap<Cow<'a, str>, f64>),
+    #[serde(rename = "gte")]
+    GreaterThanEqual(BTreeMap<Cow<'a, str>, f64>),
+}
+
+impl<'a> JsonFilter<'a> {
+    /// The first path not covered by `allowed`, see [`JsonPaths`]
+    pub(crate) fn disallowed_path(&self, allowed: &[&str]) -> Option<String> {
+        let is_allowed = |path: &str| {
+            allowed.iter().any(|allowed| {
+                *allowed == path
+                    || (allowed.starts_with(path) && allowed[path.len()..].starts_with('.'))
+            })
+        };
+
+        match self {
+            JsonFilter::Path(paths) => paths.keys().find(|path| !allowed.contains(&&***path)),
+            JsonFilter::LesserThan(paths)
+            | JsonFilter::LesserThanEqual(paths)
+            | JsonFilter::GreaterThan(paths)
+            | JsonFilter::GreaterThanEqual(paths) => {
+                paths.keys().find(|path| !allowed.contains(&&***path))
+            }
+            JsonFilter::HasKey(path) => Some(path).filter(|path| !is_allowed(path)),
+            JsonFilter::Contains(value) => {
+                return value_paths(value, "")
+                    .into_iter()
+                    .find(|path| !is_allowed(path))
+            }
+        }
+        .map(|path| path.to_string())
+    }
+
+    /// The first path compared to NaN or an infinity, which `numeric` columns can't hold
+    pub(crate) fn non_finite_path(&self) -> Option<&str> {
+        match self {
+            JsonFilter::LesserThan(paths)
+            | JsonFilter::LesserThanEqual(paths)
+            | JsonFilter::GreaterThan(paths)
+            | JsonFilter::GreaterThanEqual(paths) => paths
+                .iter()
+                .find(|(_, val)| !val.is_finite())
+                .map(|(path, _)| path.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Every key path in a JSON value, looking through arrays
+fn value_paths(value: &Value, prefix: &str) -> Vec<String> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .flat_map(|(key, value)| {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                let mut paths = value_paths(value, &path);
+                paths.insert(0, path);
+                paths
+            })
+            .collect(),
+        Value::Array(values) => values
+            .iter()
+            .flat_map(|value| value_paths(value, prefix))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The paths of a JSON column clients may filter on
+///
+/// Deserializing a [`JsonFilterSet`] fails on any other path, so clients can't probe for keys
+/// they aren't supposed to know about. `has` and `contains` may also name the parents of the
+/// listed paths.
+///
+/// ```
+/// struct MetaPaths;
+///
+/// impl seaqs::filters::JsonPaths for MetaPaths {
+///     const PATHS: &'static [&'static str] = &["plan", "seats", "address.city"];
+/// }
+/// ```
+pub trait JsonPaths {
+    const PATHS: &'static [&'static str];
+}
+
+pub struct JsonFilterSet<'a, P>(pub(crate) Vec<JsonFilter<'a>>, PhantomData<fn() -> P>);
+
+impl<'a, P> JsonFilterSet<'a, P> {
+    pub fn new() -> Self {
+        Self(Vec::new(), PhantomData)
+    }
+
+    /// Adds a filter, without checking its paths against `P`
+    pub fn push(&mut self, value: JsonFilter<'a>) {
+        self.0.push(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, JsonFilter<'a>> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[JsonFilter<'a>] {
+        &self.0
+    }
+
+    pub fn path(mut self, path: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(JsonFilter::Path([(path.into(), value.into())].into()));
+        self
+    }
+
+    pub fn has(mut self, path: impl Into<Cow<'a, str>>) -> Self {
+        self.push(JsonFilter::HasKey(path.into()));
+        self
+    }
+
+    pub fn contains(mut self, value: Value) -> Self {
+        self.push(JsonFilter::Contains(value));
+        self
+    }
+
+    pub fn lt(mut self, path: impl Into<Cow<'a, str>>, value: f64) -> Self {
+        self.push(JsonFilter::LesserThan([(path.into(), value)].into()));
+        self
+    }
+
+    pub fn lte(mut self, path: impl Into<Cow<'a, str>>, value: f64) -> Self {
+        self.push(JsonFilter::LesserThanEqual([(path.into(), value)].into()));
+        self
+    }
+
+    pub fn gt(mut self, path: impl Into<Cow<'a, str>>, value: f64) -> Self {
+        self.push(JsonFilter::GreaterThan([(path.into(), value)].into()));
+        self
+    }
+
+    pub fn gte(mut self, path: impl Into<Cow<'a, str>>, value: f64) -> Self {
+        self.push(JsonFilter::GreaterThanEqual([(path.into(), value)].into()));
+        self
+    }
+}
+
+impl<'a, P> Default for JsonFilterSet<'a, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, P> fmt::Debug for JsonFilterSet<'a, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("JsonFilterSet").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+impl<'a, P> PartialEq for JsonFilterSet<'a, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<'a, P> Serialize for JsonFilterSet<'a, P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        As::<EnumMap>::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de: 'a, 'a, P: JsonPaths> Deserialize<'de> for JsonFilterSet<'a, P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let filters: Vec<JsonFilter<'a>> = As::<EnumMap>::deserialize(deserializer)?;
+
+        if let Some(path) = filters
+            .iter()
+            .find_map(|filter| filter.disallowed_path(P::PATHS))
+        {
+            return Err(de::Error::custom(format_args!(
+                "unknown JSON path `{path}`"
+            )));
+        }
+
+        if let Some(path) = filters.iter().find_map(JsonFilter::non_finite_path) {
+            return Err(de::Error::custom(format_args!(
+                "JSON path `{path}` must be compared to a finite number"
+            )));
+        }
+
+        Ok(Self(filters, PhantomData))
+    }
+}
+
+impl<'a, P> FromIterator<JsonFilter<'a>> for JsonFilterSet<'a, P> {
+    fn from_iter<I: IntoIterator<Item = JsonFilter<'a>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect(), PhantomData)
+    }
+}
+
+impl<'a, P> Extend<JsonFilter<'a>> for JsonFilterSet<'a, P> {
+    fn extend<I: IntoIterator<Item = JsonFilter<'a>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a, P> IntoIterator for JsonFilterSet<'a, P> {
+    type Item = JsonFilter<'a>;
+    type IntoIter = std::vec::IntoIter<JsonFilter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s, 'a, P> IntoIterator for &'s JsonFilterSet<'a, P> {
+    type Item = &'s JsonFilter<'a>;
+    type IntoIter = std::slice::Iter<'s, JsonFilter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl<'a, '__s, P> utoipa::ToSchema<'__s> for JsonFilterSet<'a, P> {
+    fn schema() -> (
+        &'__s str,
+        utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
+    ) {
+        (
+            "JsonFilterSet",
+            utoipa::openapi::schema::ArrayBuilder::new()
+                .items(JsonFilter::schema().1)
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::{JsonFilter::*, JsonFilterSet, JsonPaths};
+    use crate::to_querystring;
+
+    #[derive(Debug)]
+    pub(super) struct MetaPaths;
+
+    impl JsonPaths for MetaPaths {
+        const PATHS: &'static [&'static str] = &["plan", "seats", "address.city"];
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Sample<'a> {
+        #[serde(borrow)]
+        meta: JsonFilterSet<'a, MetaPaths>,
+    }
+
+    #[test]
+    fn deserialize() {
+        const QUERY: &str = "meta[path][plan]=pro\
+                             &meta[path][address.city]=Paris\
+                             &meta[has]=address\
+                             &meta[contains]={\"address\":{\"city\":\"Paris\"}}\
+                             &meta[gte][seats]=5";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        assert_eq!(res.meta.len(), 4);
+        for filter in [
+            Path(
+                [
+                    ("plan".into(), "pro".into()),
+                    ("address.city".into(), "Paris".into()),
+                ]
+                .into(),
+            ),
+            HasKey("address".into()),
+            Contains(json!({"address": {"city": "Paris"}})),
+            GreaterThanEqual([("seats".into(), 5.0)].into()),
+        ] {
+            assert!(res.meta.iter().any(|f| f == &filter), "{filter:?}");
+        }
+    }
+
+    #[test]
+    fn deserialize_unknown_path() {
+        for query in [
+            "meta[path][secret]=1",
+            "meta[path][address]=Paris",
+            "meta[lt][address.zip]=1",
+            "meta[has]=addr",
+            "meta[contains]={\"address\":{\"zip\":\"75001\"}}",
+            "meta[contains]=[{\"secret\":true}]",
+        ] {
+            let err = from_str::<Sample>(query, ParseMode::Brackets).unwrap_err();
+            assert!(err.to_string().contains("unknown JSON path"), "{query}");
+        }
+
+        assert!(from_str::<Sample>("meta[contains]=[1,2]", ParseMode::Brackets).is_ok());
+
+        for query in [
+            "meta[lt][seats]=NaN",
+            "meta[gte][seats]=inf",
+            "meta[gt][seats]=-inf",
+        ] {
+            let err = from_str::<Sample>(query, ParseMode::Brackets).unwrap_err();
+            assert!(err.to_string().contains("finite number"), "{query}");
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let sample = Sample {
+            meta: JsonFilterSet::new()
+                .path("plan", "pro")
+                .has("address")
+                .contains(json!({"plan": "pro"}))
+                .lt("seats", 10.5),
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "meta[path][plan]=pro&meta[has]=address\
+             &meta[contains]=%7B%22plan%22%3A%22pro%22%7D&meta[lt][seats]=10.5"
+        );
+
+        // The filters come back grouped by kind, JsonFilter can't go in a BTreeSet for its floats
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(res.meta.len(), sample.meta.len());
+        assert!(sample
+            .meta
+            .iter()
+            .all(|filter| res.meta.iter().any(|f| f == filter)));
+    }
+}
+
+mod seaq {
+    use sea_query::{
+        extension::postgres::PgBinOper, Alias, Cond, Expr, IntoColumnRef, IntoCondition, SimpleExpr,
+    };
+
+    use super::{JsonFilter, JsonFilterSet};
+    use crate::seaq::ToFieldCond;
+
+    /// A Postgres text array literal of the path's keys, like `{address,city}`
+    fn path_literal(path: &str) -> String {
+        let keys = path
+            .split('.')
+            .map(|key| {
+                if !key.is_empty() && !key.contains(|c: char| ",{}\"\\ ".contains(c)) {
+                    key.to_owned()
+                } else {
+                    format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("{{{}}}", keys.join(","))
+    }
+
+    /// `col #>> '{a,b}'`, the value at the path as text
+    fn text_at(col: SimpleExpr, path: &str) -> SimpleExpr {
+        Expr::cust_with_exprs("$1 #>> $2", [col, Expr::val(path_literal(path)).into()])
+    }
+
+    /// The value at the path as a `numeric`, `NULL` unless it's a JSON number
+    ///
+    /// A `CASE` rather than an `AND` beside the cast, Postgres may evaluate the operands of `AND`
+    /// in any order and fail casting some other row's text.
+    fn number_at(col: SimpleExpr, path: &str) -> SimpleExpr {
+        Expr::cust_with_exprs(
+            "CASE WHEN jsonb_typeof($1 #> $2) = 'number' THEN CAST($1 #>> $2 AS numeric) END",
+            [col, Expr::val(path_literal(path)).into()],
+        )
+    }
+
+    impl<'a> JsonFilter<'a> {
+        fn to_json_cond(&self, col: SimpleExpr) -> Cond {
+            let compare = |paths: &std::collections::BTreeMap<std::borrow::Cow<str>, f64>,
+                           op: fn(Expr, f64) -> SimpleExpr| {
+                let mut conds = Cond::all();
+                for (path, val) in paths {
+                    let number = number_at(col.clone(), path);
+                    conds = conds.add(op(Expr::expr(number), *val));
+                }
+                conds
+            };
+
+            match self {
+                JsonFilter::Path(paths) => {
+                    let mut conds = Cond::all();
+                    for (path, val) in paths {
+                        conds = conds.add(Expr::expr(text_at(col.clone(), path)).eq(val.as_ref()));
+                    }
+                    conds
+                }
+                JsonFilter::HasKey(path) => {
+                    let (parent, key) = match path.rsplit_once('.') {
+                        Some((parent, key)) => (
+                            Expr::cust_with_exprs(
+                                "$1 #> $2",
+                                [col, Expr::val(path_literal(parent)).into()],
+                            ),
+                            key,
+                        ),
+                        None => (col, path.as_ref()),
+                    };
+                    Expr::cust_with_exprs("$1 ? $2", [parent, Expr::val(key).into()])
+                        .into_condition()
+                }
+                JsonFilter::Contains(value) => Expr::expr(col)
+                    .binary(
+                        PgBinOper::Contains,
+                        Expr::val(value.to_string()).cast_as(Alias::new("jsonb")),
+                    )
+                    .into_condition(),
+                JsonFilter::LesserThan(paths) => compare(paths, Expr::lt),
+                JsonFilter::LesserThanEqual(paths) => compare(paths, Expr::lte),
+                JsonFilter::GreaterThan(paths) => compare(paths, Expr::gt),
+                JsonFilter::GreaterThanEqual(paths) => compare(paths, Expr::gte),
+            }
+        }
+    }
+
+    impl<'a> ToFieldCond for JsonFilter<'a> {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            Some(self.to_json_cond(Expr::col(iden).into()))
+        }
+    }
+
+    impl<'a, P> ToFieldCond for JsonFilterSet<'a, P> {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            let mut conds = Cond::all();
+            let col_ref = iden.into_column_ref();
+            for filter in self.0.iter() {
+                if let Some(filter) = filter.to_cond(col_ref.clone()) {
+                    conds = conds.add(filter);
+                }
+            }
+            Some(conds)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use serde_json::json;
+
+        use super::super::tests::MetaPaths;
+        use super::JsonFilter::*;
+        use crate::{filters::JsonFilterSet, test_utils::check_query};
+
+        #[test]
+        fn test_path() {
+            check_query(
+                Path(
+                    [
+                        ("plan".into(), "pro".into()),
+                        ("address.city".into(), "Paris".into()),
+                    ]
+                    .into(),
+                ),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" #>> '{address,city}' = 'Paris' AND "aspect" #>> '{plan}' = 'pro'"#,
+            );
+        }
+
+        #[test]
+        fn test_path_quoted() {
+            check_query(
+                Path([("a b.c,d".into(), "x".into())].into()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" #>> E'{\"a b\",\"c,d\"}' = 'x'"#,
+            );
+        }
+
+        #[test]
+        fn test_has() {
+            check_query(
+                HasKey("plan".into()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" ? 'plan'"#,
+            );
+            check_query(
+                HasKey("address.city".into()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" #> '{address}' ? 'city'"#,
+            );
+        }
+
+        #[test]
+        fn test_contains() {
+            check_query(
+                Contains(json!({"plan": "pro"})),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" @> CAST(E'{\"plan\":\"pro\"}' AS jsonb)"#,
+            );
+        }
+
+        #[test]
+        fn test_compare() {
+            check_query(
+                LesserThan([("seats".into(), 10.5)].into()),
+                r#"SELECT "image" FROM "glyph" WHERE CASE WHEN jsonb_typeof("aspect" #> '{seats}') = 'number' THEN CAST("aspect" #>> '{seats}' AS numeric) END < 10.5"#,
+            );
+            check_query(
+                LesserThanEqual([("seats".into(), 10.0)].into()),
+                r#"SELECT "image" FROM "glyph" WHERE CASE WHEN jsonb_typeof("aspect" #> '{seats}') = 'number' THEN CAST("aspect" #>> '{seats}' AS numeric) END <= 10"#,
+            );
+            check_query(
+                GreaterThan([("seats".into(), 1.0)].into()),
+                r#"SELECT "image" FROM "glyph" WHERE CASE WHEN jsonb_typeof("aspect" #> '{seats}') = 'number' THEN CAST("aspect" #>> '{seats}' AS numeric) END > 1"#,
+            );
+            check_query(
+                GreaterThanEqual([("seats".into(), 1.0)].into()),
+                r#"SELECT "image" FROM "glyph" WHERE CASE WHEN jsonb_typeof("aspect" #> '{seats}') = 'number' THEN CAST("aspect" #>> '{seats}' AS numeric) END >= 1"#,
+            );
+        }
+
+        #[test]
+        fn test_set() {
+            let set = JsonFilterSet::<MetaPaths>::new()
+                .path("plan", "pro")
+                .gte("seats", 5.0);
+
+            check_query(
+                set,
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" #>> '{plan}' = 'pro' AND CASE WHEN jsonb_typeof("aspect" #> '{seats}') = 'number' THEN CAST("aspect" #>> '{seats}' AS numeric) END >= 5"#,
+            );
+        }
+    }
+}
+
+mod memory {
+    use serde_json::Value;
+
+    use super::{JsonFilter, JsonFilterSet};
+    use crate::memory::Matches;
+
+    fn at<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+        path.split('.').try_fold(value, |value, key| value.get(key))
+    }
+
+    /// `#>>`, strings without their quotes and everything else as JSON
+    fn text_at(value: &Value, path: &str) -> Option<String> {
+        at(value, path).map(|value| match value {
+            Value::String(val) => val.clone(),
+            value => value.to_string(),
+        })
+    }
+
+    /// Postgres' `@>` for `jsonb`
+    fn contains(value: &Value, other: &Value) -> bool {
+        match (value, other) {
+            (Value::Object(value), Value::Object(other)) => other
+                .iter()
+                .all(|(key, other)| value.get(key).is_some_and(|value| contains(value, other))),
+            (Value::Array(value), Value::Array(other)) => other
+                .iter()
+                .all(|other| value.iter().any(|value| contains(value, other))),
+            // An array contains a primitive it has as an element
+            (Value::Array(value), other) if !other.is_object() && !other.is_array() => {
+                value.contains(other)
+            }
+            (value, other) => value == other,
+        }
+    }
+
+    impl<'a> Matches<Value> for JsonFilter<'a> {
+        fn matches(&self, value: &Value) -> bool {
+            let compare = |paths: &std::collections::BTreeMap<std::borrow::Cow<str>, f64>,
+                           op: fn(&f64, &f64) -> bool| {
+                // JSON numbers only, as the `jsonb_typeof` check does on the SQL side
+                paths.iter().all(|(path, val)| {
+                    at(value, path)
+                        .and_then(Value::as_f64)
+                        .is_some_and(|number| op(&number, val))
+                })
+            };
+
+            match self {
+                JsonFilter::Path(paths) => paths
+                    .iter()
+                    .all(|(path, val)| text_at(value, path).as_deref() == Some(val.as_ref())),
+                JsonFilter::HasKey(path) => {
+                    let (parent, key) = match path.rsplit_once('.') {
+                        Some((parent, key)) => (at(value, parent), key),
+                        None => (Some(value), path.as_ref()),
+                    };
+                    match parent {
+                        Some(Value::Object(map)) => map.contains_key(key),
+                        Some(Value::Array(values)) => values.contains(&Value::from(key)),
+                        _ => false,
+                    }
+                }
+                JsonFilter::Contains(other) => contains(value, other),
+                JsonFilter::LesserThan(paths) => compare(paths, f64::lt),
+                JsonFilter::LesserThanEqual(paths) => compare(paths, f64::le),
+                JsonFilter::GreaterThan(paths) => compare(paths, f64::gt),
+                JsonFilter::GreaterThanEqual(paths) => compare(paths, f64::ge),
+            }
+        }
+    }
+
+    impl<'a, P> Matches<Value> for JsonFilterSet<'a, P> {
+        fn matches(&self, value: &Value) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use serde_json::json;
+
+        use super::super::tests::MetaPaths;
+        use super::JsonFilter::*;
+        use crate::{filters::JsonFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            let meta = json!({
+                "plan": "pro",
+                "seats": 12,
+                "tags": ["a", "b"],
+                "address": {"city": "Paris"}
+            });
+
+            assert!(Path([("plan".into(), "pro".into())].into()).matches(&meta));
+            assert!(Path([("seats".into(), "12".into())].into()).matches(&meta));
+            assert!(!Path([("address.city".into(), "Lyon".into())].into()).matches(&meta));
+            assert!(HasKey("address.city".into()).matches(&meta));
+            assert!(HasKey("tags.a".into()).matches(&meta));
+            assert!(!HasKey("missing".into()).matches(&meta));
+            assert!(Contains(json!({"tags": ["b"], "address": {}})).matches(&meta));
+            assert!(!Contains(json!({"tags": ["c"]})).matches(&meta));
+            assert!(LesserThan([("seats".into(), 12.5)].into()).matches(&meta));
+            assert!(!GreaterThan([("seats".into(), 12.0)].into()).matches(&meta));
+            assert!(GreaterThanEqual([("seats".into(), 12.0)].into()).matches(&meta));
+            assert!(!LesserThanEqual([("plan".into(), 1.0)].into()).matches(&meta));
+
+            let set = JsonFilterSet::<MetaPaths>::new()
+                .path("plan", "pro")
+                .gte("seats", 10.0);
+            assert!(set.matches(&meta));
+            assert!(!set.matches(&json!({"plan": "pro"})));
+            assert!(JsonFilterSet::<MetaPaths>::new().matches(&json!(null)));
+        }
+
+        #[test]
+        fn test_compare_numeric_strings() {
+            // `jsonb_typeof(...) = 'number'` keeps strings out of the SQL comparison, whatever
+            // they'd parse to, see the seaq `test_compare`
+            let gte = GreaterThanEqual([("seats".into(), 10.0)].into());
+            let lt = LesserThan([("seats".into(), 10.0)].into());
+            assert!(gte.matches(&json!({"seats": 12})));
+            for seats in ["12", "NaN", "inf", "-inf"] {
+                assert!(!gte.matches(&json!({ "seats": seats })));
+                assert!(!lt.matches(&json!({ "seats": seats })));
+            }
+        }
+    }
+}
@@ -1,13 +1,37 @@
+#[cfg(feature = "postgres")]
+mod array;
 mod date;
 mod datetime;
 mod datetime_tz;
+#[cfg(feature = "postgis")]
+mod geo;
+#[cfg(feature = "ipnetwork")]
+mod ip;
+#[cfg(feature = "json")]
+mod json;
 mod number;
+#[cfg(feature = "regex")]
+mod pattern;
+#[cfg(feature = "postgres")]
+mod search;
 mod string;
 mod uuid;
 
-pub use self::uuid::{UuidFilter, UuidFilterSet};
+pub use self::uuid::{uuid_v7_lower_bound, UuidFilter, UuidFilterSet, UuidPrefix};
+#[cfg(feature = "postgres")]
+pub use array::{ArrayFilter, ArrayFilterSet};
 pub use date::{DateFilter, DateFilterSet};
 pub use datetime::{DateTimeFilter, DateTimeFilterSet};
 pub use datetime_tz::{DateTimeTzFilter, DateTimeTzFilterSet};
+#[cfg(feature = "postgis")]
+pub use geo::{BBox, GeoError, GeoFilter, GeoFilterSet, GeoPoint, Near};
+#[cfg(feature = "ipnetwork")]
+pub use ip::{IpFilter, IpFilterSet};
+#[cfg(feature = "json")]
+pub use json::{JsonFilter, JsonFilterSet, JsonPaths};
 pub use number::{NumberFilter, NumberFilterSet};
+#[cfg(feature = "regex")]
+pub use pattern::{RegexError, RegexPattern, MAX_PATTERN_LEN, MAX_REPETITION};
+#[cfg(feature = "postgres")]
+pub use search::{SearchFilter, SearchFilterSet, TsDocument};
 pub use string::{StringFilter, StringFilterSet};
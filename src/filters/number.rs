@@ -1,9 +1,10 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::EnumMap;
 
 #[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum NumberFilter {
     #[serde(rename = "eq")]
     Equals(i64),
@@ -17,14 +18,20 @@ pub enum NumberFilter {
     GreaterThan(i64),
     #[serde(rename = "gte")]
     GreaterThanEqual(i64),
+    #[serde(rename = "in")]
+    In(Vec<i64>),
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 #[serde_with::serde_as]
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct NumberFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<NumberFilter>);
 
 impl NumberFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn push(&mut self, value: NumberFilter) {
         self.0.push(value);
     }
@@ -32,6 +39,83 @@ impl NumberFilterSet {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, NumberFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[NumberFilter] {
+        &self.0
+    }
+
+    pub fn eq(mut self, value: i64) -> Self {
+        self.push(NumberFilter::Equals(value));
+        self
+    }
+
+    pub fn neq(mut self, value: i64) -> Self {
+        self.push(NumberFilter::NotEquals(value));
+        self
+    }
+
+    pub fn lt(mut self, value: i64) -> Self {
+        self.push(NumberFilter::LesserThan(value));
+        self
+    }
+
+    pub fn lte(mut self, value: i64) -> Self {
+        self.push(NumberFilter::LesserThanEqual(value));
+        self
+    }
+
+    pub fn gt(mut self, value: i64) -> Self {
+        self.push(NumberFilter::GreaterThan(value));
+        self
+    }
+
+    pub fn gte(mut self, value: i64) -> Self {
+        self.push(NumberFilter::GreaterThanEqual(value));
+        self
+    }
+
+    pub fn is_in(mut self, values: impl IntoIterator<Item = i64>) -> Self {
+        self.push(NumberFilter::In(values.into_iter().collect()));
+        self
+    }
+}
+
+impl FromIterator<NumberFilter> for NumberFilterSet {
+    fn from_iter<I: IntoIterator<Item = NumberFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<NumberFilter> for NumberFilterSet {
+    fn extend<I: IntoIterator<Item = NumberFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for NumberFilterSet {
+    type Item = NumberFilter;
+    type IntoIter = std::vec::IntoIter<NumberFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s NumberFilterSet {
+    type Item = &'s NumberFilter;
+    type IntoIter = std::slice::Iter<'s, NumberFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(feature = "openapi")]
@@ -54,12 +138,13 @@ mod tests {
     use std::collections::BTreeSet;
     use std::iter::FromIterator;
 
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use serde_querystring::de::{from_str, ParseMode};
 
     use super::{NumberFilter::*, NumberFilterSet};
+    use crate::to_querystring;
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct Sample {
         key: NumberFilterSet,
         bar: NumberFilterSet,
@@ -67,7 +152,7 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        const QUERY: &'static str = "key[lt]=100\
+        const QUERY: &str = "key[lt]=100\
                                     &key[gt]=50\
                                     &key[eq]=75\
                                     &bar[lte]=200\
@@ -96,39 +181,115 @@ mod tests {
             BTreeSet::from_iter(bar.0.iter())
         )
     }
+
+    #[test]
+    fn serialize() {
+        let mut key = NumberFilterSet::default();
+        key.push(Equals(75));
+        key.push(NotEquals(-150));
+        key.push(LesserThan(100));
+        let mut bar = NumberFilterSet::default();
+        bar.push(LesserThanEqual(200));
+        bar.push(GreaterThan(50));
+        bar.push(GreaterThanEqual(100));
+        bar.push(In(vec![1, -2]));
+        let sample = Sample { key, bar };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "key[eq]=75&key[neq]=-150&key[lt]=100\
+             &bar[lte]=200&bar[gt]=50&bar[gte]=100&bar[in]=1&bar[in]=-2"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.key.0.iter()),
+            BTreeSet::from_iter(sample.key.0.iter())
+        );
+        assert_eq!(
+            BTreeSet::from_iter(res.bar.0.iter()),
+            BTreeSet::from_iter(sample.bar.0.iter())
+        );
+    }
+
+    #[test]
+    fn build() {
+        let set = NumberFilterSet::new()
+            .eq(7)
+            .neq(8)
+            .lt(20)
+            .lte(10)
+            .gt(1)
+            .gte(5);
+        assert_eq!(
+            set.as_slice(),
+            &[
+                Equals(7),
+                NotEquals(8),
+                LesserThan(20),
+                LesserThanEqual(10),
+                GreaterThan(1),
+                GreaterThanEqual(5)
+            ]
+        );
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, Equals(_)))
+            .collect::<NumberFilterSet>();
+        set.extend([GreaterThan(5)]);
+        assert_eq!(set.len(), 2);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&Equals(7), &GreaterThan(5)]
+        );
+    }
 }
 
 #[cfg(feature = "seaq")]
 mod seaq {
-    use sea_query::{Cond, Expr, IntoColumnRef, IntoCondition};
+    use sea_query::{Cond, Expr, IntoColumnRef, IntoCondition, SimpleExpr};
 
     use crate::seaq::ToFieldCond;
 
     use super::{NumberFilter, NumberFilterSet};
 
+    impl NumberFilter {
+        /// The condition on any numeric expression rather than a column, e.g. an array's length
+        pub(crate) fn to_expr_cond(&self, expr: SimpleExpr) -> Cond {
+            let expr = Expr::expr(expr);
+            match self {
+                NumberFilter::Equals(val) => expr.eq(*val).into_condition(),
+                NumberFilter::NotEquals(val) => expr.ne(*val).into_condition(),
+                NumberFilter::GreaterThan(val) => expr.gt(*val).into_condition(),
+                NumberFilter::GreaterThanEqual(val) => expr.gte(*val).into_condition(),
+                NumberFilter::LesserThan(val) => expr.lt(*val).into_condition(),
+                NumberFilter::LesserThanEqual(val) => expr.lte(*val).into_condition(),
+                NumberFilter::In(val) => expr.is_in(val.iter().copied()).into_condition(),
+            }
+        }
+    }
+
+    impl NumberFilterSet {
+        pub(crate) fn to_expr_cond(&self, expr: SimpleExpr) -> Cond {
+            let mut conds = Cond::all();
+            for filter in self.0.iter() {
+                conds = conds.add(filter.to_expr_cond(expr.clone()));
+            }
+            conds
+        }
+    }
+
     impl ToFieldCond for NumberFilter {
         fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
-            Some(match self {
-                NumberFilter::Equals(val) => Expr::col(iden).eq(*val).into_condition(),
-                NumberFilter::NotEquals(val) => Expr::col(iden).ne(*val).into_condition(),
-                NumberFilter::GreaterThan(val) => Expr::col(iden).gt(*val).into_condition(),
-                NumberFilter::GreaterThanEqual(val) => Expr::col(iden).gte(*val).into_condition(),
-                NumberFilter::LesserThan(val) => Expr::col(iden).lt(*val).into_condition(),
-                NumberFilter::LesserThanEqual(val) => Expr::col(iden).lte(*val).into_condition(),
-            })
+            Some(self.to_expr_cond(Expr::col(iden).into()))
         }
     }
 
     impl ToFieldCond for NumberFilterSet {
         fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
-            let mut conds = Cond::all();
-            let col_ref = iden.into_column_ref();
-            for filter in self.0.iter() {
-                if let Some(filter) = filter.to_cond(col_ref.clone()) {
-                    conds = conds.add(filter);
-                }
-            }
-            Some(conds)
+            Some(self.to_expr_cond(Expr::col(iden).into()))
         }
     }
 
@@ -198,3 +359,58 @@ mod seaq {
         }
     }
 }
+
+mod memory {
+    use super::{NumberFilter, NumberFilterSet};
+    use crate::memory::Matches;
+
+    impl<V: Copy + Into<i64>> Matches<V> for NumberFilter {
+        fn matches(&self, value: &V) -> bool {
+            let value: i64 = (*value).into();
+            match self {
+                NumberFilter::Equals(val) => value == *val,
+                NumberFilter::NotEquals(val) => value != *val,
+                NumberFilter::GreaterThan(val) => value > *val,
+                NumberFilter::GreaterThanEqual(val) => value >= *val,
+                NumberFilter::LesserThan(val) => value < *val,
+                NumberFilter::LesserThanEqual(val) => value <= *val,
+                NumberFilter::In(val) => val.contains(&value),
+            }
+        }
+    }
+
+    impl<V: ?Sized> Matches<V> for NumberFilterSet
+    where
+        NumberFilter: Matches<V>,
+    {
+        fn matches(&self, value: &V) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::NumberFilter::*;
+        use crate::{filters::NumberFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            assert!(Equals(100).matches(&100));
+            assert!(!Equals(100).matches(&101));
+            assert!(NotEquals(100).matches(&101));
+            assert!(LesserThan(100).matches(&99));
+            assert!(!LesserThan(100).matches(&100));
+            assert!(LesserThanEqual(100).matches(&100));
+            assert!(GreaterThan(100).matches(&101u8));
+            assert!(!GreaterThan(100).matches(&100));
+            assert!(GreaterThanEqual(100).matches(&100i32));
+            assert!(In(vec![1, 100]).matches(&100));
+            assert!(!In(vec![]).matches(&100));
+
+            let set = NumberFilterSet::new().gte(10).lt(20);
+            assert!(set.matches(&10));
+            assert!(!set.matches(&20));
+            assert!(NumberFilterSet::new().matches(&0));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::{borrow::Cow, fmt};
+
+use regex_syntax::hir::{Hir, HirKind};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The longest pattern accepted, in bytes
+pub const MAX_PATTERN_LEN: usize = 256;
+/// The highest bound a counted repetition like `a{2,50}` may use
+pub const MAX_REPETITION: u32 = 100;
+const NEST_LIMIT: u32 = 16;
+
+/// A regular expression checked to be cheap enough to hand to the database
+///
+/// Patterns are parsed with `regex-syntax`, which also rejects backreferences and look-arounds
+/// that some databases support, and are refused when they are too long, too deeply nested, use
+/// large counted repetitions or nest unbounded ones like `(a+)+`, the usual source of
+/// catastrophic backtracking.
+#[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
+#[derive(Debug, Clone)]
+pub struct RegexPattern<'a>(Cow<'a, str>);
+
+impl<'a> RegexPattern<'a> {
+    pub fn new(pattern: impl Into<Cow<'a, str>>) -> Result<Self, RegexError> {
+        let pattern = pattern.into();
+
+        if pattern.len() > MAX_PATTERN_LEN {
+            return Err(RegexError::TooLong(pattern.len()));
+        }
+
+        let hir = regex_syntax::ParserBuilder::new()
+            .nest_limit(NEST_LIMIT)
+            .build()
+            .parse(&pattern)
+            .map_err(|err| RegexError::Syntax(err.to_string()))?;
+        check_complexity(&hir, false)?;
+
+        Ok(Self(pattern))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn check_complexity(hir: &Hir, repeated: bool) -> Result<(), RegexError> {
+    match hir.kind() {
+        HirKind::Repetition(rep) => {
+            if rep.min > MAX_REPETITION || rep.max.is_some_and(|max| max > MAX_REPETITION) {
+                return Err(RegexError::TooComplex("counted repetition is too large"));
+            }
+            let repeats = rep.max != Some(0) && rep.max != Some(1);
+            if repeats && repeated {
+                return Err(RegexError::TooComplex("nested repetition"));
+            }
+            // Bounded nesting like `(\.[0-9]{1,3}){3}` stays cheap, only an unbounded outer
+            // repetition makes the number of ways to match explode
+            check_complexity(&rep.sub, repeated || rep.max.is_none())
+        }
+        HirKind::Capture(capture) => check_complexity(&capture.sub, repeated),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs
+            .iter()
+            .try_for_each(|hir| check_complexity(hir, repeated)),
+        HirKind::Empty | HirKind::Literal(_) | HirKind::Class(_) | HirKind::Look(_) => Ok(()),
+    }
+}
+
+impl<'a> fmt::Display for RegexPattern<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> Serialize for RegexPattern<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for RegexPattern<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(pattern).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexError {
+    TooLong(usize),
+    Syntax(String),
+    TooComplex(&'static str),
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong(len) => write!(
+                f,
+                "regex is {} bytes long, at most {} are allowed",
+                len, MAX_PATTERN_LEN
+            ),
+            Self::Syntax(err) => write!(f, "invalid regex: {}", err),
+            Self::TooComplex(reason) => write!(f, "regex is too complex: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegexError, RegexPattern};
+
+    #[test]
+    fn valid() {
+        for pattern in [
+            "^error: .*timeout",
+            "[0-9]{1,3}(\\.[0-9]{1,3}){3}",
+            "(foo|bar)+baz?",
+            "(?i)warn",
+            "(ab?)+",
+        ] {
+            assert!(RegexPattern::new(pattern).is_ok(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            RegexPattern::new("a".repeat(257)).unwrap_err(),
+            RegexError::TooLong(257)
+        );
+        assert!(matches!(
+            RegexPattern::new("(a").unwrap_err(),
+            RegexError::Syntax(_)
+        ));
+        assert!(matches!(
+            RegexPattern::new("(a)\\1").unwrap_err(),
+            RegexError::Syntax(_)
+        ));
+        assert_eq!(
+            RegexPattern::new("a{1000}").unwrap_err(),
+            RegexError::TooComplex("counted repetition is too large")
+        );
+        for pattern in ["(a+)+", "(a*b?)*c", "((ab){2,5})+", "(x|y*)*"] {
+            assert_eq!(
+                RegexPattern::new(pattern).unwrap_err(),
+                RegexError::TooComplex("nested repetition"),
+                "{pattern}"
+            );
+        }
+        assert!(RegexPattern::new("(".repeat(20) + &")".repeat(20)).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_with::EnumMap;
+
+pub use self::seaq::TsDocument;
+
+/// Postgres full-text search on a document column
+///
+/// Unlike `StringFilter::Contains`, these can use a GIN index and be ranked, see [`TsDocument`].
+#[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchFilter<'a> {
+    /// `websearch_to_tsquery`, which understands quotes, `or` and `-`
+    Search(Cow<'a, str>),
+    /// `phraseto_tsquery`, the words must follow each other
+    Phrase(Cow<'a, str>),
+    /// `plainto_tsquery`, all the words must be present
+    Plain(Cow<'a, str>),
+}
+
+#[cfg_attr(test, derive(PartialEq))]
+#[serde_with::serde_as]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchFilterSet<'a>(#[serde_as(as = "EnumMap")] pub(crate) Vec<SearchFilter<'a>>);
+
+impl<'a> SearchFilterSet<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: SearchFilter<'a>) {
+        self.0.push(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SearchFilter<'a>> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[SearchFilter<'a>] {
+        &self.0
+    }
+
+    pub fn search(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(SearchFilter::Search(value.into()));
+        self
+    }
+
+    pub fn phrase(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(SearchFilter::Phrase(value.into()));
+        self
+    }
+
+    pub fn plain(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(SearchFilter::Plain(value.into()));
+        self
+    }
+}
+
+impl<'a> FromIterator<SearchFilter<'a>> for SearchFilterSet<'a> {
+    fn from_iter<I: IntoIterator<Item = SearchFilter<'a>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> Extend<SearchFilter<'a>> for SearchFilterSet<'a> {
+    fn extend<I: IntoIterator<Item = SearchFilter<'a>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a> IntoIterator for SearchFilterSet<'a> {
+    type Item = SearchFilter<'a>;
+    type IntoIter = std::vec::IntoIter<SearchFilter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s, 'a> IntoIterator for &'s SearchFilterSet<'a> {
+    type Item = &'s SearchFilter<'a>;
+    type IntoIter = std::slice::Iter<'s, SearchFilter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl<'a, '__s> utoipa::ToSchema<'__s> for SearchFilterSet<'a> {
+    fn schema() -> (
+        &'__s str,
+        utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
+    ) {
+        (
+            "SearchFilterSet",
+            utoipa::openapi::schema::ArrayBuilder::new()
+                .items(SearchFilter::schema().1)
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::iter::FromIterator;
+
+    use serde::{Deserialize, Serialize};
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use crate::{filters::SearchFilterSet, to_querystring};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Sample<'a> {
+        body: SearchFilterSet<'a>,
+    }
+
+    #[test]
+    fn deserialize() {
+        const QUERY: &str = "body[search]=\"sea query\" -orm\
+                             &body[phrase]=rust crate\
+                             &body[plain]=filters";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let body = SearchFilterSet::new()
+            .search("\"sea query\" -orm")
+            .phrase("rust crate")
+            .plain("filters");
+
+        assert_eq!(
+            BTreeSet::from_iter(res.body.0.iter()),
+            BTreeSet::from_iter(body.0.iter())
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let sample = Sample {
+            body: SearchFilterSet::new()
+                .search("sea or orm")
+                .phrase("rust crate")
+                .plain("filters"),
+        };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "body[search]=sea%20or%20orm&body[phrase]=rust%20crate&body[plain]=filters"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.body.0.iter()),
+            BTreeSet::from_iter(sample.body.0.iter())
+        );
+    }
+}
+
+mod seaq {
+    use std::borrow::Cow;
+
+    use sea_query::{
+        extension::postgres::PgBinOper, Alias, ColumnRef, Cond, Expr, Func, IntoColumnRef,
+        IntoCondition, SimpleExpr,
+    };
+
+    use super::{SearchFilter, SearchFilterSet};
+    use crate::seaq::ToFieldCond;
+
+    /// The document a [`SearchFilterSet`] searches in, and the text search configuration to use
+    #[derive(Debug, Clone)]
+    pub struct TsDocument {
+        column: ColumnRef,
+        stored: bool,
+        config: Option<Cow<'static, str>>,
+    }
+
+    impl TsDocument {
+        /// A text column, turned into a `tsvector` with `to_tsvector` on every query
+        pub fn text<I: IntoColumnRef>(column: I) -> Self {
+            Self {
+                column: column.into_column_ref(),
+                stored: false,
+                config: None,
+            }
+        }
+
+        /// A column already holding a `tsvector`, usually a generated and indexed one
+        pub fn stored<I: IntoColumnRef>(column: I) -> Self {
+            Self {
+                column: column.into_column_ref(),
+                stored: true,
+                config: None,
+            }
+        }
+
+        /// The text search configuration, e.g. `english`, otherwise the database's
+        /// `default_text_search_config` is used
+        pub fn config(mut self, config: impl Into<Cow<'static, str>>) -> Self {
+            self.config = Some(config.into());
+            self
+        }
+
+        fn call(&self, func: &str, value: SimpleExpr) -> SimpleExpr {
+            let mut args = Vec::with_capacity(2);
+            if let Some(config) = &self.config {
+                args.push(Expr::val(config.as_ref()).cast_as(Alias::new("regconfig")));
+            }
+            args.push(value);
+            Func::cust(Alias::new(func)).args(args).into()
+        }
+
+        fn vector(&self) -> SimpleExpr {
+            if self.stored {
+                Expr::col(self.column.clone()).into()
+            } else {
+                self.call("to_tsvector", Expr::col(self.column.clone()).into())
+            }
+        }
+
+        fn query(&self, filter: &SearchFilter) -> SimpleExpr {
+            match filter {
+                SearchFilter::Search(val) => {
+                    self.call("websearch_to_tsquery", Expr::val(val.as_ref()).into())
+                }
+                SearchFilter::Phrase(val) => {
+                    self.call("phraseto_tsquery", Expr::val(val.as_ref()).into())
+                }
+                SearchFilter::Plain(val) => {
+                    self.call("plainto_tsquery", Expr::val(val.as_ref()).into())
+                }
+            }
+        }
+    }
+
+    impl<'a> SearchFilter<'a> {
+        pub fn to_search_cond(&self, document: &TsDocument) -> Cond {
+            Expr::expr(document.vector())
+                .binary(PgBinOper::Matches, document.query(self))
+                .into_condition()
+        }
+    }
+
+    impl<'a> SearchFilterSet<'a> {
+        pub fn to_search_cond(&self, document: &TsDocument) -> Option<Cond> {
+            let mut conds = Cond::all();
+            for filter in self.0.iter() {
+                conds = conds.add(filter.to_search_cond(document));
+            }
+            Some(conds)
+        }
+
+        /// `ts_rank` of the document against all the queries, for sorting by relevance
+        ///
+        /// Return it from [`ToCond::sort_expr`](crate::ToCond::sort_expr) to make a sort field
+        /// like `relevance` available to `ApplyFilters`.
+        pub fn rank(&self, document: &TsDocument) -> Option<SimpleExpr> {
+            let query = self
+                .0
+                .iter()
+                .map(|filter| document.query(filter))
+                .reduce(|all, query| Expr::cust_with_exprs("($1 && $2)", [all, query]))?;
+
+            Some(
+                Func::cust(Alias::new("ts_rank"))
+                    .args([document.vector(), query])
+                    .into(),
+            )
+        }
+    }
+
+    /// Searches a text column with the default configuration, use `to_search_cond` for more
+    impl<'a> ToFieldCond for SearchFilter<'a> {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            Some(self.to_search_cond(&TsDocument::text(iden)))
+        }
+    }
+
+    /// Searches a text column with the default configuration, use `to_search_cond` for more
+    impl<'a> ToFieldCond for SearchFilterSet<'a> {
+        fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            self.to_search_cond(&TsDocument::text(iden))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use sea_query::{tests_cfg::*, Cond, PostgresQueryBuilder, Query, SimpleExpr};
+        use serde_querystring::de::ParseMode;
+
+        use super::{SearchFilter::*, TsDocument};
+        use crate::{
+            filters::SearchFilterSet, test_utils::check_query, ApplyFilters, Filter, QueryFilter,
+            ToCond,
+        };
+
+        #[test]
+        fn test_search() {
+            check_query(
+                Search("sea -orm".into()),
+                r#"SELECT "image" FROM "glyph" WHERE to_tsvector("aspect") @@ websearch_to_tsquery('sea -orm')"#,
+            );
+        }
+
+        #[test]
+        fn test_phrase() {
+            check_query(
+                Phrase("sea query".into()),
+                r#"SELECT "image" FROM "glyph" WHERE to_tsvector("aspect") @@ phraseto_tsquery('sea query')"#,
+            );
+        }
+
+        #[test]
+        fn test_plain() {
+            check_query(
+                Plain("sea query".into()),
+                r#"SELECT "image" FROM "glyph" WHERE to_tsvector("aspect") @@ plainto_tsquery('sea query')"#,
+            );
+        }
+
+        #[test]
+        fn test_config() {
+            let set = SearchFilterSet::new().search("sea");
+            let document = TsDocument::text(Glyph::Aspect).config("english");
+
+            let query = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .cond_where(set.to_search_cond(&document).unwrap())
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(
+                query,
+                "SELECT \"image\" FROM \"glyph\" WHERE \
+                 to_tsvector(CAST('english' AS regconfig), \"aspect\") @@ \
+                 websearch_to_tsquery(CAST('english' AS regconfig), 'sea')"
+            );
+        }
+
+        #[test]
+        fn test_stored_rank() {
+            let set = SearchFilterSet::new().search("sea").plain("query");
+            let document = TsDocument::stored(Glyph::Tokens).config("simple");
+
+            let query = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .cond_where(set.to_search_cond(&document).unwrap())
+                .order_by_expr(set.rank(&document).unwrap(), sea_query::Order::Desc)
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(
+                query,
+                "SELECT \"image\" FROM \"glyph\" WHERE \
+                 \"tokens\" @@ websearch_to_tsquery(CAST('simple' AS regconfig), 'sea') AND \
+                 \"tokens\" @@ plainto_tsquery(CAST('simple' AS regconfig), 'query') \
+                 ORDER BY ts_rank(\"tokens\", (websearch_to_tsquery(CAST('simple' AS regconfig), 'sea') && \
+                 plainto_tsquery(CAST('simple' AS regconfig), 'query'))) DESC"
+            );
+        }
+
+        #[test]
+        fn test_sort_by_relevance() {
+            #[derive(serde::Deserialize)]
+            struct Filters {
+                body: Option<SearchFilterSet<'static>>,
+            }
+
+            impl Filters {
+                fn document() -> TsDocument {
+                    TsDocument::stored(Glyph::Tokens).config("english")
+                }
+            }
+
+            impl ToCond for Filters {
+                const EXPR_SORT_FIELDS: &'static [&'static str] = &["relevance"];
+
+                fn to_cond(&self) -> Cond {
+                    let mut cond = Cond::all();
+                    if let Some(body) = &self.body {
+                        if let Some(body) = body.to_search_cond(&Self::document()) {
+                            cond = cond.add(body)
+                        }
+                    }
+                    cond
+                }
+
+                fn sort_expr(&self, field: &str) -> Option<SimpleExpr> {
+                    match field {
+                        "relevance" => self.body.as_ref()?.rank(&Self::document()),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl Filter for Filters {
+                const SORTABLE_FIELDS: &'static [&'static str] = &["id", "relevance"];
+            }
+
+            let filters = serde_querystring::from_str::<QueryFilter<Filters>>(
+                "filter[body][search]=sea&sort=relevance&order=DESC&end=5",
+                ParseMode::Brackets,
+            )
+            .unwrap();
+
+            let query = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .apply_filters(&filters)
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(
+                query,
+                "SELECT \"image\" FROM \"glyph\" WHERE \
+                 \"tokens\" @@ websearch_to_tsquery(CAST('english' AS regconfig), 'sea') \
+                 ORDER BY ts_rank(\"tokens\", websearch_to_tsquery(CAST('english' AS regconfig), 'sea')) DESC \
+                 LIMIT 5 OFFSET 0"
+            );
+
+            // Without a search there's nothing to rank by, and no "relevance" column either
+            let filters = serde_querystring::from_str::<QueryFilter<Filters>>(
+                "sort=relevance&order=DESC&end=5",
+                ParseMode::Brackets,
+            )
+            .unwrap();
+
+            let query = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .apply_filters(&filters)
+                .to_string(PostgresQueryBuilder);
+
+            assert_eq!(query, "SELECT \"image\" FROM \"glyph\" LIMIT 5 OFFSET 0");
+        }
+
+        #[test]
+        fn test_empty_rank() {
+            assert!(SearchFilterSet::new()
+                .rank(&TsDocument::text(Glyph::Aspect))
+                .is_none());
+        }
+    }
+}
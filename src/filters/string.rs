@@ -1,25 +1,59 @@
 use std::borrow::Cow;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::EnumMap;
 
+#[cfg(feature = "regex")]
+use super::RegexPattern;
+
 #[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum StringFilter<'a> {
+    #[serde(rename = "eq")]
+    Equals(Cow<'a, str>),
+    #[serde(rename = "neq")]
+    NotEquals(Cow<'a, str>),
     Contains(Cow<'a, str>),
     NotContains(Cow<'a, str>),
     StartsWith(Cow<'a, str>),
     EndsWith(Cow<'a, str>),
+    /// A raw `LIKE` pattern, with the client's own `%` and `_` wildcards
+    ///
+    /// `\` escapes a wildcard or itself, patterns with one get an `ESCAPE '\'` since SQLite has no
+    /// default escape character. A trailing `\` matches nothing, Postgres rejects the pattern.
+    Like(Cow<'a, str>),
+    /// `Like` ignoring case, lowering both sides so every database treats it the same way
+    ILike(Cow<'a, str>),
+    In(Vec<Cow<'a, str>>),
+    /// Matches the regular expression, `~` on Postgres and `REGEXP` on MySQL and SQLite
+    ///
+    /// Only [`ToFieldCond::to_backend_cond`](crate::ToFieldCond::to_backend_cond) renders the
+    /// `REGEXP` form, `to_cond` always gives Postgres' operators.
+    /// [`ApplyFilters::apply_backend_filters`](crate::ApplyFilters::apply_backend_filters) gets it
+    /// through a `ToCond::to_backend_cond` passing the backend on. SQLite has no `REGEXP` until a
+    /// `regexp(pattern, text)` function is registered on the connection.
+    #[cfg(feature = "regex")]
+    Regex(#[cfg_attr(feature = "openapi", schema(value_type = String))] RegexPattern<'a>),
+    /// Matches the regular expression ignoring case, `~*` on Postgres
+    #[cfg(feature = "regex")]
+    IRegex(#[cfg_attr(feature = "openapi", schema(value_type = String))] RegexPattern<'a>),
+    #[cfg(feature = "regex")]
+    NotRegex(#[cfg_attr(feature = "openapi", schema(value_type = String))] RegexPattern<'a>),
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 #[serde_with::serde_as]
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct StringFilterSet<'a>(#[serde_as(as = "EnumMap")] pub(crate) Vec<StringFilter<'a>>);
 
 impl<'a> StringFilterSet<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn push(&mut self, value: StringFilter<'a>) {
         self.0.push(value);
     }
@@ -27,6 +61,113 @@ impl<'a> StringFilterSet<'a> {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, StringFilter<'a>> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[StringFilter<'a>] {
+        &self.0
+    }
+
+    pub fn eq(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::Equals(value.into()));
+        self
+    }
+
+    pub fn neq(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::NotEquals(value.into()));
+        self
+    }
+
+    pub fn contains(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::Contains(value.into()));
+        self
+    }
+
+    pub fn not_contains(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::NotContains(value.into()));
+        self
+    }
+
+    pub fn starts_with(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::StartsWith(value.into()));
+        self
+    }
+
+    pub fn ends_with(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::EndsWith(value.into()));
+        self
+    }
+
+    pub fn like(mut self, pattern: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::Like(pattern.into()));
+        self
+    }
+
+    pub fn ilike(mut self, pattern: impl Into<Cow<'a, str>>) -> Self {
+        self.push(StringFilter::ILike(pattern.into()));
+        self
+    }
+
+    pub fn is_in<V: Into<Cow<'a, str>>>(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        self.push(StringFilter::In(
+            values.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn regex(mut self, pattern: RegexPattern<'a>) -> Self {
+        self.push(StringFilter::Regex(pattern));
+        self
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn iregex(mut self, pattern: RegexPattern<'a>) -> Self {
+        self.push(StringFilter::IRegex(pattern));
+        self
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn not_regex(mut self, pattern: RegexPattern<'a>) -> Self {
+        self.push(StringFilter::NotRegex(pattern));
+        self
+    }
+}
+
+impl<'a> FromIterator<StringFilter<'a>> for StringFilterSet<'a> {
+    fn from_iter<I: IntoIterator<Item = StringFilter<'a>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> Extend<StringFilter<'a>> for StringFilterSet<'a> {
+    fn extend<I: IntoIterator<Item = StringFilter<'a>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a> IntoIterator for StringFilterSet<'a> {
+    type Item = StringFilter<'a>;
+    type IntoIter = std::vec::IntoIter<StringFilter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s, 'a> IntoIterator for &'s StringFilterSet<'a> {
+    type Item = &'s StringFilter<'a>;
+    type IntoIter = std::slice::Iter<'s, StringFilter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(feature = "openapi")]
@@ -49,13 +190,13 @@ mod tests {
     use std::collections::BTreeSet;
     use std::iter::FromIterator;
 
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use serde_querystring::de::{from_str, ParseMode};
 
     use super::StringFilter::*;
-    use crate::filters::StringFilterSet;
+    use crate::{filters::StringFilterSet, to_querystring};
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct Sample<'a> {
         key: StringFilterSet<'a>,
         bla: StringFilterSet<'a>,
@@ -63,7 +204,7 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        const QUERY: &'static str = "key[contains]=right_there\
+        const QUERY: &str = "key[contains]=right_there\
                                     &key[notcontains]=not_there\
                                     &bla[startswith]=hello_there\
                                     &bla[endswith]=bye";
@@ -88,44 +229,197 @@ mod tests {
             BTreeSet::from_iter(bla.0.iter())
         )
     }
+
+    #[test]
+    fn serialize() {
+        let mut key = StringFilterSet::default();
+        key.push(Contains("right there".into()));
+        key.push(NotContains("50% & more".into()));
+        let mut bla = StringFilterSet::default();
+        bla.push(StartsWith("[hello]=there".into()));
+        bla.push(EndsWith("bye+ü".into()));
+        bla.push(Like("50\\%%".into()));
+        bla.push(ILike("a_c%".into()));
+        bla.push(In(vec!["x,y".into(), "z".into()]));
+        let sample = Sample { key, bla };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "key[contains]=right%20there\
+             &key[notcontains]=50%25%20%26%20more\
+             &bla[startswith]=%5Bhello%5D%3Dthere\
+             &bla[endswith]=bye%2B%C3%BC\
+             &bla[like]=50%5C%25%25\
+             &bla[ilike]=a_c%25\
+             &bla[in]=x%2Cy&bla[in]=z"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.key.0.iter()),
+            BTreeSet::from_iter(sample.key.0.iter())
+        );
+        assert_eq!(
+            BTreeSet::from_iter(res.bla.0.iter()),
+            BTreeSet::from_iter(sample.bla.0.iter())
+        );
+    }
+
+    #[test]
+    fn build() {
+        let set = StringFilterSet::new()
+            .contains("a")
+            .not_contains(String::from("b"))
+            .starts_with("c")
+            .ends_with("d");
+        assert_eq!(
+            set.as_slice(),
+            &[
+                Contains("a".into()),
+                NotContains("b".into()),
+                StartsWith("c".into()),
+                EndsWith("d".into())
+            ]
+        );
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, Contains(_)))
+            .collect::<StringFilterSet>();
+        set.extend([EndsWith("e".into())]);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&Contains("a".into()), &EndsWith("e".into())]
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn deserialize_regex() {
+        use crate::filters::RegexPattern;
+
+        const QUERY: &str = "key[regex]=^err(or)?:\
+                             &key[notregex]=timeout$\
+                             &bla[iregex]=warn";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let key = StringFilterSet::new()
+            .regex(RegexPattern::new("^err(or)?:").unwrap())
+            .not_regex(RegexPattern::new("timeout$").unwrap());
+        assert_eq!(
+            BTreeSet::from_iter(res.key.0.iter()),
+            BTreeSet::from_iter(key.0.iter())
+        );
+        assert_eq!(
+            res.bla.as_slice(),
+            &[IRegex(RegexPattern::new("warn").unwrap())]
+        );
+
+        let err = from_str::<Sample>("key[regex]=(a%2B)%2B&bla[iregex]=a", ParseMode::Brackets)
+            .unwrap_err();
+        assert!(err.to_string().contains("nested repetition"));
+    }
 }
 
 #[cfg(feature = "seaq")]
 mod seaq {
-    use sea_query::{Cond, Expr, IntoColumnRef, IntoCondition};
+    use sea_query::{Cond, Expr, Func, IntoColumnRef, IntoCondition};
 
     use super::{StringFilter, StringFilterSet};
-    use crate::seaq::ToFieldCond;
+    use crate::seaq::{like_expr, Backend, ToFieldCond};
+
+    /// `~`, `~*` and `!~` on Postgres, `REGEXP` with an inline flag for the case elsewhere
+    #[cfg(feature = "regex")]
+    fn regex_cond<I: IntoColumnRef>(
+        iden: I,
+        backend: Backend,
+        pattern: &super::RegexPattern,
+        case_insensitive: bool,
+        negated: bool,
+    ) -> Cond {
+        let col = Expr::col(iden).into();
+        match backend {
+            Backend::Postgres => {
+                let op = match (case_insensitive, negated) {
+                    (false, false) => "~",
+                    (true, false) => "~*",
+                    (false, true) => "!~",
+                    (true, true) => "!~*",
+                };
+                let sql = format!("$1 {} $2", op);
+                Expr::cust_with_exprs(&sql, [col, Expr::val(pattern.as_str()).into()])
+            }
+            Backend::MySql | Backend::Sqlite => {
+                let flag = if case_insensitive { "(?i)" } else { "(?-i)" };
+                let sql = if negated {
+                    "? NOT REGEXP ?"
+                } else {
+                    "? REGEXP ?"
+                };
+                let pattern = [flag, pattern.as_str()].join("");
+                Expr::cust_with_exprs(sql, [col, Expr::val(pattern).into()])
+            }
+        }
+        .into_condition()
+    }
 
     impl<'a> ToFieldCond for StringFilter<'a> {
         fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            self.to_backend_cond(iden, Backend::Postgres)
+        }
+
+        #[cfg_attr(not(feature = "regex"), allow(unused_variables))]
+        fn to_backend_cond<I: IntoColumnRef>(&self, iden: I, backend: Backend) -> Option<Cond> {
             Some(match self {
+                StringFilter::Equals(val) => Expr::col(iden).eq(val.as_ref()).into_condition(),
+                StringFilter::NotEquals(val) => Expr::col(iden).ne(val.as_ref()).into_condition(),
                 StringFilter::Contains(val) => {
-                    let value = ["%", &val, "%"].join("");
-                    Expr::col(iden).like(value).into_condition()
+                    let value = ["%", val, "%"].join("");
+                    Expr::col(iden).like(like_expr(value)).into_condition()
                 }
                 StringFilter::NotContains(val) => {
-                    let value = ["%", &val, "%"].join("");
-                    Expr::col(iden).not_like(value).into_condition()
+                    let value = ["%", val, "%"].join("");
+                    Expr::col(iden).not_like(like_expr(value)).into_condition()
                 }
                 StringFilter::StartsWith(val) => {
-                    let value = [&val, "%"].join("");
-                    Expr::col(iden).like(value).into_condition()
+                    let value = [val, "%"].join("");
+                    Expr::col(iden).like(like_expr(value)).into_condition()
                 }
                 StringFilter::EndsWith(val) => {
-                    let value = ["%", &val].join("");
-                    Expr::col(iden).like(value).into_condition()
+                    let value = ["%", val].join("");
+                    Expr::col(iden).like(like_expr(value)).into_condition()
                 }
+                StringFilter::Like(val) => Expr::col(iden)
+                    .like(like_expr(val.to_string()))
+                    .into_condition(),
+                StringFilter::ILike(val) => Expr::expr(Func::lower(Expr::col(iden)))
+                    .like(like_expr(val.to_lowercase()))
+                    .into_condition(),
+                StringFilter::In(val) => Expr::col(iden)
+                    .is_in(val.iter().map(|val| val.as_ref()))
+                    .into_condition(),
+                #[cfg(feature = "regex")]
+                StringFilter::Regex(val) => regex_cond(iden, backend, val, false, false),
+                #[cfg(feature = "regex")]
+                StringFilter::IRegex(val) => regex_cond(iden, backend, val, true, false),
+                #[cfg(feature = "regex")]
+                StringFilter::NotRegex(val) => regex_cond(iden, backend, val, false, true),
             })
         }
     }
 
     impl<'a> ToFieldCond for StringFilterSet<'a> {
         fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
+            self.to_backend_cond(iden, Backend::Postgres)
+        }
+
+        fn to_backend_cond<I: IntoColumnRef>(&self, iden: I, backend: Backend) -> Option<Cond> {
             let mut conds = Cond::all();
             let col_ref = iden.into_column_ref();
             for filter in self.0.iter() {
-                if let Some(filter) = filter.to_cond(col_ref.clone()) {
+                if let Some(filter) = filter.to_backend_cond(col_ref.clone(), backend) {
                     conds = conds.add(filter);
                 }
             }
@@ -138,6 +432,18 @@ mod seaq {
         use super::StringFilter::*;
         use crate::{filters::StringFilterSet, test_utils::check_query};
 
+        #[test]
+        fn test_eq() {
+            check_query(
+                Equals("string".into()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" = 'string'"#,
+            );
+            check_query(
+                NotEquals("string".into()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" <> 'string'"#,
+            );
+        }
+
         #[test]
         fn test_contains() {
             check_query(
@@ -170,6 +476,48 @@ mod seaq {
             );
         }
 
+        #[test]
+        fn test_like() {
+            check_query(
+                Like("a_c%".into()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" LIKE 'a_c%'"#,
+            );
+            check_query(
+                ILike("%JoHn%".into()),
+                r#"SELECT "image" FROM "glyph" WHERE LOWER("aspect") LIKE '%john%'"#,
+            );
+        }
+
+        #[test]
+        fn test_like_escape() {
+            use sea_query::{tests_cfg::*, Query, SqliteQueryBuilder};
+
+            use crate::ToFieldCond;
+
+            // `to_cond` adds the `ESCAPE` SQLite needs to read `\` like Postgres and MySQL do
+            let set = StringFilterSet::new()
+                .like("50\\%%")
+                .ilike("A\\_%")
+                .contains("a\\b");
+            let sqlite = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .cond_where(set.to_cond(Glyph::Aspect).unwrap())
+                .to_string(SqliteQueryBuilder);
+            assert_eq!(
+                sqlite,
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" LIKE '50\%%' ESCAPE '\' AND LOWER("aspect") LIKE 'a\_%' ESCAPE '\' AND "aspect" LIKE '%a\b%' ESCAPE '\'"#
+            );
+        }
+
+        #[test]
+        fn test_in() {
+            check_query(
+                In(vec!["a".into(), "b".into()]),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" IN ('a', 'b')"#,
+            );
+        }
+
         #[test]
         fn test_set() {
             let mut set = StringFilterSet::default();
@@ -181,5 +529,148 @@ mod seaq {
                 r#"SELECT "image" FROM "glyph" WHERE "aspect" LIKE '%string%' AND "aspect" LIKE 'string%'"#,
             );
         }
+
+        #[cfg(feature = "regex")]
+        #[test]
+        fn test_regex() {
+            use crate::filters::RegexPattern;
+
+            let pattern = || RegexPattern::new("^err").unwrap();
+            check_query(
+                Regex(pattern()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" ~ '^err'"#,
+            );
+            check_query(
+                IRegex(pattern()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" ~* '^err'"#,
+            );
+            check_query(
+                NotRegex(pattern()),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" !~ '^err'"#,
+            );
+        }
+
+        #[cfg(feature = "regex")]
+        #[test]
+        fn test_regex_backends() {
+            use sea_query::{tests_cfg::*, MysqlQueryBuilder, Query, SqliteQueryBuilder};
+
+            use crate::{filters::RegexPattern, seaq::Backend, ToFieldCond};
+
+            let set = StringFilterSet::new()
+                .regex(RegexPattern::new("^err").unwrap())
+                .iregex(RegexPattern::new("warn").unwrap())
+                .not_regex(RegexPattern::new("x$").unwrap());
+
+            let mysql = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .cond_where(set.to_backend_cond(Glyph::Aspect, Backend::MySql).unwrap())
+                .to_string(MysqlQueryBuilder);
+            assert_eq!(
+                mysql,
+                "SELECT `image` FROM `glyph` WHERE `aspect` REGEXP '(?-i)^err' \
+                 AND `aspect` REGEXP '(?i)warn' AND `aspect` NOT REGEXP '(?-i)x$'"
+            );
+
+            let sqlite = Query::select()
+                .column(Glyph::Image)
+                .from(Glyph::Table)
+                .cond_where(set.to_backend_cond(Glyph::Aspect, Backend::Sqlite).unwrap())
+                .to_string(SqliteQueryBuilder);
+            assert_eq!(
+                sqlite,
+                "SELECT \"image\" FROM \"glyph\" WHERE \"aspect\" REGEXP '(?-i)^err' \
+                 AND \"aspect\" REGEXP '(?i)warn' AND \"aspect\" NOT REGEXP '(?-i)x$'"
+            );
+        }
+    }
+}
+
+mod memory {
+    use super::{StringFilter, StringFilterSet};
+    use crate::memory::{like, Matches};
+
+    impl<'a, V: AsRef<str> + ?Sized> Matches<V> for StringFilter<'a> {
+        fn matches(&self, value: &V) -> bool {
+            let value = value.as_ref();
+            match self {
+                StringFilter::Equals(val) => value == val,
+                StringFilter::NotEquals(val) => value != val,
+                StringFilter::Contains(val) => like(&["%", val, "%"].join(""), value),
+                StringFilter::NotContains(val) => !like(&["%", val, "%"].join(""), value),
+                StringFilter::StartsWith(val) => like(&[val, "%"].join(""), value),
+                StringFilter::EndsWith(val) => like(&["%", val].join(""), value),
+                StringFilter::Like(val) => like(val, value),
+                StringFilter::ILike(val) => like(&val.to_lowercase(), &value.to_lowercase()),
+                StringFilter::In(val) => val.iter().any(|val| val == value),
+                #[cfg(feature = "regex")]
+                StringFilter::Regex(val) => regex_matches(val, false, value),
+                #[cfg(feature = "regex")]
+                StringFilter::IRegex(val) => regex_matches(val, true, value),
+                #[cfg(feature = "regex")]
+                StringFilter::NotRegex(val) => !regex_matches(val, false, value),
+            }
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn regex_matches(pattern: &super::RegexPattern, case_insensitive: bool, value: &str) -> bool {
+        ::regex::RegexBuilder::new(pattern.as_str())
+            .case_insensitive(case_insensitive)
+            .build()
+            .is_ok_and(|regex| regex.is_match(value))
+    }
+
+    impl<'a, V: ?Sized> Matches<V> for StringFilterSet<'a>
+    where
+        StringFilter<'a>: Matches<V>,
+    {
+        fn matches(&self, value: &V) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::StringFilter::*;
+        use crate::{filters::StringFilterSet, memory::Matches};
+
+        #[test]
+        fn test_matches() {
+            assert!(Equals("a string".into()).matches("a string"));
+            assert!(!Equals("a str%".into()).matches("a string"));
+            assert!(NotEquals("a str".into()).matches("a string"));
+            assert!(Contains("ring".into()).matches("a string"));
+            assert!(!Contains("Ring".into()).matches("a string"));
+            assert!(NotContains("x".into()).matches("a string"));
+            assert!(StartsWith("a s".into()).matches(&String::from("a string")));
+            assert!(!StartsWith("string".into()).matches("a string"));
+            assert!(EndsWith("ing".into()).matches("a string"));
+            // The value is used as a LIKE pattern, wildcards included
+            assert!(Contains("s_r".into()).matches("a string"));
+            assert!(Like("a_s%".into()).matches("a string"));
+            assert!(!Like("A%".into()).matches("a string"));
+            assert!(ILike("A%ING".into()).matches("a string"));
+            assert!(In(vec!["b".into(), "a string".into()]).matches("a string"));
+            assert!(!In(vec![]).matches("a string"));
+
+            let set = StringFilterSet::new().starts_with("a").not_contains("x");
+            assert!(set.matches("a string"));
+            assert!(!set.matches("a text"));
+        }
+
+        #[cfg(feature = "regex")]
+        #[test]
+        fn test_matches_regex() {
+            use crate::filters::RegexPattern;
+
+            let pattern = |pattern| RegexPattern::new(pattern).unwrap();
+            assert!(Regex(pattern("^a s.*g$")).matches("a string"));
+            assert!(!Regex(pattern("^A")).matches("a string"));
+            assert!(IRegex(pattern("^A")).matches("a string"));
+            assert!(NotRegex(pattern("[0-9]")).matches("a string"));
+            assert!(!NotRegex(pattern("str")).matches("a string"));
+        }
     }
 }
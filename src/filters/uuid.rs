@@ -1,23 +1,133 @@
-use serde::Deserialize;
+use std::fmt;
+
+use chrono::{DateTime, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::EnumMap;
 use uuid::Uuid;
 
 #[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
-#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum UuidFilter {
     #[serde(rename = "eq")]
     Equals(Uuid),
-    #[serde(rename = "in")]
+    #[serde(rename = "neq")]
+    NotEquals(Uuid),
+    #[serde(rename = "lt")]
+    LesserThan(Uuid),
+    #[serde(rename = "lte")]
+    LesserThanEqual(Uuid),
+    #[serde(rename = "gt")]
+    GreaterThan(Uuid),
+    #[serde(rename = "gte")]
+    GreaterThanEqual(Uuid),
+    #[serde(rename = "in", deserialize_with = "uuid_list")]
     In(Vec<Uuid>),
+    #[serde(rename = "nin", deserialize_with = "uuid_list")]
+    NotIn(Vec<Uuid>),
+    /// `IS NULL` when true, `IS NOT NULL` when false
+    #[serde(rename = "null")]
+    Null(bool),
+    /// The UUID's text starts with the given hex digits, `CAST(col AS text) LIKE 'abcd%'`
+    #[serde(rename = "prefix")]
+    Prefix(UuidPrefix),
+}
+
+/// The first hex digits of a UUID, laid out like the start of its hyphenated text
+///
+/// Digits may be given in either case and with or without hyphens, `0190A3-0c` and `0190a30c`
+/// both become `0190a30c`, and a ninth digit gets the hyphen the full UUID has there.
+#[cfg_attr(test, derive(Eq, PartialEq, Ord, PartialOrd))]
+#[derive(Debug, Clone)]
+pub struct UuidPrefix(String);
+
+impl UuidPrefix {
+    /// `None` unless `prefix` has 1 to 32 hex digits and nothing but hyphens besides them
+    pub fn new(prefix: &str) -> Option<Self> {
+        let digits = prefix.chars().filter(|c| *c != '-').collect::<Vec<_>>();
+        if digits.is_empty() || digits.len() > 32 || !digits.iter().all(char::is_ascii_hexdigit) {
+            return None;
+        }
+
+        let mut text = String::with_capacity(36);
+        for (i, digit) in digits.into_iter().enumerate() {
+            if [8, 12, 16, 20].contains(&i) {
+                text.push('-');
+            }
+            text.push(digit.to_ascii_lowercase());
+        }
+        Some(Self(text))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for UuidPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for UuidPrefix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for UuidPrefix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let prefix = String::deserialize(deserializer)?;
+        Self::new(&prefix).ok_or_else(|| {
+            serde::de::Error::custom(format_args!(
+                "`{prefix}` isn't the start of a UUID, expected up to 32 hex digits"
+            ))
+        })
+    }
+}
+
+/// Accepts both repeated keys and comma separated values, `id[in]=a&id[in]=b,c`, in any of the
+/// forms `Uuid::parse_str` knows
+fn uuid_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Uuid>, D::Error> {
+    let mut uuids = Vec::new();
+    for item in Vec::<String>::deserialize(deserializer)? {
+        for part in item
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            uuids.push(Uuid::parse_str(part).map_err(serde::de::Error::custom)?);
+        }
+    }
+    Ok(uuids)
+}
+
+/// The smallest UUIDv7 that can be generated at `time`
+///
+/// UUIDv7 keys start with their creation time in milliseconds, so they sort like it and
+/// `id >= uuid_v7_lower_bound(t)` keeps the rows created at or after `t`, without an index on a
+/// separate timestamp column. Times outside what the 48 bits of the timestamp can hold are
+/// clamped.
+pub fn uuid_v7_lower_bound<Tz: TimeZone>(time: &DateTime<Tz>) -> Uuid {
+    let millis = time.timestamp_millis().clamp(0, (1 << 48) - 1) as u64;
+
+    let mut bytes = [0; 16];
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    bytes[6] = 0x70;
+    bytes[8] = 0x80;
+    Uuid::from_bytes(bytes)
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 #[serde_with::serde_as]
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct UuidFilterSet(#[serde_as(as = "EnumMap")] pub(crate) Vec<UuidFilter>);
 
 impl UuidFilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn push(&mut self, value: UuidFilter) {
         self.0.push(value);
     }
@@ -25,20 +135,160 @@ impl UuidFilterSet {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, UuidFilter> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[UuidFilter] {
+        &self.0
+    }
+
+    pub fn eq(mut self, value: Uuid) -> Self {
+        self.push(UuidFilter::Equals(value));
+        self
+    }
+
+    pub fn neq(mut self, value: Uuid) -> Self {
+        self.push(UuidFilter::NotEquals(value));
+        self
+    }
+
+    pub fn lt(mut self, value: Uuid) -> Self {
+        self.push(UuidFilter::LesserThan(value));
+        self
+    }
+
+    pub fn lte(mut self, value: Uuid) -> Self {
+        self.push(UuidFilter::LesserThanEqual(value));
+        self
+    }
+
+    pub fn gt(mut self, value: Uuid) -> Self {
+        self.push(UuidFilter::GreaterThan(value));
+        self
+    }
+
+    pub fn gte(mut self, value: Uuid) -> Self {
+        self.push(UuidFilter::GreaterThanEqual(value));
+        self
+    }
+
+    /// UUIDv7 keys generated at or after `time`, see [`uuid_v7_lower_bound`]
+    pub fn created_after<Tz: TimeZone>(self, time: &DateTime<Tz>) -> Self {
+        self.gte(uuid_v7_lower_bound(time))
+    }
+
+    /// UUIDv7 keys generated before `time`, see [`uuid_v7_lower_bound`]
+    pub fn created_before<Tz: TimeZone>(self, time: &DateTime<Tz>) -> Self {
+        self.lt(uuid_v7_lower_bound(time))
+    }
+
+    pub fn is_in(mut self, values: impl IntoIterator<Item = Uuid>) -> Self {
+        self.push(UuidFilter::In(values.into_iter().collect()));
+        self
+    }
+
+    pub fn not_in(mut self, values: impl IntoIterator<Item = Uuid>) -> Self {
+        self.push(UuidFilter::NotIn(values.into_iter().collect()));
+        self
+    }
+
+    pub fn is_null(mut self, value: bool) -> Self {
+        self.push(UuidFilter::Null(value));
+        self
+    }
+
+    pub fn prefix(mut self, value: UuidPrefix) -> Self {
+        self.push(UuidFilter::Prefix(value));
+        self
+    }
+}
+
+impl FromIterator<UuidFilter> for UuidFilterSet {
+    fn from_iter<I: IntoIterator<Item = UuidFilter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<UuidFilter> for UuidFilterSet {
+    fn extend<I: IntoIterator<Item = UuidFilter>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for UuidFilterSet {
+    type Item = UuidFilter;
+    type IntoIter = std::vec::IntoIter<UuidFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s UuidFilterSet {
+    type Item = &'s UuidFilter;
+    type IntoIter = std::slice::Iter<'s, UuidFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(feature = "openapi")]
-impl<'__s> utoipa::ToSchema<'__s> for UuidFilterSet {
-    fn schema() -> (
-        &'__s str,
-        utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
-    ) {
-        (
-            "UuidFilterSet",
-            utoipa::openapi::schema::ArrayBuilder::new()
-                .items(UuidFilter::schema().1)
-                .into(),
-        )
+mod openapi {
+    use utoipa::openapi::{
+        schema::Schema, ArrayBuilder, ObjectBuilder, RefOr, SchemaFormat, SchemaType,
+    };
+    use utoipa::ToSchema;
+
+    use super::{UuidFilter, UuidFilterSet};
+
+    fn uuid() -> ObjectBuilder {
+        ObjectBuilder::new()
+            .schema_type(SchemaType::String)
+            .format(Some(SchemaFormat::Custom("uuid".into())))
+    }
+
+    impl<'__s> ToSchema<'__s> for UuidFilter {
+        fn schema() -> (&'__s str, RefOr<Schema>) {
+            (
+                "UuidFilter",
+                ObjectBuilder::new()
+                    .property("eq", uuid())
+                    .property("neq", uuid())
+                    .property("lt", uuid())
+                    .property("lte", uuid())
+                    .property("gt", uuid())
+                    .property("gte", uuid())
+                    .property("in", ArrayBuilder::new().items(uuid()))
+                    .property("nin", ArrayBuilder::new().items(uuid()))
+                    .property(
+                        "null",
+                        ObjectBuilder::new().schema_type(SchemaType::Boolean),
+                    )
+                    .property(
+                        "prefix",
+                        ObjectBuilder::new()
+                            .schema_type(SchemaType::String)
+                            .pattern(Some("^[0-9a-fA-F-]+$")),
+                    )
+                    .into(),
+            )
+        }
+    }
+
+    impl<'__s> ToSchema<'__s> for UuidFilterSet {
+        fn schema() -> (&'__s str, RefOr<Schema>) {
+            (
+                "UuidFilterSet",
+                ArrayBuilder::new().items(UuidFilter::schema().1).into(),
+            )
+        }
     }
 }
 
@@ -47,21 +297,22 @@ mod tests {
     use std::collections::BTreeSet;
     use std::iter::FromIterator;
 
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use serde_querystring::de::{from_str, ParseMode};
     use uuid::uuid;
 
     use super::UuidFilter::*;
-    use super::UuidFilterSet;
+    use super::{UuidFilterSet, UuidPrefix};
+    use crate::to_querystring;
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct Sample {
         id: UuidFilterSet,
     }
 
     #[test]
     fn deserialize() {
-        const QUERY: &'static str = "id[in]=23191e01-8af8-4381-848c-f9387116d132\
+        const QUERY: &str = "id[in]=23191e01-8af8-4381-848c-f9387116d132\
                                     &id[in]=23191e01-8af8-4381-848c-f9387116d132\
                                     &id[eq]=23191e01-8af8-4381-848c-f9387116d132";
 
@@ -79,11 +330,150 @@ mod tests {
             BTreeSet::from_iter(id.0.iter())
         );
     }
+
+    #[test]
+    fn deserialize_lists() {
+        const QUERY: &str = "id[in]=23191e01-8af8-4381-848c-f9387116d132,\
+                             23191e018af84381848cf9387116d133\
+                             &id[nin]=urn:uuid:23191E01-8AF8-4381-848C-F9387116D134\
+                             &id[neq]=23191e01-8af8-4381-848c-f9387116d135\
+                             &id[null]=false";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let id = UuidFilterSet::new()
+            .is_in([
+                uuid!("23191e01-8af8-4381-848c-f9387116d132"),
+                uuid!("23191e01-8af8-4381-848c-f9387116d133"),
+            ])
+            .not_in([uuid!("23191e01-8af8-4381-848c-f9387116d134")])
+            .neq(uuid!("23191e01-8af8-4381-848c-f9387116d135"))
+            .is_null(false);
+
+        assert_eq!(
+            BTreeSet::from_iter(res.id.0.iter()),
+            BTreeSet::from_iter(id.0.iter())
+        );
+
+        assert!(from_str::<Sample>("id[in]=23191e01,nope", ParseMode::Brackets).is_err());
+    }
+
+    #[test]
+    fn deserialize_ordering() {
+        const QUERY: &str = "id[gt]=017e12ef-9c00-7000-8000-000000000000\
+                             &id[lte]=017e12ef9c0070008000000000000001";
+
+        let res = from_str::<Sample>(QUERY, ParseMode::Brackets).unwrap();
+
+        let id = UuidFilterSet::new()
+            .gt(uuid!("017e12ef-9c00-7000-8000-000000000000"))
+            .lte(uuid!("017e12ef-9c00-7000-8000-000000000001"));
+        assert_eq!(
+            BTreeSet::from_iter(res.id.0.iter()),
+            BTreeSet::from_iter(id.0.iter())
+        );
+    }
+
+    #[test]
+    fn deserialize_prefix() {
+        let res = from_str::<Sample>("id[prefix]=0190A3-0c1", ParseMode::Brackets).unwrap();
+        assert_eq!(
+            res.id.as_slice(),
+            &[Prefix(UuidPrefix::new("0190a30c1").unwrap())]
+        );
+        assert_eq!(UuidPrefix::new("0190A30c1").unwrap().as_str(), "0190a30c-1");
+
+        for query in [
+            "id[prefix]=",
+            "id[prefix]=xyz",
+            "id[prefix]=00000000-0000-0000-0000-0000000000000",
+        ] {
+            let err = from_str::<Sample>(query, ParseMode::Brackets).unwrap_err();
+            assert!(
+                err.to_string().contains("isn't the start of a UUID"),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn v7_lower_bound() {
+        use chrono::{TimeZone, Utc};
+
+        use super::uuid_v7_lower_bound;
+
+        let time = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let bound = uuid_v7_lower_bound(&time);
+        assert_eq!(bound, uuid!("017e12ef-9c00-7000-8000-000000000000"));
+        assert_eq!(bound.get_version_num(), 7);
+        assert_eq!(bound.get_variant(), uuid::Variant::RFC4122);
+
+        let later = uuid_v7_lower_bound(&(time + chrono::Duration::milliseconds(1)));
+        assert!(later > uuid!("017e12ef-9c00-7fff-bfff-ffffffffffff"));
+
+        let before_epoch = Utc.with_ymd_and_hms(1960, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            uuid_v7_lower_bound(&before_epoch),
+            uuid!("00000000-0000-7000-8000-000000000000")
+        );
+
+        assert_eq!(
+            UuidFilterSet::new().created_after(&time).as_slice(),
+            &[GreaterThanEqual(bound)]
+        );
+        assert_eq!(
+            UuidFilterSet::new().created_before(&time).as_slice(),
+            &[LesserThan(bound)]
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let mut id = UuidFilterSet::default();
+        id.push(Equals(uuid!("23191e01-8af8-4381-848c-f9387116d132")));
+        id.push(In(vec![
+            uuid!("00000000-0000-0000-0000-ffff00000001"),
+            uuid!("00000000-0000-0000-0000-ffff00000002"),
+        ]));
+        let sample = Sample { id };
+
+        let query = to_querystring(&sample).unwrap();
+        assert_eq!(
+            query,
+            "id[eq]=23191e01-8af8-4381-848c-f9387116d132\
+             &id[in]=00000000-0000-0000-0000-ffff00000001\
+             &id[in]=00000000-0000-0000-0000-ffff00000002"
+        );
+
+        let res = from_str::<Sample>(&query, ParseMode::Brackets).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(res.id.0.iter()),
+            BTreeSet::from_iter(sample.id.0.iter())
+        );
+    }
+
+    #[test]
+    fn build() {
+        let first = uuid!("00000000-0000-0000-0000-ffff00000001");
+        let second = uuid!("00000000-0000-0000-0000-ffff00000002");
+        let set = UuidFilterSet::new().eq(first).is_in([first, second]);
+        assert_eq!(set.as_slice(), &[Equals(first), In(vec![first, second])]);
+
+        let mut set = set
+            .into_iter()
+            .filter(|filter| matches!(filter, In(_)))
+            .collect::<UuidFilterSet>();
+        set.extend([Equals(second)]);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![&In(vec![first, second]), &Equals(second)]
+        );
+    }
 }
 
 #[cfg(feature = "seaq")]
 mod seaq {
-    use sea_query::{Cond, Expr, IntoColumnRef, IntoCondition};
+    use sea_query::{Alias, Cond, Expr, IntoColumnRef, IntoCondition};
 
     use super::{UuidFilter, UuidFilterSet};
     use crate::seaq::ToFieldCond;
@@ -92,7 +482,23 @@ mod seaq {
         fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond> {
             Some(match self {
                 UuidFilter::Equals(val) => Expr::col(iden).eq(*val).into_condition(),
+                UuidFilter::NotEquals(val) => Expr::col(iden).ne(*val).into_condition(),
+                UuidFilter::LesserThan(val) => Expr::col(iden).lt(*val).into_condition(),
+                UuidFilter::LesserThanEqual(val) => Expr::col(iden).lte(*val).into_condition(),
+                UuidFilter::GreaterThan(val) => Expr::col(iden).gt(*val).into_condition(),
+                UuidFilter::GreaterThanEqual(val) => Expr::col(iden).gte(*val).into_condition(),
                 UuidFilter::In(val) => Expr::col(iden).is_in(val.iter().copied()).into_condition(),
+                UuidFilter::NotIn(val) => Expr::col(iden)
+                    .is_not_in(val.iter().copied())
+                    .into_condition(),
+                UuidFilter::Null(true) => Expr::col(iden).is_null().into_condition(),
+                UuidFilter::Null(false) => Expr::col(iden).is_not_null().into_condition(),
+                // Hex digits and hyphens only, nothing `LIKE` would take for a wildcard
+                UuidFilter::Prefix(prefix) => {
+                    Expr::expr(Expr::col(iden).cast_as(Alias::new("text")))
+                        .like(format!("{}%", prefix))
+                        .into_condition()
+                }
             })
         }
     }
@@ -115,7 +521,10 @@ mod seaq {
         use uuid::uuid;
 
         use super::UuidFilter::*;
-        use crate::{filters::UuidFilterSet, test_utils::check_query};
+        use crate::{
+            filters::{UuidFilterSet, UuidPrefix},
+            test_utils::check_query,
+        };
 
         #[test]
         fn test_eq() {
@@ -140,6 +549,61 @@ mod seaq {
             );
         }
 
+        #[test]
+        fn test_neq() {
+            check_query(
+                NotEquals(uuid!("00000000-0000-0000-0000-ffff00000001")),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" <> '00000000-0000-0000-0000-ffff00000001'"#,
+            );
+        }
+
+        #[test]
+        fn test_range() {
+            let set = UuidFilterSet::new()
+                .gt(uuid!("017e12ef-9c00-7000-8000-000000000000"))
+                .lte(uuid!("017e12ef-9c00-7000-8000-000000000001"));
+
+            check_query(
+                set,
+                "SELECT \"image\" FROM \"glyph\" WHERE \
+                \"aspect\" > '017e12ef-9c00-7000-8000-000000000000' AND \
+                \"aspect\" <= '017e12ef-9c00-7000-8000-000000000001'",
+            );
+        }
+
+        #[test]
+        fn test_not_in() {
+            check_query(
+                NotIn(vec![
+                    uuid!("00000000-0000-0000-0000-ffff00000001"),
+                    uuid!("00000000-0000-0000-0000-ffff00000002"),
+                ]),
+                "SELECT \"image\" FROM \"glyph\" WHERE \"aspect\" NOT IN (\
+                    '00000000-0000-0000-0000-ffff00000001', \
+                    '00000000-0000-0000-0000-ffff00000002')",
+            );
+        }
+
+        #[test]
+        fn test_null() {
+            check_query(
+                Null(true),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" IS NULL"#,
+            );
+            check_query(
+                Null(false),
+                r#"SELECT "image" FROM "glyph" WHERE "aspect" IS NOT NULL"#,
+            );
+        }
+
+        #[test]
+        fn test_prefix() {
+            check_query(
+                Prefix(UuidPrefix::new("0190a30c1").unwrap()),
+                r#"SELECT "image" FROM "glyph" WHERE CAST("aspect" AS text) LIKE '0190a30c-1%'"#,
+            );
+        }
+
         #[test]
         fn test_set() {
             let mut set = UuidFilterSet::default();
@@ -157,3 +621,120 @@ mod seaq {
         }
     }
 }
+
+mod memory {
+    use uuid::Uuid;
+
+    use super::{UuidFilter, UuidFilterSet};
+    use crate::memory::Matches;
+
+    impl Matches<Uuid> for UuidFilter {
+        fn matches(&self, value: &Uuid) -> bool {
+            match self {
+                UuidFilter::Equals(val) => value == val,
+                UuidFilter::NotEquals(val) => value != val,
+                UuidFilter::LesserThan(val) => value < val,
+                UuidFilter::LesserThanEqual(val) => value <= val,
+                UuidFilter::GreaterThan(val) => value > val,
+                UuidFilter::GreaterThanEqual(val) => value >= val,
+                UuidFilter::In(val) => val.contains(value),
+                UuidFilter::NotIn(val) => !val.contains(value),
+                UuidFilter::Null(null) => !null,
+                UuidFilter::Prefix(prefix) => value.to_string().starts_with(prefix.as_str()),
+            }
+        }
+    }
+
+    /// Like SQL, a missing value only passes `null`, comparing it to anything is never true
+    impl Matches<Option<Uuid>> for UuidFilter {
+        fn matches(&self, value: &Option<Uuid>) -> bool {
+            match (self, value) {
+                (UuidFilter::Null(null), value) => *null == value.is_none(),
+                (filter, Some(value)) => filter.matches(value),
+                (_, None) => false,
+            }
+        }
+    }
+
+    impl Matches<Uuid> for UuidFilterSet {
+        fn matches(&self, value: &Uuid) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    impl Matches<Option<Uuid>> for UuidFilterSet {
+        fn matches(&self, value: &Option<Uuid>) -> bool {
+            self.0.iter().all(|filter| filter.matches(value))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use uuid::{uuid, Uuid};
+
+        use super::UuidFilter::*;
+        use crate::{
+            filters::{UuidFilterSet, UuidPrefix},
+            memory::Matches,
+        };
+
+        #[test]
+        fn test_matches() {
+            let first = uuid!("00000000-0000-0000-0000-ffff00000001");
+            let second = uuid!("00000000-0000-0000-0000-ffff00000002");
+
+            assert!(Equals(first).matches(&first));
+            assert!(!Equals(first).matches(&second));
+            assert!(In(vec![first, second]).matches(&second));
+            assert!(!In(vec![]).matches(&second));
+
+            let set = UuidFilterSet::new().is_in([first, second]).eq(first);
+            assert!(set.matches(&first));
+            assert!(!set.matches(&second));
+        }
+
+        #[test]
+        fn test_matches_prefix() {
+            let id = uuid!("0190a30c-1b2c-7000-8000-000000000000");
+            assert!(Prefix(UuidPrefix::new("0190A30C1b").unwrap()).matches(&id));
+            assert!(!Prefix(UuidPrefix::new("0190a30d").unwrap()).matches(&id));
+        }
+
+        #[test]
+        fn test_matches_range() {
+            let first = uuid!("017e12ef-9c00-7000-8000-000000000000");
+            let second = uuid!("017e12ef-9c01-7000-8000-000000000000");
+
+            assert!(LesserThan(second).matches(&first));
+            assert!(!LesserThan(first).matches(&first));
+            assert!(LesserThanEqual(first).matches(&first));
+            assert!(GreaterThan(first).matches(&second));
+            assert!(!GreaterThan(second).matches(&second));
+            assert!(GreaterThanEqual(second).matches(&second));
+            assert!(!GreaterThan(first).matches(&None::<Uuid>));
+        }
+
+        #[test]
+        fn test_matches_exclusions() {
+            let first = uuid!("00000000-0000-0000-0000-ffff00000001");
+            let second = uuid!("00000000-0000-0000-0000-ffff00000002");
+
+            assert!(NotEquals(first).matches(&second));
+            assert!(!NotEquals(first).matches(&first));
+            assert!(NotIn(vec![first]).matches(&second));
+            assert!(!NotIn(vec![first, second]).matches(&second));
+            assert!(!Null(true).matches(&first));
+            assert!(Null(false).matches(&first));
+
+            assert!(Null(true).matches(&None::<Uuid>));
+            assert!(!Null(false).matches(&None::<Uuid>));
+            assert!(!NotEquals(first).matches(&None::<Uuid>));
+            assert!(Null(false).matches(&Some(first)));
+
+            let set = UuidFilterSet::new().is_null(false).neq(first);
+            assert!(set.matches(&Some(second)));
+            assert!(!set.matches(&Some(first)));
+            assert!(!set.matches(&None::<Uuid>));
+        }
+    }
+}
@@ -0,0 +1,1206 @@
+//! async-graphql input types for the filter sets and `QueryFilter`
+//!
+//! Each portable filter set has an input object with one optional field per operator, like
+//! `NumberFilterInput { eq, neq, lt, lte, gt, gte, in }`, which converts into the filter set. A
+//! filter struct implements [`GraphQLFilter`] to name its own input object, built from those, and
+//! [`QueryFilterInput`] then takes it along with `start`/`end` or `page`/`perPage`, `order`, `q`,
+//! `fields` and a `sort` enum listing [`Filter::SORTABLE_FIELDS`]. It converts into a
+//! `QueryFilter`, ready for `ApplyFilters`.
+//!
+//! The sets behind a feature get their inputs with it: `ArrayFilterInput<T>`, as
+//! `StringArrayFilterInput`, `IntArrayFilterInput` or `UuidArrayFilterInput`, `IpFilterInput`,
+//! `JsonFilterInput<P>`, `GeoFilterInput` and `SearchFilterInput`. Values which aren't plain
+//! GraphQL types are scalars parsed like their querystring form, `Near` as `"lat,lng,radius"` for
+//! instance, and `JsonFilterInput<P>` refuses paths outside `P::PATHS` as deserializing does.
+
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use async_graphql::{
+    indexmap::IndexMap,
+    registry::{Deprecation, MetaEnumValue, MetaInputValue, MetaType, MetaTypeId, Registry},
+    Enum, InputObject, InputType, InputValueError, InputValueResult, Name, Value,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+#[cfg(feature = "regex")]
+use crate::filters::RegexPattern;
+#[cfg(feature = "postgres")]
+use crate::filters::{ArrayFilter, ArrayFilterSet, SearchFilter, SearchFilterSet};
+#[cfg(feature = "postgis")]
+use crate::filters::{BBox, GeoFilter, GeoFilterSet, Near};
+use crate::filters::{
+    DateFilter, DateFilterSet, DateTimeFilter, DateTimeFilterSet, DateTimeTzFilter,
+    DateTimeTzFilterSet, NumberFilter, NumberFilterSet, StringFilter, StringFilterSet, UuidFilter,
+    UuidFilterSet, UuidPrefix,
+};
+#[cfg(feature = "ipnetwork")]
+use crate::filters::{IpFilter, IpFilterSet};
+#[cfg(feature = "json")]
+use crate::filters::{JsonFilter, JsonFilterSet, JsonPaths};
+use crate::{Filter, Order, QueryFilter};
+
+#[derive(Debug, Default, InputObject)]
+pub struct StringFilterInput {
+    pub eq: Option<String>,
+    pub neq: Option<String>,
+    pub contains: Option<String>,
+    pub not_contains: Option<String>,
+    pub starts_with: Option<String>,
+    pub ends_with: Option<String>,
+    pub like: Option<String>,
+    pub ilike: Option<String>,
+    #[graphql(name = "in")]
+    pub is_in: Option<Vec<String>>,
+    #[cfg(feature = "regex")]
+    pub regex: Option<RegexPattern<'static>>,
+    #[cfg(feature = "regex")]
+    pub iregex: Option<RegexPattern<'static>>,
+    #[cfg(feature = "regex")]
+    pub not_regex: Option<RegexPattern<'static>>,
+}
+
+impl From<StringFilterInput> for StringFilterSet<'static> {
+    fn from(input: StringFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.eq.map(|value| StringFilter::Equals(value.into())));
+        set.extend(input.neq.map(|value| StringFilter::NotEquals(value.into())));
+        set.extend(
+            input
+                .contains
+                .map(|value| StringFilter::Contains(value.into())),
+        );
+        set.extend(
+            input
+                .not_contains
+                .map(|value| StringFilter::NotContains(value.into())),
+        );
+        set.extend(
+            input
+                .starts_with
+                .map(|value| StringFilter::StartsWith(value.into())),
+        );
+        set.extend(
+            input
+                .ends_with
+                .map(|value| StringFilter::EndsWith(value.into())),
+        );
+        set.extend(input.like.map(|value| StringFilter::Like(value.into())));
+        set.extend(input.ilike.map(|value| StringFilter::ILike(value.into())));
+        set.extend(
+            input
+                .is_in
+                .map(|values| StringFilter::In(values.into_iter().map(Into::into).collect())),
+        );
+        #[cfg(feature = "regex")]
+        {
+            set.extend(input.regex.map(StringFilter::Regex));
+            set.extend(input.iregex.map(StringFilter::IRegex));
+            set.extend(input.not_regex.map(StringFilter::NotRegex));
+        }
+        set
+    }
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct NumberFilterInput {
+    pub eq: Option<i64>,
+    pub neq: Option<i64>,
+    pub lt: Option<i64>,
+    pub lte: Option<i64>,
+    pub gt: Option<i64>,
+    pub gte: Option<i64>,
+    #[graphql(name = "in")]
+    pub is_in: Option<Vec<i64>>,
+}
+
+impl From<NumberFilterInput> for NumberFilterSet {
+    fn from(input: NumberFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.eq.map(NumberFilter::Equals));
+        set.extend(input.neq.map(NumberFilter::NotEquals));
+        set.extend(input.lt.map(NumberFilter::LesserThan));
+        set.extend(input.lte.map(NumberFilter::LesserThanEqual));
+        set.extend(input.gt.map(NumberFilter::GreaterThan));
+        set.extend(input.gte.map(NumberFilter::GreaterThanEqual));
+        set.extend(input.is_in.map(NumberFilter::In));
+        set
+    }
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct UuidFilterInput {
+    pub eq: Option<Uuid>,
+    pub neq: Option<Uuid>,
+    pub lt: Option<Uuid>,
+    pub lte: Option<Uuid>,
+    pub gt: Option<Uuid>,
+    pub gte: Option<Uuid>,
+    #[graphql(name = "in")]
+    pub is_in: Option<Vec<Uuid>>,
+    pub nin: Option<Vec<Uuid>>,
+    /// `true` for `IS NULL`, `false` for `IS NOT NULL`
+    pub null: Option<bool>,
+    pub prefix: Option<UuidPrefix>,
+}
+
+impl From<UuidFilterInput> for UuidFilterSet {
+    fn from(input: UuidFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.eq.map(UuidFilter::Equals));
+        set.extend(input.neq.map(UuidFilter::NotEquals));
+        set.extend(input.lt.map(UuidFilter::LesserThan));
+        set.extend(input.lte.map(UuidFilter::LesserThanEqual));
+        set.extend(input.gt.map(UuidFilter::GreaterThan));
+        set.extend(input.gte.map(UuidFilter::GreaterThanEqual));
+        set.extend(input.is_in.map(UuidFilter::In));
+        set.extend(input.nin.map(UuidFilter::NotIn));
+        set.extend(input.null.map(UuidFilter::Null));
+        set.extend(input.prefix.map(UuidFilter::Prefix));
+        set
+    }
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct DateFilterInput {
+    pub before: Option<NaiveDate>,
+    pub after: Option<NaiveDate>,
+    pub eq: Option<NaiveDate>,
+    pub neq: Option<NaiveDate>,
+    pub lte: Option<NaiveDate>,
+    pub gt: Option<NaiveDate>,
+    pub gte: Option<NaiveDate>,
+}
+
+impl From<DateFilterInput> for DateFilterSet {
+    fn from(input: DateFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.before.map(DateFilter::Before));
+        set.extend(input.after.map(DateFilter::After));
+        set.extend(input.eq.map(DateFilter::Equals));
+        set.extend(input.neq.map(DateFilter::NotEquals));
+        set.extend(input.lte.map(DateFilter::LesserThanEqual));
+        set.extend(input.gt.map(DateFilter::GreaterThan));
+        set.extend(input.gte.map(DateFilter::GreaterThanEqual));
+        set
+    }
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct DateTimeFilterInput {
+    pub before: Option<NaiveDateTime>,
+    pub after: Option<NaiveDateTime>,
+    pub eq: Option<NaiveDateTime>,
+    pub neq: Option<NaiveDateTime>,
+    pub lte: Option<NaiveDateTime>,
+    pub gt: Option<NaiveDateTime>,
+    pub gte: Option<NaiveDateTime>,
+}
+
+impl From<DateTimeFilterInput> for DateTimeFilterSet {
+    fn from(input: DateTimeFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.before.map(DateTimeFilter::Before));
+        set.extend(input.after.map(DateTimeFilter::After));
+        set.extend(input.eq.map(DateTimeFilter::Equals));
+        set.extend(input.neq.map(DateTimeFilter::NotEquals));
+        set.extend(input.lte.map(DateTimeFilter::LesserThanEqual));
+        set.extend(input.gt.map(DateTimeFilter::GreaterThan));
+        set.extend(input.gte.map(DateTimeFilter::GreaterThanEqual));
+        set
+    }
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct DateTimeTzFilterInput {
+    pub before: Option<DateTime<FixedOffset>>,
+    pub after: Option<DateTime<FixedOffset>>,
+    pub eq: Option<DateTime<FixedOffset>>,
+    pub neq: Option<DateTime<FixedOffset>>,
+    pub lte: Option<DateTime<FixedOffset>>,
+    pub gt: Option<DateTime<FixedOffset>>,
+    pub gte: Option<DateTime<FixedOffset>>,
+}
+
+impl From<DateTimeTzFilterInput> for DateTimeTzFilterSet {
+    fn from(input: DateTimeTzFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.before.map(DateTimeTzFilter::Before));
+        set.extend(input.after.map(DateTimeTzFilter::After));
+        set.extend(input.eq.map(DateTimeTzFilter::Equals));
+        set.extend(input.neq.map(DateTimeTzFilter::NotEquals));
+        set.extend(input.lte.map(DateTimeTzFilter::LesserThanEqual));
+        set.extend(input.gt.map(DateTimeTzFilter::GreaterThan));
+        set.extend(input.gte.map(DateTimeTzFilter::GreaterThanEqual));
+        set
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[derive(Debug, InputObject)]
+#[graphql(concrete(name = "StringArrayFilterInput", params(String)))]
+#[graphql(concrete(name = "IntArrayFilterInput", params(i64)))]
+#[graphql(concrete(name = "UuidArrayFilterInput", params(Uuid)))]
+pub struct ArrayFilterInput<T: InputType> {
+    pub contains: Option<Vec<T>>,
+    pub contained_by: Option<Vec<T>>,
+    pub overlaps: Option<Vec<T>>,
+    pub any: Option<T>,
+    pub length: Option<NumberFilterInput>,
+}
+
+#[cfg(feature = "postgres")]
+impl<T: InputType> From<ArrayFilterInput<T>> for ArrayFilterSet<T> {
+    fn from(input: ArrayFilterInput<T>) -> Self {
+        let mut set = Self::new();
+        set.extend(input.contains.map(ArrayFilter::Contains));
+        set.extend(input.contained_by.map(ArrayFilter::ContainedBy));
+        set.extend(input.overlaps.map(ArrayFilter::Overlaps));
+        set.extend(input.any.map(ArrayFilter::Any));
+        set.extend(
+            input
+                .length
+                .map(|length| ArrayFilter::Length(length.into())),
+        );
+        set
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+#[derive(Debug, Default, InputObject)]
+pub struct IpFilterInput {
+    pub eq: Option<IpAddrInput>,
+    #[graphql(name = "in")]
+    pub is_in: Option<Vec<IpAddrInput>>,
+    pub within: Option<IpNetworkInput>,
+}
+
+#[cfg(feature = "ipnetwork")]
+impl From<IpFilterInput> for IpFilterSet {
+    fn from(input: IpFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.eq.map(|addr| IpFilter::Equals(addr.0)));
+        set.extend(
+            input
+                .is_in
+                .map(|addrs| IpFilter::In(addrs.into_iter().map(|addr| addr.0).collect())),
+        );
+        set.extend(input.within.map(|network| IpFilter::Within(network.0)));
+        set
+    }
+}
+
+/// An address as the `IpAddr` scalar, `"10.0.0.1"` or `"::1"`
+#[cfg(feature = "ipnetwork")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpAddrInput(pub std::net::IpAddr);
+
+/// A subnet as the `IpNetwork` scalar, `"10.0.0.0/8"`
+#[cfg(feature = "ipnetwork")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetworkInput(pub ipnetwork::IpNetwork);
+
+/// A path and the text its value must equal
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, InputObject)]
+pub struct JsonPathInput {
+    pub path: String,
+    pub value: String,
+}
+
+/// A path and the number its value is compared to
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, InputObject)]
+pub struct JsonNumberInput {
+    pub path: String,
+    pub value: f64,
+}
+
+/// The operators of [`JsonFilterInput`], the same input object whatever its paths
+#[cfg(feature = "json")]
+#[derive(Debug, Default, InputObject)]
+#[graphql(name = "JsonFilterInput")]
+pub struct JsonFilterFields {
+    pub path: Option<Vec<JsonPathInput>>,
+    pub has: Option<String>,
+    pub contains: Option<async_graphql::Json<serde_json::Value>>,
+    pub lt: Option<Vec<JsonNumberInput>>,
+    pub lte: Option<Vec<JsonNumberInput>>,
+    pub gt: Option<Vec<JsonNumberInput>>,
+    pub gte: Option<Vec<JsonNumberInput>>,
+}
+
+#[cfg(feature = "json")]
+impl JsonFilterFields {
+    fn filters(&self) -> Vec<JsonFilter<'static>> {
+        let numbers = |numbers: &Vec<JsonNumberInput>| {
+            numbers
+                .iter()
+                .map(|number| (number.path.clone().into(), number.value))
+                .collect()
+        };
+
+        let mut filters = Vec::new();
+        filters.extend(self.path.as_ref().map(|paths| {
+            JsonFilter::Path(
+                paths
+                    .iter()
+                    .map(|path| (path.path.clone().into(), path.value.clone().into()))
+                    .collect(),
+            )
+        }));
+        filters.extend(self.has.clone().map(|path| JsonFilter::HasKey(path.into())));
+        filters.extend(
+            self.contains
+                .as_ref()
+                .map(|value| JsonFilter::Contains(value.0.clone())),
+        );
+        filters.extend(self.lt.as_ref().map(numbers).map(JsonFilter::LesserThan));
+        filters.extend(
+            self.lte
+                .as_ref()
+                .map(numbers)
+                .map(JsonFilter::LesserThanEqual),
+        );
+        filters.extend(self.gt.as_ref().map(numbers).map(JsonFilter::GreaterThan));
+        filters.extend(
+            self.gte
+                .as_ref()
+                .map(numbers)
+                .map(JsonFilter::GreaterThanEqual),
+        );
+        filters
+    }
+}
+
+/// `JsonFilterInput`, whose paths are checked against `P::PATHS` as a `JsonFilterSet<P>`'s are
+/// when deserialized
+#[cfg(feature = "json")]
+pub struct JsonFilterInput<P> {
+    pub fields: JsonFilterFields,
+    paths: PhantomData<fn() -> P>,
+}
+
+#[cfg(feature = "json")]
+impl<P> fmt::Debug for JsonFilterInput<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("JsonFilterInput")
+            .field(&self.fields)
+            .finish()
+    }
+}
+
+#[cfg(feature = "json")]
+impl<P: JsonPaths> InputType for JsonFilterInput<P> {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        JsonFilterFields::type_name()
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        JsonFilterFields::create_type_info(registry)
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        let fields = JsonFilterFields::parse(value).map_err(InputValueError::propagate)?;
+        let filters = fields.filters();
+        if let Some(path) = filters
+            .iter()
+            .find_map(|filter| filter.disallowed_path(P::PATHS))
+        {
+            return Err(InputValueError::custom(format!(
+                "unknown JSON path `{path}`"
+            )));
+        }
+        if let Some(path) = filters.iter().find_map(JsonFilter::non_finite_path) {
+            return Err(InputValueError::custom(format!(
+                "JSON path `{path}` must be compared to a finite number"
+            )));
+        }
+        Ok(Self {
+            fields,
+            paths: PhantomData,
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        self.fields.to_value()
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<P> From<JsonFilterInput<P>> for JsonFilterSet<'static, P> {
+    fn from(input: JsonFilterInput<P>) -> Self {
+        input.fields.filters().into_iter().collect()
+    }
+}
+
+#[cfg(feature = "postgis")]
+#[derive(Debug, Default, InputObject)]
+pub struct GeoFilterInput {
+    pub near: Option<Near>,
+    pub bbox: Option<BBox>,
+}
+
+#[cfg(feature = "postgis")]
+impl From<GeoFilterInput> for GeoFilterSet {
+    fn from(input: GeoFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.near.map(GeoFilter::Near));
+        set.extend(input.bbox.map(GeoFilter::BBox));
+        set
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[derive(Debug, Default, InputObject)]
+pub struct SearchFilterInput {
+    pub search: Option<String>,
+    pub phrase: Option<String>,
+    pub plain: Option<String>,
+}
+
+#[cfg(feature = "postgres")]
+impl From<SearchFilterInput> for SearchFilterSet<'static> {
+    fn from(input: SearchFilterInput) -> Self {
+        let mut set = Self::new();
+        set.extend(input.search.map(|value| SearchFilter::Search(value.into())));
+        set.extend(input.phrase.map(|value| SearchFilter::Phrase(value.into())));
+        set.extend(input.plain.map(|value| SearchFilter::Plain(value.into())));
+        set
+    }
+}
+
+/// Patterns are checked like the querystring ones, a refused pattern fails the input
+#[cfg(feature = "regex")]
+#[async_graphql::Scalar(name = "Regex")]
+impl async_graphql::ScalarType for RegexPattern<'static> {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(pattern) => Ok(RegexPattern::new(pattern)?),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.as_str().to_owned())
+    }
+}
+
+/// Up to 32 hex digits, hyphens allowed, like the querystring's `id[prefix]`
+#[async_graphql::Scalar(name = "UuidPrefix")]
+impl async_graphql::ScalarType for UuidPrefix {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(prefix) => UuidPrefix::new(&prefix).ok_or_else(|| {
+                InputValueError::custom(format!(
+                    "`{prefix}` isn't the start of a UUID, expected up to 32 hex digits"
+                ))
+            }),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.as_str().to_owned())
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+#[async_graphql::Scalar(name = "IpAddr")]
+impl async_graphql::ScalarType for IpAddrInput {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(addr) => Ok(Self(addr.parse()?)),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+#[async_graphql::Scalar(name = "IpNetwork")]
+impl async_graphql::ScalarType for IpNetworkInput {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(network) => Ok(Self(network.parse()?)),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+/// `"lat,lng,radius"`, the radius being in meters
+#[cfg(feature = "postgis")]
+#[async_graphql::Scalar(name = "Near")]
+impl async_graphql::ScalarType for Near {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(near) => Ok(near.parse()?),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// `"minx,miny,maxx,maxy"`, the south-west and north-east corners as `lng,lat`
+#[cfg(feature = "postgis")]
+#[async_graphql::Scalar(name = "BBox")]
+impl async_graphql::ScalarType for BBox {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(bbox) => Ok(bbox.parse()?),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// A filter struct given as a GraphQL input, through [`QueryFilterInput`]
+pub trait GraphQLFilter: Filter + Sized {
+    /// Prefix of the types made for it, `User` gives `UserQuery` and `UserSort`
+    const NAME: &'static str;
+
+    /// The input object clients send, made of the `*FilterInput` types
+    type Input: InputType + Into<Self>;
+}
+
+/// `ASC` or `DESC`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[graphql(name = "Order")]
+pub enum OrderInput {
+    Asc,
+    Desc,
+}
+
+impl From<OrderInput> for Order {
+    fn from(order: OrderInput) -> Self {
+        match order {
+            OrderInput::Asc => Order::Asc,
+            OrderInput::Desc => Order::Desc,
+        }
+    }
+}
+
+/// One of `T::SORTABLE_FIELDS`, as the `{NAME}Sort` enum with upper-cased values
+pub struct SortField<T> {
+    field: &'static str,
+    filter: PhantomData<fn() -> T>,
+}
+
+impl<T> SortField<T> {
+    pub fn as_str(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl<T> fmt::Debug for SortField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SortField").field(&self.field).finish()
+    }
+}
+
+impl<T: GraphQLFilter> InputType for SortField<T> {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("{}Sort", T::NAME))
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_input_type::<Self, _>(MetaTypeId::Enum, |_| MetaType::Enum {
+            name: Self::type_name().into_owned(),
+            description: None,
+            enum_values: T::SORTABLE_FIELDS
+                .iter()
+                .map(|field| {
+                    let name = field.to_ascii_uppercase();
+                    let value = MetaEnumValue {
+                        name: name.clone(),
+                        description: None,
+                        deprecation: Deprecation::NoDeprecated,
+                        visible: None,
+                        inaccessible: false,
+                        tags: Vec::new(),
+                        directive_invocations: Vec::new(),
+                    };
+                    (name, value)
+                })
+                .collect(),
+            visible: None,
+            inaccessible: false,
+            tags: Vec::new(),
+            rust_typename: Some(std::any::type_name::<Self>()),
+            directive_invocations: Vec::new(),
+            requires_scopes: Vec::new(),
+        })
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        let name = match &value {
+            Some(Value::Enum(name)) => name.as_str(),
+            Some(Value::String(name)) => name.as_str(),
+            _ => return Err(InputValueError::expected_type(value.unwrap_or_default())),
+        };
+        T::SORTABLE_FIELDS
+            .iter()
+            .find(|field| field.eq_ignore_ascii_case(name))
+            .map(|field| Self {
+                field,
+                filter: PhantomData,
+            })
+            .ok_or_else(|| InputValueError::custom(format!("unknown sort field `{}`", name)))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Enum(Name::new(self.field.to_ascii_uppercase()))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+}
+
+/// `QueryFilter` as the `{NAME}Query` input object
+pub struct QueryFilterInput<T: GraphQLFilter> {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+    pub page: Option<i32>,
+    pub per_page: Option<i32>,
+    pub sort: Option<SortField<T>>,
+    pub order: Option<OrderInput>,
+    pub q: Option<String>,
+    pub fields: Option<String>,
+    pub filter: Option<T::Input>,
+}
+
+impl<T: GraphQLFilter> fmt::Debug for QueryFilterInput<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryFilterInput")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("page", &self.page)
+            .field("per_page", &self.per_page)
+            .field("sort", &self.sort)
+            .field("order", &self.order)
+            .field("q", &self.q)
+            .field("fields", &self.fields)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: GraphQLFilter> InputType for QueryFilterInput<T> {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("{}Query", T::NAME))
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_input_type::<Self, _>(MetaTypeId::InputObject, |registry| {
+            MetaType::InputObject {
+                name: Self::type_name().into_owned(),
+                description: None,
+                input_fields: [
+                    input_field::<Option<i32>>(registry, "start"),
+                    input_field::<Option<i32>>(registry, "end"),
+                    input_field::<Option<i32>>(registry, "page"),
+                    input_field::<Option<i32>>(registry, "perPage"),
+                    input_field::<Option<SortField<T>>>(registry, "sort"),
+                    input_field::<Option<OrderInput>>(registry, "order"),
+                    input_field::<Option<String>>(registry, "q"),
+                    input_field::<Option<String>>(registry, "fields"),
+                    input_field::<Option<T::Input>>(registry, "filter"),
+                ]
+                .into_iter()
+                .collect(),
+                visible: None,
+                inaccessible: false,
+                tags: Vec::new(),
+                rust_typename: Some(std::any::type_name::<Self>()),
+                oneof: false,
+                directive_invocations: Vec::new(),
+            }
+        })
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        let object = match value {
+            Some(Value::Object(object)) => object,
+            value => return Err(InputValueError::expected_type(value.unwrap_or_default())),
+        };
+        let field = |name: &str| object.get(name).cloned();
+        Ok(Self {
+            start: InputType::parse(field("start")).map_err(InputValueError::propagate)?,
+            end: InputType::parse(field("end")).map_err(InputValueError::propagate)?,
+            page: InputType::parse(field("page")).map_err(InputValueError::propagate)?,
+            per_page: InputType::parse(field("perPage")).map_err(InputValueError::propagate)?,
+            sort: InputType::parse(field("sort")).map_err(InputValueError::propagate)?,
+            order: InputType::parse(field("order")).map_err(InputValueError::propagate)?,
+            q: InputType::parse(field("q")).map_err(InputValueError::propagate)?,
+            fields: InputType::parse(field("fields")).map_err(InputValueError::propagate)?,
+            filter: InputType::parse(field("filter")).map_err(InputValueError::propagate)?,
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        let mut object = IndexMap::new();
+        object.insert(Name::new("start"), self.start.to_value());
+        object.insert(Name::new("end"), self.end.to_value());
+        object.insert(Name::new("page"), self.page.to_value());
+        object.insert(Name::new("perPage"), self.per_page.to_value());
+        object.insert(Name::new("sort"), self.sort.to_value());
+        object.insert(Name::new("order"), self.order.to_value());
+        object.insert(Name::new("q"), self.q.to_value());
+        object.insert(Name::new("fields"), self.fields.to_value());
+        object.insert(Name::new("filter"), self.filter.to_value());
+        Value::Object(object)
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+}
+
+fn input_field<I: InputType>(registry: &mut Registry, name: &str) -> (String, MetaInputValue) {
+    let value = MetaInputValue {
+        name: name.to_owned(),
+        description: None,
+        ty: I::create_type_info(registry),
+        deprecation: Deprecation::NoDeprecated,
+        default_value: None,
+        visible: None,
+        inaccessible: false,
+        tags: Vec::new(),
+        is_secret: false,
+        directive_invocations: Vec::new(),
+    };
+    (name.to_owned(), value)
+}
+
+impl<T: GraphQLFilter> From<QueryFilterInput<T>> for QueryFilter<T> {
+    fn from(input: QueryFilterInput<T>) -> Self {
+        QueryFilter {
+            start: input.start,
+            end: input.end,
+            sort: input.sort.map(|sort| sort.as_str().to_owned()),
+            order: input.order.map(|order| Order::from(order).to_string()),
+            q: input.q,
+            page: input.page,
+            per_page: input.per_page,
+            fields: input.fields,
+            filter: input.filter.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptyMutation, EmptySubscription, InputObject, Object, Schema};
+
+    use super::{
+        DateFilterInput, GraphQLFilter, NumberFilterInput, QueryFilterInput, StringFilterInput,
+    };
+    use crate::filters::{DateFilterSet, NumberFilterSet, StringFilterSet};
+    use crate::{Filter, QueryFilter};
+
+    struct UserFilters {
+        name: Option<StringFilterSet<'static>>,
+        age: Option<NumberFilterSet>,
+        birthday: Option<DateFilterSet>,
+    }
+
+    impl Filter for UserFilters {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["name", "age", "created_at"];
+        const SEARCHABLE_FIELDS: &'static [&'static str] = &["name"];
+        const SELECTABLE_FIELDS: &'static [&'static str] = &["name", "age"];
+        const KEY_FIELDS: &'static [&'static str] = &["id"];
+    }
+
+    #[derive(InputObject)]
+    struct UserFilterInput {
+        name: Option<StringFilterInput>,
+        age: Option<NumberFilterInput>,
+        birthday: Option<DateFilterInput>,
+    }
+
+    impl From<UserFilterInput> for UserFilters {
+        fn from(input: UserFilterInput) -> Self {
+            Self {
+                name: input.name.map(Into::into),
+                age: input.age.map(Into::into),
+                birthday: input.birthday.map(Into::into),
+            }
+        }
+    }
+
+    impl GraphQLFilter for UserFilters {
+        const NAME: &'static str = "User";
+        type Input = UserFilterInput;
+    }
+
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn users(&self, query: QueryFilterInput<UserFilters>) -> String {
+            let query = QueryFilter::from(query);
+            let filter = query
+                .get_filter()
+                .map(|filter| format!("{:?} {:?} {:?}", filter.name, filter.age, filter.birthday));
+            format!(
+                "{} {} {:?} {} {:?} {:?}",
+                query.get_offset(),
+                query.get_limit(query.get_offset()),
+                query.get_sort(),
+                query.get_order(),
+                query.get_q(),
+                filter,
+            )
+        }
+    }
+
+    fn schema() -> Schema<Root, EmptyMutation, EmptySubscription> {
+        Schema::new(Root, EmptyMutation, EmptySubscription)
+    }
+
+    #[test]
+    fn sdl() {
+        let sdl = schema().sdl();
+        assert!(sdl.contains("enum UserSort {\n\tNAME\n\tAGE\n\tCREATED_AT\n}"));
+        assert!(sdl.contains(
+            "input UserQuery {\n\tstart: Int\n\tend: Int\n\tpage: Int\n\tperPage: Int\n\tsort: UserSort\n\torder: Order\n\
+             \tq: String\n\tfields: String\n\tfilter: UserFilterInput\n}"
+        ));
+        assert!(sdl.contains("\tnotContains: String\n"));
+        assert!(sdl.contains("\tin: [Int!]\n"));
+    }
+
+    #[tokio::test]
+    async fn query() {
+        let response = schema()
+            .execute(
+                r#"{ users(query: {
+                    start: 20, end: 40, sort: CREATED_AT, order: DESC, q: "jo",
+                    filter: { age: { gte: 20, in: [20, 30] }, birthday: { before: "2000-01-01", gt: "1990-01-01" } }
+                }) }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["users"],
+            "20 20 Some(\"created_at\") DESC Some(\"jo\") Some(\"None \
+             Some(NumberFilterSet([GreaterThanEqual(20), In([20, 30])])) \
+             Some(DateFilterSet([Before(2000-01-01), GreaterThan(1990-01-01)]))\")"
+        );
+
+        let response = schema()
+            .execute(r#"{ users(query: { sort: EMAIL }) }"#)
+            .await;
+        assert_eq!(response.errors.len(), 1);
+    }
+
+    #[cfg(feature = "regex")]
+    #[tokio::test]
+    async fn regex() {
+        let response = schema()
+            .execute(r#"{ users(query: { filter: { name: { iregex: "^jo" } } }) }"#)
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let response = schema()
+            .execute(r#"{ users(query: { filter: { name: { regex: "(a+)+" } } }) }"#)
+            .await;
+        assert!(response.errors[0].message.contains("nested repetition"));
+    }
+
+    #[tokio::test]
+    async fn uuid_prefix() {
+        use super::UuidFilterInput;
+        use crate::filters::UuidFilterSet;
+
+        struct Root;
+
+        #[Object]
+        impl Root {
+            async fn teams(&self, id: UuidFilterInput) -> String {
+                format!("{:?}", UuidFilterSet::from(id))
+            }
+        }
+
+        let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+        assert!(schema.sdl().contains("\tprefix: UuidPrefix\n"));
+
+        let response = schema
+            .execute(r#"{ teams(id: { prefix: "0190A3-0c" }) }"#)
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["teams"],
+            "UuidFilterSet([Prefix(UuidPrefix(\"0190a30c\"))])"
+        );
+
+        let response = schema.execute(r#"{ teams(id: { prefix: "xyz" }) }"#).await;
+        assert!(response.errors[0]
+            .message
+            .contains("expected up to 32 hex digits"));
+    }
+
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    async fn array_and_search() {
+        use super::{ArrayFilterInput, SearchFilterInput};
+        use crate::filters::{ArrayFilterSet, SearchFilterSet};
+
+        struct Root;
+
+        #[Object]
+        impl Root {
+            async fn posts(
+                &self,
+                tags: ArrayFilterInput<String>,
+                scores: ArrayFilterInput<i64>,
+                body: SearchFilterInput,
+            ) -> String {
+                format!(
+                    "{:?} {:?} {:?}",
+                    ArrayFilterSet::from(tags),
+                    ArrayFilterSet::from(scores),
+                    SearchFilterSet::from(body)
+                )
+            }
+        }
+
+        let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+        let sdl = schema.sdl();
+        assert!(sdl.contains(
+            "input StringArrayFilterInput {\n\tcontains: [String!]\n\tcontainedBy: [String!]\n\
+             \toverlaps: [String!]\n\tany: String\n\tlength: NumberFilterInput\n}"
+        ));
+        assert!(sdl.contains("input IntArrayFilterInput {\n\tcontains: [Int!]\n"));
+        assert!(sdl.contains(
+            "input SearchFilterInput {\n\tsearch: String\n\tphrase: String\n\tplain: String\n}"
+        ));
+
+        let response = schema
+            .execute(
+                r#"{ posts(
+                    tags: { overlaps: ["a", "b"], length: { gte: 2 } },
+                    scores: { any: 3 },
+                    body: { search: "rust -go" }
+                ) }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["posts"],
+            "ArrayFilterSet([Overlaps([\"a\", \"b\"]), Length(NumberFilterSet([GreaterThanEqual(2)]))]) \
+             ArrayFilterSet([Any(3)]) SearchFilterSet([Search(\"rust -go\")])"
+        );
+    }
+
+    #[cfg(all(feature = "ipnetwork", feature = "postgis"))]
+    #[tokio::test]
+    async fn ip_and_geo() {
+        use super::{GeoFilterInput, IpFilterInput};
+        use crate::filters::{GeoFilterSet, IpFilterSet};
+
+        struct Root;
+
+        #[Object]
+        impl Root {
+            async fn visits(&self, ip: IpFilterInput, place: GeoFilterInput) -> String {
+                format!(
+                    "{:?} {:?}",
+                    IpFilterSet::from(ip),
+                    GeoFilterSet::from(place)
+                )
+            }
+        }
+
+        let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+        let sdl = schema.sdl();
+        assert!(sdl.contains(
+            "input IpFilterInput {\n\teq: IpAddr\n\tin: [IpAddr!]\n\twithin: IpNetwork\n}"
+        ));
+        assert!(sdl.contains("input GeoFilterInput {\n\tnear: Near\n\tbbox: BBox\n}"));
+
+        let response = schema
+            .execute(
+                r#"{ visits(
+                    ip: { in: ["10.0.0.1", "::1"], within: "10.0.0.0/8" },
+                    place: { near: "48.85,2.35,500", bbox: "2,48,3,49" }
+                ) }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["visits"],
+            "IpFilterSet([In([10.0.0.1, ::1]), Within(V4(Ipv4Network { addr: 10.0.0.0, prefix: 8 }))]) \
+             GeoFilterSet([Near(Near { point: GeoPoint { lat: 48.85, lng: 2.35 }, radius: 500.0 }), \
+             BBox(BBox { min: GeoPoint { lat: 48.0, lng: 2.0 }, max: GeoPoint { lat: 49.0, lng: 3.0 } })])"
+        );
+
+        let response = schema
+            .execute(r#"{ visits(ip: { eq: "10.0.0.256" }, place: {}) }"#)
+            .await;
+        assert!(response.errors[0]
+            .message
+            .contains("invalid IP address syntax"));
+        let response = schema
+            .execute(r#"{ visits(ip: {}, place: { near: "91,0,1" }) }"#)
+            .await;
+        assert!(response.errors[0].message.contains("latitude"));
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn json() {
+        use super::JsonFilterInput;
+        use crate::filters::{JsonFilterSet, JsonPaths};
+
+        struct MetaPaths;
+
+        impl JsonPaths for MetaPaths {
+            const PATHS: &'static [&'static str] = &["plan", "seats", "address.city"];
+        }
+
+        struct OtherPaths;
+
+        impl JsonPaths for OtherPaths {
+            const PATHS: &'static [&'static str] = &["kind"];
+        }
+
+        struct Root;
+
+        #[Object]
+        impl Root {
+            async fn accounts(
+                &self,
+                meta: JsonFilterInput<MetaPaths>,
+                other: Option<JsonFilterInput<OtherPaths>>,
+            ) -> String {
+                format!(
+                    "{:?} {:?}",
+                    JsonFilterSet::from(meta),
+                    other.map(JsonFilterSet::from)
+                )
+            }
+        }
+
+        // Both path lists share the one input object
+        let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+        let sdl = schema.sdl();
+        assert!(sdl.contains(
+            "input JsonFilterInput {\n\tpath: [JsonPathInput!]\n\thas: String\n\tcontains: JSON\n\
+             \tlt: [JsonNumberInput!]\n\tlte: [JsonNumberInput!]\n\tgt: [JsonNumberInput!]\n\
+             \tgte: [JsonNumberInput!]\n}"
+        ));
+        assert!(sdl.contains("input JsonNumberInput {\n\tpath: String!\n\tvalue: Float!\n}"));
+
+        let response = schema
+            .execute(
+                r#"{ accounts(meta: {
+                    path: [{ path: "plan", value: "pro" }],
+                    has: "address",
+                    contains: { address: { city: "Paris" } },
+                    gte: [{ path: "seats", value: 10 }]
+                }) }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["accounts"],
+            "JsonFilterSet([Path({\"plan\": \"pro\"}), HasKey(\"address\"), \
+             Contains(Object {\"address\": Object {\"city\": String(\"Paris\")}}), \
+             GreaterThanEqual({\"seats\": 10.0})]) None"
+        );
+
+        let response = schema
+            .execute(r#"{ accounts(meta: { gte: [{ path: "kind", value: 1 }] }) }"#)
+            .await;
+        assert!(response.errors[0]
+            .message
+            .contains("unknown JSON path `kind`"));
+        let response = schema
+            .execute(r#"{ accounts(meta: { contains: { secret: 1 } }) }"#)
+            .await;
+        assert!(response.errors[0]
+            .message
+            .contains("unknown JSON path `secret`"));
+    }
+
+    #[cfg(feature = "seaq")]
+    impl crate::seaq::ToCond for UserFilters {
+        fn to_cond(&self) -> sea_query::Cond {
+            use crate::seaq::ToFieldCond;
+
+            sea_query::Cond::all()
+                .add_option(self.name.to_cond(sea_query::Alias::new("name")))
+                .add_option(self.age.to_cond(sea_query::Alias::new("age")))
+                .add_option(self.birthday.to_cond(sea_query::Alias::new("birthday")))
+        }
+    }
+
+    #[cfg(feature = "seaq")]
+    #[test]
+    fn apply() {
+        use async_graphql::{value, InputType};
+        use sea_query::{Iden, PostgresQueryBuilder, Query};
+
+        use crate::seaq::ApplyFilters;
+
+        #[derive(Iden)]
+        struct User;
+        #[derive(Iden)]
+        struct Id;
+
+        let input = QueryFilterInput::<UserFilters>::parse(Some(value!({
+            "sort": "AGE",
+            "order": "DESC",
+            "end": 5,
+            "filter": { "name": { "startsWith": "jo" }, "age": { "lt": 65 } }
+        })))
+        .unwrap();
+
+        let q = Query::select()
+            .column(Id)
+            .from(User)
+            .apply_filters(&input.into())
+            .to_string(PostgresQueryBuilder);
+        assert_eq!(
+            q,
+            "SELECT \"id\" FROM \"user\" WHERE \"name\" LIKE 'jo%' AND \"age\" < 65 \
+             ORDER BY \"age\" DESC LIMIT 5 OFFSET 0"
+        );
+
+        let input = QueryFilterInput::<UserFilters>::parse(Some(value!({
+            "page": 3,
+            "perPage": 20,
+            "fields": "age",
+        })))
+        .unwrap();
+        let q = Query::select()
+            .from(User)
+            .apply_filters(&input.into())
+            .to_string(PostgresQueryBuilder);
+        assert_eq!(q, "SELECT \"id\", \"age\" FROM \"user\" LIMIT 20 OFFSET 40");
+    }
+}
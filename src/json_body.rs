@@ -0,0 +1,653 @@
+//! Filters sent as a JSON request body, for those which don't fit in a URL
+//!
+//! ```json
+//! {
+//!     "age": { "$gte": 20 },
+//!     "status": "active",
+//!     "$or": [{ "name": { "$contains": "john" } }, { "id": { "$in": ["…", "…"] } }]
+//! }
+//! ```
+//!
+//! The keys of an object are ANDed together. A field takes a value, compared for equality, or an
+//! object of operators: `$eq`, `$ne`, `$lt`, `$lte`, `$gt`, `$gte`, `$in` and `$nin` on every
+//! field, `$contains`, `$notContains`, `$startsWith`, `$endsWith`, `$like` and `$ilike` on text
+//! fields, and with the `regex` feature `$regex`, with `"$options": "i"` to ignore case. UUID
+//! fields can be compared with `null`. `$and` and `$or` take an array of such objects, and `$not`
+//! a single one.
+//!
+//! Fields are looked up in a whitelist of [`Field`]s, as for RSQL, the document is checked
+//! against [`Limits`], and errors carry the JSON pointer of the offending value.
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::expr::{CompareOp, ExprFields, Field, FieldFilter, FieldKind, FilterExpr};
+use crate::filters::{StringFilter, UuidFilter};
+use crate::Filter;
+
+/// How big a filter document may get
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Length of the JSON text, only checked by [`parse_with`]
+    pub max_bytes: usize,
+    /// Nesting of `$and`, `$or` and `$not`
+    pub max_depth: usize,
+    /// Operators in the whole document
+    pub max_conditions: usize,
+    /// Values in a single `$in` or `$nin`
+    pub max_list_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024,
+            max_depth: 8,
+            max_conditions: 256,
+            max_list_len: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    pointer: String,
+    message: String,
+}
+
+impl JsonError {
+    /// The JSON pointer of the value the error is about, empty for the whole document
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pointer.as_str() {
+            "" => write!(f, "{} at the root", self.message),
+            pointer => write!(f, "{} at `{}`", self.message, pointer),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Parse a JSON filter document using the fields in `fields` and the default [`Limits`]
+pub fn parse(input: &str, fields: &'static [Field]) -> Result<FilterExpr<'static>, JsonError> {
+    parse_with(input, fields, &Limits::default())
+}
+
+pub fn parse_with(
+    input: &str,
+    fields: &'static [Field],
+    limits: &Limits,
+) -> Result<FilterExpr<'static>, JsonError> {
+    if input.len() > limits.max_bytes {
+        return Err(JsonError {
+            pointer: String::new(),
+            message: format!("the filter is longer than {} bytes", limits.max_bytes),
+        });
+    }
+    let value = serde_json::from_str(input).map_err(|err| JsonError {
+        pointer: String::new(),
+        message: format!("invalid JSON, {}", err),
+    })?;
+    from_value(&value, fields, limits)
+}
+
+/// Read an already parsed JSON filter document
+pub fn from_value(
+    value: &Value,
+    fields: &'static [Field],
+    limits: &Limits,
+) -> Result<FilterExpr<'static>, JsonError> {
+    let mut walker = Walker {
+        fields,
+        limits,
+        conditions: 0,
+    };
+    walker.document(value, "", 0)
+}
+
+/// A JSON filter document as a `QueryFilter`'s `filter`, over the fields listed by `F`
+///
+/// Like [`Rsql`](crate::rsql::Rsql), `F` implements [`ExprFields`] and [`Filter`]. It's read
+/// with the default [`Limits`], except `max_bytes` which is up to the body extractor.
+pub struct JsonBody<F> {
+    expr: FilterExpr<'static>,
+    fields: PhantomData<fn() -> F>,
+}
+
+impl<F> JsonBody<F> {
+    pub fn expr(&self) -> &FilterExpr<'static> {
+        &self.expr
+    }
+
+    pub fn into_expr(self) -> FilterExpr<'static> {
+        self.expr
+    }
+}
+
+impl<F> fmt::Debug for JsonBody<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("JsonBody").field(&self.expr).finish()
+    }
+}
+
+impl<'de, F: ExprFields> Deserialize<'de> for JsonBody<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(Self {
+            expr: from_value(&value, F::FIELDS, &Limits::default()).map_err(de::Error::custom)?,
+            fields: PhantomData,
+        })
+    }
+}
+
+impl<F: Filter> Filter for JsonBody<F> {
+    const SORTABLE_FIELDS: &'static [&'static str] = F::SORTABLE_FIELDS;
+    const SEARCHABLE_FIELDS: &'static [&'static str] = F::SEARCHABLE_FIELDS;
+    const SELECTABLE_FIELDS: &'static [&'static str] = F::SELECTABLE_FIELDS;
+    const KEY_FIELDS: &'static [&'static str] = F::KEY_FIELDS;
+
+    fn get_max_limit() -> i32 {
+        F::get_max_limit()
+    }
+
+    fn get_first_page() -> i32 {
+        F::get_first_page()
+    }
+}
+
+#[cfg(feature = "seaq")]
+impl<F> crate::seaq::ToCond for JsonBody<F> {
+    fn to_cond(&self) -> sea_query::Cond {
+        self.expr.to_cond()
+    }
+}
+
+struct Walker<'l> {
+    fields: &'static [Field],
+    limits: &'l Limits,
+    conditions: usize,
+}
+
+impl<'l> Walker<'l> {
+    fn document(
+        &mut self,
+        value: &Value,
+        pointer: &str,
+        depth: usize,
+    ) -> Result<FilterExpr<'static>, JsonError> {
+        if depth > self.limits.max_depth {
+            return Err(error(
+                pointer,
+                format!("nested deeper than {} levels", self.limits.max_depth),
+            ));
+        }
+        let object = value
+            .as_object()
+            .ok_or_else(|| error(pointer, "expected an object".to_owned()))?;
+
+        let mut exprs = Vec::with_capacity(object.len());
+        for (key, value) in object {
+            let pointer = &child(pointer, key);
+            let expr = match key.as_str() {
+                "$and" | "$or" => {
+                    let documents = match value.as_array() {
+                        Some(documents) if !documents.is_empty() => documents,
+                        _ => {
+                            return Err(error(
+                                pointer,
+                                format!("`{}` takes a non-empty array", key),
+                            ))
+                        }
+                    };
+                    let exprs = documents
+                        .iter()
+                        .enumerate()
+                        .map(|(i, document)| {
+                            self.document(document, &child(pointer, &i.to_string()), depth + 1)
+                        })
+                        .collect::<Result<_, _>>()?;
+                    match key.as_str() {
+                        "$and" => FilterExpr::And(exprs),
+                        _ => FilterExpr::Or(exprs),
+                    }
+                }
+                "$not" => FilterExpr::Not(Box::new(self.document(value, pointer, depth + 1)?)),
+                key if key.starts_with('$') => {
+                    return Err(error(pointer, format!("unknown operator `{}`", key)))
+                }
+                name => self.field(name, value, pointer)?,
+            };
+            exprs.push(expr);
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            FilterExpr::And(exprs)
+        })
+    }
+
+    fn field(
+        &mut self,
+        name: &str,
+        value: &Value,
+        pointer: &str,
+    ) -> Result<FilterExpr<'static>, JsonError> {
+        let field = self
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| error(pointer, format!("unknown field `{}`", name)))?;
+
+        let operators = match value {
+            Value::Object(operators) => operators,
+            value => return self.operator(field, "$eq", value, None, pointer),
+        };
+        if operators.is_empty() {
+            return Err(error(pointer, "expected at least one operator".to_owned()));
+        }
+
+        let options = operators.get("$options");
+        if options.is_some() && !operators.contains_key("$regex") {
+            return Err(error(
+                &child(pointer, "$options"),
+                "`$options` only applies to `$regex`".to_owned(),
+            ));
+        }
+
+        let mut exprs = operators
+            .iter()
+            .filter(|(operator, _)| *operator != "$options")
+            .map(|(operator, value)| {
+                let pointer = &child(pointer, operator);
+                self.operator(field, operator, value, options, pointer)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            FilterExpr::And(exprs)
+        })
+    }
+
+    fn operator(
+        &mut self,
+        field: &'static Field,
+        operator: &str,
+        value: &Value,
+        options: Option<&Value>,
+        pointer: &str,
+    ) -> Result<FilterExpr<'static>, JsonError> {
+        self.conditions += 1;
+        if self.conditions > self.limits.max_conditions {
+            return Err(error(
+                pointer,
+                format!("more than {} conditions", self.limits.max_conditions),
+            ));
+        }
+
+        let at = |message| error(pointer, message);
+        let compare = |op| match value {
+            Value::Null => Err(at("only UUID fields can be compared with `null`".to_owned())),
+            value => FilterExpr::compare(field, op, &scalar(value, pointer)?).map_err(at),
+        };
+        match operator {
+            "$eq" | "$ne" if value.is_null() && field.kind == FieldKind::Uuid => {
+                let filter = UuidFilter::Null(operator == "$eq");
+                Ok(FilterExpr::Field(field, FieldFilter::Uuid(filter)))
+            }
+            "$eq" => compare(CompareOp::Eq),
+            "$ne" => compare(CompareOp::Ne),
+            "$lt" => compare(CompareOp::Lt),
+            "$lte" => compare(CompareOp::Le),
+            "$gt" => compare(CompareOp::Gt),
+            "$gte" => compare(CompareOp::Ge),
+            "$in" | "$nin" => {
+                let values = value
+                    .as_array()
+                    .ok_or_else(|| at(format!("`{}` takes an array", operator)))?;
+                if values.len() > self.limits.max_list_len {
+                    return Err(at(format!("more than {} values", self.limits.max_list_len)));
+                }
+                let values = values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| scalar(value, &child(pointer, &i.to_string())))
+                    .collect::<Result<_, _>>()?;
+                FilterExpr::in_list(field, values, operator == "$nin").map_err(at)
+            }
+            "$contains" | "$notContains" | "$startsWith" | "$endsWith" | "$like" | "$ilike" => {
+                let value = text(field, operator, value, pointer)?.into();
+                let filter = match operator {
+                    "$contains" => StringFilter::Contains(value),
+                    "$notContains" => StringFilter::NotContains(value),
+                    "$startsWith" => StringFilter::StartsWith(value),
+                    "$endsWith" => StringFilter::EndsWith(value),
+                    "$like" => StringFilter::Like(value),
+                    _ => StringFilter::ILike(value),
+                };
+                Ok(FilterExpr::Field(field, FieldFilter::String(filter)))
+            }
+            "$regex" => {
+                let pattern = text(field, operator, value, pointer)?;
+                let filter = regex(pattern, options, pointer)?;
+                Ok(FilterExpr::Field(field, FieldFilter::String(filter)))
+            }
+            _ => Err(at(format!("unknown operator `{}`", operator))),
+        }
+    }
+}
+
+/// The string value of an operator only text fields have
+fn text(field: &Field, operator: &str, value: &Value, pointer: &str) -> Result<String, JsonError> {
+    if field.kind != FieldKind::String {
+        return Err(error(
+            pointer,
+            format!(
+                "`{}` is only supported on text fields, not `{}`",
+                operator, field.name
+            ),
+        ));
+    }
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        _ => Err(error(pointer, "expected a string".to_owned())),
+    }
+}
+
+#[cfg(feature = "regex")]
+fn regex(
+    pattern: String,
+    options: Option<&Value>,
+    pointer: &str,
+) -> Result<StringFilter<'static>, JsonError> {
+    let insensitive = match options.map(|options| options.as_str()) {
+        None | Some(Some("")) => false,
+        Some(Some("i")) => true,
+        Some(_) => {
+            let parent = pointer.rsplit_once('/').map_or("", |(parent, _)| parent);
+            return Err(error(
+                &child(parent, "$options"),
+                "the only option is `i`".to_owned(),
+            ));
+        }
+    };
+    let pattern = crate::filters::RegexPattern::new(pattern)
+        .map_err(|err| error(pointer, format!("invalid pattern, {}", err)))?;
+    Ok(match insensitive {
+        false => StringFilter::Regex(pattern),
+        true => StringFilter::IRegex(pattern),
+    })
+}
+
+#[cfg(not(feature = "regex"))]
+fn regex(_: String, _: Option<&Value>, pointer: &str) -> Result<StringFilter<'static>, JsonError> {
+    Err(error(
+        pointer,
+        "`$regex` needs the `regex` feature".to_owned(),
+    ))
+}
+
+/// A string or a number, as the text the field's value is parsed from
+fn scalar(value: &Value, pointer: &str) -> Result<String, JsonError> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        _ => Err(error(pointer, "expected a string or a number".to_owned())),
+    }
+}
+
+/// `pointer/key`, escaping `~` and `/` in the key
+fn child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn error(pointer: &str, message: String) -> JsonError {
+    JsonError {
+        pointer: pointer.to_owned(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_with, Limits};
+    use crate::expr::{Field, FieldFilter, FieldKind, FilterExpr};
+    use crate::filters::{NumberFilter, StringFilter, UuidFilter};
+
+    static FIELDS: &[Field] = &[
+        Field::new("age", FieldKind::Number),
+        Field::new("name", FieldKind::String),
+        Field::new("status", FieldKind::String),
+        Field::new("id", FieldKind::Uuid),
+        Field::new("a/b", FieldKind::String),
+    ];
+
+    const ID: &str = "23191e01-8af8-4381-848c-f9387116d132";
+
+    fn age(filter: NumberFilter) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[0], FieldFilter::Number(filter))
+    }
+
+    fn string(field: usize, filter: StringFilter<'static>) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[field], FieldFilter::String(filter))
+    }
+
+    fn id(filter: UuidFilter) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[3], FieldFilter::Uuid(filter))
+    }
+
+    fn error(input: &str) -> (String, String) {
+        let err = parse(input, FIELDS).unwrap_err();
+        (err.pointer().to_owned(), err.message().to_owned())
+    }
+
+    #[test]
+    fn document() {
+        let expr = parse(
+            &format!(
+                r#"{{
+                    "age": {{ "$gte": 20 }},
+                    "status": "active",
+                    "$or": [{{ "name": {{ "$contains": "john" }} }}, {{ "id": {{ "$in": ["{ID}"] }} }}]
+                }}"#
+            ),
+            FIELDS,
+        )
+        .unwrap();
+
+        assert_eq!(
+            expr,
+            FilterExpr::And(vec![
+                FilterExpr::Or(vec![
+                    string(1, StringFilter::Contains("john".into())),
+                    id(UuidFilter::In(vec![ID.parse().unwrap()])),
+                ]),
+                age(NumberFilter::GreaterThanEqual(20)),
+                string(2, StringFilter::Equals("active".into())),
+            ])
+        );
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(
+            parse(r#"{"$not": {"age": {"$gt": 18, "$lt": 65}}}"#, FIELDS).unwrap(),
+            FilterExpr::Not(Box::new(FilterExpr::And(vec![
+                age(NumberFilter::GreaterThan(18)),
+                age(NumberFilter::LesserThan(65)),
+            ])))
+        );
+        assert_eq!(
+            parse(r#"{"$and": [{"age": 20}, {"age": {"$ne": "21"}}]}"#, FIELDS).unwrap(),
+            FilterExpr::And(vec![
+                age(NumberFilter::Equals(20)),
+                age(NumberFilter::NotEquals(21)),
+            ])
+        );
+        assert_eq!(
+            parse(r#"{"status": {"$nin": ["a", "b"]}}"#, FIELDS).unwrap(),
+            FilterExpr::Not(Box::new(string(
+                2,
+                StringFilter::In(vec!["a".into(), "b".into()])
+            )))
+        );
+        assert_eq!(
+            parse(r#"{"id": null}"#, FIELDS).unwrap(),
+            id(UuidFilter::Null(true))
+        );
+        assert_eq!(
+            parse(r#"{"id": {"$ne": null}}"#, FIELDS).unwrap(),
+            id(UuidFilter::Null(false))
+        );
+        assert_eq!(
+            parse(r#"{"name": {"$ilike": "%jo%"}}"#, FIELDS).unwrap(),
+            string(1, StringFilter::ILike("%jo%".into()))
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex() {
+        assert!(matches!(
+            parse(r#"{"name": {"$regex": "^jo", "$options": "i"}}"#, FIELDS).unwrap(),
+            FilterExpr::Field(_, FieldFilter::String(StringFilter::IRegex(_)))
+        ));
+        assert_eq!(
+            error(r#"{"name": {"$regex": "^jo", "$options": "x"}}"#),
+            (
+                "/name/$options".to_owned(),
+                "the only option is `i`".to_owned()
+            )
+        );
+        assert_eq!(error(r#"{"name": {"$options": "i"}}"#).0, "/name/$options");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error(r#"{"$or": [{"age": 1}, {"age": {"$gte": "old"}}]}"#).0,
+            "/$or/1/age/$gte"
+        );
+        assert_eq!(
+            error(r#"{"email": "a"}"#),
+            ("/email".to_owned(), "unknown field `email`".to_owned())
+        );
+        assert_eq!(error(r#"{"a/b": {"$gt": []}}"#).0, "/a~1b/$gt");
+        assert_eq!(
+            error(r#"{"age": {"$contains": "1"}}"#),
+            (
+                "/age/$contains".to_owned(),
+                "`$contains` is only supported on text fields, not `age`".to_owned()
+            )
+        );
+        assert_eq!(error(r#"{"age": null}"#).0, "/age");
+        assert_eq!(error(r#"{"$or": []}"#).0, "/$or");
+        assert_eq!(error(r#"{"$nor": []}"#).0, "/$nor");
+        assert_eq!(error(r#"{"age": {}}"#).0, "/age");
+        assert_eq!(error("[]").0, "");
+        assert!(parse(r#"{"age": }"#, FIELDS)
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid JSON,"));
+        assert_eq!(
+            parse(r#"{"email": 1}"#, FIELDS).unwrap_err().to_string(),
+            "unknown field `email` at `/email`"
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let limits = Limits {
+            max_bytes: 64,
+            max_depth: 1,
+            max_conditions: 2,
+            max_list_len: 2,
+        };
+        let error = |input: &str| parse_with(input, FIELDS, &limits).unwrap_err().to_string();
+
+        assert_eq!(
+            error(r#"{"$not": {"$not": {"age": 1}}}"#),
+            "nested deeper than 1 levels at `/$not/$not`"
+        );
+        assert_eq!(
+            error(r#"{"age": {"$gt": 1, "$lt": 5}, "name": "a"}"#),
+            "more than 2 conditions at `/name`"
+        );
+        assert_eq!(
+            error(r#"{"age": {"$in": [1, 2, 3]}}"#),
+            "more than 2 values at `/age/$in`"
+        );
+        assert_eq!(
+            error(&format!(r#"{{"name": "{}"}}"#, "a".repeat(64))),
+            "the filter is longer than 64 bytes at the root"
+        );
+    }
+
+    #[cfg(feature = "seaq")]
+    #[test]
+    fn query_filter() {
+        use sea_query::{Iden, PostgresQueryBuilder, Query};
+
+        use super::JsonBody;
+        use crate::{expr::ExprFields, seaq::ApplyFilters, Filter, QueryFilter};
+
+        #[derive(Iden)]
+        struct User;
+        #[derive(Iden)]
+        struct Id;
+
+        struct UserFields;
+
+        impl ExprFields for UserFields {
+            const FIELDS: &'static [Field] = FIELDS;
+        }
+
+        impl Filter for UserFields {
+            const SORTABLE_FIELDS: &'static [&'static str] = &["age"];
+        }
+
+        let filters = serde_json::from_str::<QueryFilter<JsonBody<UserFields>>>(
+            r#"{
+                "filter": {"$or": [{"age": {"$lt": 18}}, {"status": {"$in": ["a", "b"]}}]},
+                "sort": "age",
+                "order": "DESC",
+                "start": 0,
+                "end": 5
+            }"#,
+        )
+        .unwrap();
+
+        let q = Query::select()
+            .column(Id)
+            .from(User)
+            .apply_filters(&filters)
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(
+            q,
+            "SELECT \"id\" FROM \"user\" WHERE \"age\" < 18 OR \"status\" IN ('a', 'b') \
+             ORDER BY \"age\" DESC LIMIT 5 OFFSET 0"
+        );
+
+        let err = serde_json::from_str::<QueryFilter<JsonBody<UserFields>>>(
+            r#"{"filter": {"age": {"$between": [1, 2]}}}"#,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("unknown operator `$between` at `/age/$between`"));
+    }
+}
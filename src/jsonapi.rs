@@ -0,0 +1,299 @@
+//! JSON:API's query parameters, `filter[age][gte]=20&sort=-created_at,name&page[number]=2`
+//!
+//! `filter` is read like `QueryFilter`'s, with the bracket-mode filter sets. `sort` lists fields
+//! from [`Filter::SORTABLE_FIELDS`], descending when prefixed with `-`. Pages are given either
+//! as `page[number]` and `page[size]`, 1-based with ten to a page when the size is missing, or as
+//! `page[offset]` and `page[limit]`. `page[cursor]` is kept as is for keyset pagination, along
+//! with `page[size]` or `page[limit]` as the limit.
+
+use std::fmt;
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{Filter, Order, QueryFilter};
+
+/// The `filter`, `sort` and `page` parameters, deserialized into a `QueryFilter`
+///
+/// The first sort field becomes the `QueryFilter`'s `sort` and `order`, so `ApplyFilters` and
+/// the other consumers of `QueryFilter` see it, and [`JsonApiQuery::sorts`] has all of them.
+pub struct JsonApiQuery<T> {
+    pub filters: QueryFilter<T>,
+    sorts: Vec<(&'static str, Order)>,
+    cursor: Option<String>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for JsonApiQuery<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonApiQuery")
+            .field("filters", &self.filters)
+            .field("sorts", &self.sorts)
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
+impl<T> JsonApiQuery<T> {
+    /// Every field of `sort`, in order
+    pub fn sorts(&self) -> &[(&'static str, Order)] {
+        &self.sorts
+    }
+
+    /// `page[cursor]`, to be decoded by whoever issued it
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    pub fn into_inner(self) -> QueryFilter<T> {
+        self.filters
+    }
+}
+
+impl<T> From<JsonApiQuery<T>> for QueryFilter<T> {
+    fn from(query: JsonApiQuery<T>) -> Self {
+        query.filters
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct RawQuery<T> {
+    filter: Option<T>,
+    sort: Option<String>,
+    page: Option<RawPage>,
+}
+
+#[derive(Default, Deserialize)]
+struct RawPage {
+    number: Option<u32>,
+    size: Option<u32>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    cursor: Option<String>,
+}
+
+impl<'de, T: Deserialize<'de> + Filter> Deserialize<'de> for JsonApiQuery<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawQuery::<T>::deserialize(deserializer)?;
+
+        let sorts = match raw.sort.as_deref() {
+            Some(sort) => parse_sort::<T>(sort).map_err(de::Error::custom)?,
+            None => Vec::new(),
+        };
+        let page = raw.page.unwrap_or_default();
+        let (start, end) = range(&page).map_err(de::Error::custom)?;
+
+        let (sort, order) = match sorts.first() {
+            Some((field, order)) => (Some(field.to_string()), Some(order.to_string())),
+            None => (None, None),
+        };
+
+        Ok(Self {
+            filters: QueryFilter {
+                start,
+                end,
+                sort,
+                order,
+                q: None,
+                page: None,
+                per_page: None,
+                fields: None,
+                filter: raw.filter,
+            },
+            sorts,
+            cursor: page.cursor,
+        })
+    }
+}
+
+/// `-created_at,name`
+fn parse_sort<T: Filter>(sort: &str) -> Result<Vec<(&'static str, Order)>, String> {
+    sort.split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (name, order) = match field.strip_prefix('-') {
+                Some(name) => (name, Order::Desc),
+                None => (field, Order::Asc),
+            };
+            T::validate_sortable_field(name)
+                .map(|name| (name, order))
+                .ok_or_else(|| format!("`{}` can't be used in sort", name))
+        })
+        .collect()
+}
+
+/// `start` and `end` of the requested page
+fn range(page: &RawPage) -> Result<(Option<i32>, Option<i32>), String> {
+    let numbered = page.number.is_some() || page.size.is_some();
+    let offset = page.offset.is_some() || page.limit.is_some();
+
+    let (start, limit) = match (numbered, offset) {
+        (true, true) => {
+            return Err(
+                "page[number] and page[size] can't be mixed with page[offset] and page[limit]"
+                    .to_owned(),
+            )
+        }
+        _ if page.cursor.is_some() => {
+            if page.number.is_some() || page.offset.is_some() {
+                return Err(
+                    "page[cursor] can't be mixed with page[number] or page[offset]".to_owned(),
+                );
+            }
+            (None, page.size.or(page.limit))
+        }
+        (true, false) => {
+            let size = page.size.unwrap_or(10);
+            match page.number.unwrap_or(1) {
+                0 => return Err("page[number] starts at 1".to_owned()),
+                number => (Some((number - 1).saturating_mul(size)), Some(size)),
+            }
+        }
+        (false, true) => (page.offset, page.limit),
+        (false, false) => (None, None),
+    };
+
+    let end = limit.map(|limit| clamp(start.unwrap_or_default().saturating_add(limit)));
+    Ok((start.map(clamp), end))
+}
+
+fn clamp(value: u32) -> i32 {
+    value.min(i32::MAX as u32) as i32
+}
+
+#[cfg(feature = "seaq")]
+mod seaq {
+    use sea_query::SelectStatement;
+
+    use super::JsonApiQuery;
+    use crate::seaq::{ApplyFilters, ToCond};
+    use crate::Filter;
+
+    impl<T: Filter + ToCond> JsonApiQuery<T> {
+        /// [`ApplyFilters::apply_filters`], then ordering by the sort fields after the first
+        pub fn apply_to_select<'s>(
+            &self,
+            statement: &'s mut SelectStatement,
+        ) -> &'s mut SelectStatement {
+            let statement = statement.apply_filters(&self.filters);
+            for (field, order) in self.sorts.iter().skip(1) {
+                if let Some(expr) = self.filters.get_order_expr(field) {
+                    statement.order_by_expr(expr, order.to_seaquery());
+                }
+            }
+            statement
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::JsonApiQuery;
+    use crate::filters::{NumberFilterSet, StringFilterSet};
+    use crate::{Filter, Order};
+
+    #[derive(Debug, Deserialize)]
+    struct UserFilters<'a> {
+        #[serde(borrow)]
+        name: Option<StringFilterSet<'a>>,
+        age: Option<NumberFilterSet>,
+    }
+
+    impl<'a> Filter for UserFilters<'a> {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["name", "age", "created_at"];
+    }
+
+    #[cfg(feature = "seaq")]
+    impl<'a> crate::seaq::ToCond for UserFilters<'a> {
+        fn to_cond(&self) -> sea_query::Cond {
+            use crate::seaq::ToFieldCond;
+
+            let mut cond = sea_query::Cond::all();
+            if let Some(name) = self.name.to_cond(sea_query::Alias::new("name")) {
+                cond = cond.add(name)
+            }
+            if let Some(age) = self.age.to_cond(sea_query::Alias::new("age")) {
+                cond = cond.add(age)
+            }
+            cond
+        }
+    }
+
+    fn query(query: &str) -> Result<JsonApiQuery<UserFilters<'_>>, String> {
+        from_str(query, ParseMode::Brackets).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn sort() {
+        let sorted =
+            query("filter[age][gte]=20&filter[name][eq]=jo&sort=-created_at,name").unwrap();
+        assert_eq!(
+            sorted.sorts(),
+            [("created_at", Order::Desc), ("name", Order::Asc)]
+        );
+        assert_eq!(sorted.filters.get_sort(), Some("created_at"));
+        assert_eq!(sorted.filters.get_order(), Order::Desc);
+        let filter = sorted.filters.filter.unwrap();
+        assert_eq!(filter.age, Some(NumberFilterSet::new().gte(20)));
+        assert_eq!(filter.name, Some(StringFilterSet::new().eq("jo")));
+
+        assert!(query("sort=-email")
+            .unwrap_err()
+            .contains("`email` can't be used in sort"));
+    }
+
+    #[test]
+    fn pages() {
+        let range = |query: &str| {
+            let filters = self::query(query).unwrap().into_inner();
+            let offset = filters.get_offset();
+            (offset, filters.get_limit(offset))
+        };
+
+        assert_eq!(range("page[number]=3&page[size]=20"), (40, 20));
+        assert_eq!(range("page[number]=2"), (10, 10));
+        assert_eq!(range("page[size]=5"), (0, 5));
+        assert_eq!(range("page[offset]=30&page[limit]=15"), (30, 15));
+        assert_eq!(range("page[limit]=15"), (0, 15));
+        assert_eq!(range(""), (0, 10));
+
+        let cursor = query("page[cursor]=abc&page[size]=25").unwrap();
+        assert_eq!(cursor.cursor(), Some("abc"));
+        assert_eq!(cursor.filters.get_limit(0), 25);
+
+        assert!(query("page[number]=0").unwrap_err().contains("starts at 1"));
+        assert!(query("page[number]=1&page[offset]=10").is_err());
+        assert!(query("page[cursor]=abc&page[number]=2").is_err());
+        assert!(query("page[size]=-1").is_err());
+    }
+
+    #[cfg(feature = "seaq")]
+    #[test]
+    fn apply() {
+        use sea_query::{Iden, PostgresQueryBuilder, Query};
+
+        #[derive(Iden)]
+        struct User;
+        #[derive(Iden)]
+        struct Id;
+
+        let query = query(
+            "filter[age][gte]=20&filter[name][contains]=jo&sort=-created_at,name\
+             &page[number]=2&page[size]=20",
+        )
+        .unwrap();
+
+        let q = query
+            .apply_to_select(Query::select().column(Id).from(User))
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(
+            q,
+            "SELECT \"id\" FROM \"user\" WHERE \"name\" LIKE '%jo%' AND \"age\" >= 20 \
+             ORDER BY \"created_at\" DESC, \"name\" ASC LIMIT 20 OFFSET 20"
+        );
+    }
+}
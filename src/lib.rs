@@ -1,7 +1,19 @@
 #![doc = include_str!("../README.md")]
 
+pub mod expr;
 pub mod filters;
+#[cfg(feature = "async-graphql")]
+pub mod graphql;
+#[cfg(feature = "json-body")]
+pub mod json_body;
+pub mod jsonapi;
+mod memory;
+pub mod odata;
 mod query;
+pub mod rsql;
+pub mod ser;
+pub mod syntax;
+pub mod text;
 
 #[cfg(feature = "seaq")]
 #[cfg(test)]
@@ -10,7 +22,26 @@ mod test_utils;
 #[cfg(feature = "seaq")]
 mod seaq;
 
+#[cfg(feature = "sea-orm")]
+mod seaorm;
+
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite"
+))]
+mod binder;
+
 #[cfg(feature = "seaq")]
-pub use seaq::{ApplyConds, ApplyFilters, ToCond, ToFieldCond};
+pub use seaq::{ApplyConds, ApplyDeleteFilters, ApplyFilters, Backend, ToCond, ToFieldCond};
 
+pub use memory::{Matches, Sortable};
 pub use query::{Filter, Order, QueryFilter};
+pub use ser::to_querystring;
+
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite"
+))]
+pub use binder::SqlxStatement;
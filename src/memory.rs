@@ -0,0 +1,503 @@
+//! A set of traits and impls for evaluating filters against values living in memory
+//!
+//! The semantics follow the SQL the filters lower to, so the same `QueryFilter` can be served
+//! from a database table or from a `Vec` of cached records.
+
+use std::cmp::Ordering;
+
+use crate::{syntax::escape_like, Filter, QueryFilter};
+
+/// Whether a value passes a filter, the in-memory counterpart of `ToFieldCond` and `ToCond`
+///
+/// Filter sets and filter structs match when all of their filters do, so an empty one matches
+/// everything, like an empty `Cond::all()`.
+pub trait Matches<V: ?Sized> {
+    fn matches(&self, value: &V) -> bool;
+}
+
+impl<T, V> Matches<V> for Option<T>
+where
+    T: Matches<V>,
+    V: ?Sized,
+{
+    fn matches(&self, value: &V) -> bool {
+        match self {
+            Some(filter) => filter.matches(value),
+            None => true,
+        }
+    }
+}
+
+impl<V: ?Sized> Matches<V> for () {
+    fn matches(&self, _value: &V) -> bool {
+        true
+    }
+}
+
+/// Records which can be ordered by the fields listed in [`Filter::SORTABLE_FIELDS`]
+pub trait Sortable {
+    fn cmp_by(&self, other: &Self, field: &str) -> Ordering;
+
+    /// The text of one of [`Filter::SEARCHABLE_FIELDS`], for the `q` parameter
+    ///
+    /// Fields without one are taken for `NULL` columns, which `q` never matches.
+    fn search_text(&self, _field: &str) -> Option<&str> {
+        None
+    }
+}
+
+impl<T: Filter> QueryFilter<T> {
+    /// Filter, sort and paginate records the same way `ApplyFilters` does for a `SELECT`
+    ///
+    /// `q` is looked up in [`Sortable::search_text`] of the searchable fields.
+    pub fn apply_to<R, I>(&self, records: I) -> Vec<R>
+    where
+        T: Matches<R>,
+        R: Sortable,
+        I: IntoIterator<Item = R>,
+    {
+        let offset = self.get_offset();
+        let limit = self.get_limit(offset);
+        let order = self.get_order();
+        let sort = self.get_sort();
+        let pattern = self
+            .get_q()
+            .map(|q| format!("%{}%", escape_like(&q.to_lowercase())));
+
+        let mut records = records
+            .into_iter()
+            .filter(|record| self.filter.matches(record))
+            .filter(|record| match &pattern {
+                Some(pattern) => T::SEARCHABLE_FIELDS.iter().any(|field| {
+                    let text = record.search_text(field).map(str::to_lowercase);
+                    text.is_some_and(|text| like(pattern, &text))
+                }),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(field) = sort {
+            records.sort_by(|a, b| match order {
+                crate::Order::Desc => b.cmp_by(a, field),
+                _ => a.cmp_by(b, field),
+            });
+        }
+
+        records
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    }
+}
+
+/// SQL's `LIKE` with `%` and `_` wildcards, matching case-sensitively
+///
+/// `\` escapes the character after it, as it does with the `ESCAPE '\'` the SQL side adds, and a
+/// trailing one matches nothing, as on SQLite.
+pub(crate) fn like(pattern: &str, value: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let value = value.chars().collect::<Vec<_>>();
+
+    // Positions to come back to when a mismatch happens after the last `%`
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack = None;
+
+    while v < value.len() {
+        // How many pattern characters the next value character consumes, if it matches
+        let width = match pattern.get(p) {
+            Some('%') => {
+                backtrack = Some((p, v));
+                p += 1;
+                continue;
+            }
+            Some('_') => Some(1),
+            Some('\\') => pattern
+                .get(p + 1)
+                .and_then(|c| (*c == value[v]).then_some(2)),
+            Some(c) => (*c == value[v]).then_some(1),
+            None => None,
+        };
+        match (width, backtrack) {
+            (Some(width), _) => {
+                p += width;
+                v += 1;
+            }
+            (None, Some((bp, bv))) => {
+                backtrack = Some((bp, bv + 1));
+                p = bp + 1;
+                v = bv + 1;
+            }
+            (None, None) => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use serde_querystring::de::ParseMode;
+
+    use super::{like, Matches, Sortable};
+    use crate::{
+        filters::{NumberFilterSet, StringFilterSet},
+        Filter, QueryFilter,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Record {
+        id: i64,
+        name: String,
+        score: i64,
+    }
+
+    impl Sortable for Record {
+        fn cmp_by(&self, other: &Self, field: &str) -> Ordering {
+            match field {
+                "id" => self.id.cmp(&other.id),
+                "name" => self.name.cmp(&other.name),
+                "score" => self.score.cmp(&other.score),
+                _ => Ordering::Equal,
+            }
+        }
+
+        fn search_text(&self, field: &str) -> Option<&str> {
+            match field {
+                "name" => Some(&self.name),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    struct Filters {
+        name: Option<StringFilterSet<'static>>,
+        score: Option<NumberFilterSet>,
+    }
+
+    impl Matches<Record> for Filters {
+        fn matches(&self, record: &Record) -> bool {
+            self.name.matches(&record.name) && self.score.matches(&record.score)
+        }
+    }
+
+    impl Filter for Filters {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["id", "name", "score"];
+        const SEARCHABLE_FIELDS: &'static [&'static str] = &["name"];
+    }
+
+    fn records() -> Vec<Record> {
+        [("John", 25), ("Johnny", 45), ("Jane", 30), ("Joe", 60)]
+            .into_iter()
+            .enumerate()
+            .map(|(id, (name, score))| Record {
+                id: id as i64 + 1,
+                name: name.into(),
+                score,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_like() {
+        assert!(like("%", ""));
+        assert!(like("%john%", "big john doe"));
+        assert!(like("j_hn", "john"));
+        assert!(like("%a%b", "xxaxxbxxb"));
+        assert!(!like("%a%b", "xxaxxbxxc"));
+        assert!(!like("john", "John"));
+        assert!(!like("j_hn", "jhn"));
+        assert!(like("50\\%%", "50% off"));
+        assert!(!like("50\\%%", "500 off"));
+        assert!(like("%a\\_b", "xa_b"));
+        assert!(!like("%a\\_b", "xacb"));
+        assert!(like("a\\\\b", "a\\b"));
+        assert!(like("50%", "50% off"));
+        assert!(!like("a\\", "a\\"));
+        assert!(!like("%\\", "a\\"));
+    }
+
+    #[test]
+    fn test_apply_to() {
+        let filters = serde_querystring::from_str::<QueryFilter<Filters>>(
+            "filter[score][lt]=50&filter[name][startswith]=J&start=1&end=3&sort=score&order=DESC",
+            ParseMode::Brackets,
+        )
+        .unwrap();
+
+        let names = filters
+            .apply_to(records())
+            .into_iter()
+            .map(|record| record.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["Jane", "John"]);
+    }
+
+    #[test]
+    fn test_apply_to_q() {
+        let filters = serde_querystring::from_str::<QueryFilter<Filters>>(
+            "q=JOHN&sort=id",
+            ParseMode::Brackets,
+        )
+        .unwrap();
+
+        let names = filters
+            .apply_to(records())
+            .into_iter()
+            .map(|record| record.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["John", "Johnny"]);
+    }
+
+    #[test]
+    fn test_apply_to_q_wildcards() {
+        let records = ["50% off", "5_0", "500"]
+            .into_iter()
+            .enumerate()
+            .map(|(id, name)| Record {
+                id: id as i64 + 1,
+                name: name.into(),
+                score: 0,
+            })
+            .collect::<Vec<_>>();
+        let names = |query: &str| {
+            serde_querystring::from_str::<QueryFilter<Filters>>(query, ParseMode::Brackets)
+                .unwrap()
+                .apply_to(records.clone())
+                .into_iter()
+                .map(|record| record.name)
+                .collect::<Vec<_>>()
+        };
+
+        // `%` and `_` are searched for, not taken for wildcards
+        assert_eq!(names("q=%25"), vec!["50% off"]);
+        assert_eq!(names("q=_"), vec!["5_0"]);
+    }
+
+    #[test]
+    fn test_apply_to_defaults() {
+        let filters = QueryFilter::<Filters>::default();
+        assert_eq!(filters.apply_to(records()), records());
+    }
+
+    #[cfg(feature = "sqlx-sqlite")]
+    mod sqlite {
+        use std::borrow::Cow;
+
+        use proptest::{prelude::*, test_runner::TestRunner};
+        use sea_query::{ColumnDef, Cond, Iden, Query, Table};
+        use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+
+        use super::{Filters, Record};
+        use crate::{
+            filters::{NumberFilter, NumberFilterSet, StringFilter, StringFilterSet},
+            ApplyFilters, QueryFilter, SqlxStatement, ToCond, ToFieldCond,
+        };
+
+        #[derive(Iden)]
+        enum Records {
+            Table,
+            Id,
+            Name,
+            Score,
+        }
+
+        impl ToCond for Filters {
+            fn to_cond(&self) -> Cond {
+                let mut cond = Cond::all();
+                if let Some(name) = self.name.to_cond(Records::Name) {
+                    cond = cond.add(name)
+                }
+                if let Some(score) = self.score.to_cond(Records::Score) {
+                    cond = cond.add(score)
+                }
+                cond
+            }
+        }
+
+        fn text() -> impl Strategy<Value = String> {
+            "[abA%_\\\\]{0,4}"
+        }
+
+        fn string_filter() -> impl Strategy<Value = StringFilter<'static>> {
+            let value = || text().prop_map(Cow::Owned);
+            prop_oneof![
+                value().prop_map(StringFilter::Contains),
+                value().prop_map(StringFilter::NotContains),
+                value().prop_map(StringFilter::StartsWith),
+                value().prop_map(StringFilter::EndsWith),
+                value().prop_map(StringFilter::Equals),
+                value().prop_map(StringFilter::Like),
+                value().prop_map(StringFilter::ILike),
+                prop::collection::vec(value(), 0..3).prop_map(StringFilter::In),
+            ]
+        }
+
+        fn number_filter() -> impl Strategy<Value = NumberFilter> {
+            prop_oneof![
+                (-5..5i64).prop_map(NumberFilter::Equals),
+                (-5..5i64).prop_map(NumberFilter::NotEquals),
+                (-5..5i64).prop_map(NumberFilter::LesserThan),
+                (-5..5i64).prop_map(NumberFilter::LesserThanEqual),
+                (-5..5i64).prop_map(NumberFilter::GreaterThan),
+                (-5..5i64).prop_map(NumberFilter::GreaterThanEqual),
+                prop::collection::vec(-5..5i64, 0..3).prop_map(NumberFilter::In),
+            ]
+        }
+
+        fn query_filter() -> impl Strategy<Value = QueryFilter<Filters>> {
+            let name = proptest::option::of(prop::collection::vec(string_filter(), 0..3));
+            let score = proptest::option::of(prop::collection::vec(number_filter(), 0..3));
+            let sort = proptest::option::of(prop_oneof![
+                Just("id"),
+                Just("name"),
+                Just("score"),
+                Just("unknown")
+            ]);
+            let order = proptest::option::of(prop_oneof![Just("ASC"), Just("desc"), Just("x")]);
+            (
+                proptest::option::of(0..6i32),
+                proptest::option::of(0..12i32),
+                sort,
+                order,
+                proptest::option::of(text()),
+                proptest::option::of((name, score)),
+            )
+                .prop_map(|(start, end, sort, order, q, filter)| QueryFilter {
+                    start,
+                    end,
+                    sort: sort.map(String::from),
+                    order: order.map(String::from),
+                    q,
+                    page: None,
+                    per_page: None,
+                    fields: None,
+                    filter: filter.map(|(name, score)| Filters {
+                        name: name.map(StringFilterSet::from_iter),
+                        score: score.map(NumberFilterSet::from_iter),
+                    }),
+                })
+        }
+
+        fn records() -> impl Strategy<Value = Vec<Record>> {
+            prop::collection::vec((text(), -5..5i64), 0..12).prop_map(|rows| {
+                rows.into_iter()
+                    .enumerate()
+                    .map(|(id, (name, score))| Record {
+                        id: id as i64 + 1,
+                        name,
+                        score,
+                    })
+                    .collect()
+            })
+        }
+
+        async fn pool() -> SqlitePool {
+            let pool = SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap();
+
+            // Postgres' LIKE is case-sensitive, and so is the in-memory one
+            sqlx::query("PRAGMA case_sensitive_like = true")
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let create = Table::create()
+                .table(Records::Table)
+                .col(ColumnDef::new(Records::Id).integer().primary_key())
+                .col(ColumnDef::new(Records::Name).string().not_null())
+                .col(ColumnDef::new(Records::Score).integer().not_null())
+                .to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(&create).execute(&pool).await.unwrap();
+
+            pool
+        }
+
+        async fn query(
+            pool: &SqlitePool,
+            records: &[Record],
+            filters: &QueryFilter<Filters>,
+        ) -> Vec<Record> {
+            sqlx::query("DELETE FROM records")
+                .execute(pool)
+                .await
+                .unwrap();
+
+            if !records.is_empty() {
+                let mut insert = Query::insert();
+                insert.into_table(Records::Table).columns([
+                    Records::Id,
+                    Records::Name,
+                    Records::Score,
+                ]);
+                for record in records {
+                    insert.values_panic([
+                        record.id.into(),
+                        record.name.clone().into(),
+                        record.score.into(),
+                    ]);
+                }
+                SqlxStatement::sqlite(&insert)
+                    .query()
+                    .execute(pool)
+                    .await
+                    .unwrap();
+            }
+
+            let statement = SqlxStatement::sqlite(
+                Query::select()
+                    .columns([Records::Id, Records::Name, Records::Score])
+                    .from(Records::Table)
+                    .apply_filters(filters),
+            );
+
+            statement
+                .query_as::<_, (i64, String, i64)>()
+                .fetch_all(pool)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(id, name, score)| Record { id, name, score })
+                .collect()
+        }
+
+        #[test]
+        fn test_same_as_sqlite() {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let pool = runtime.block_on(pool());
+
+            TestRunner::default()
+                .run(&(records(), query_filter()), |(records, filters)| {
+                    let from_sql = runtime.block_on(query(&pool, &records, &filters));
+                    let from_memory = filters.apply_to(records);
+
+                    // Rows tied on the sort field may come back in any order, their sort
+                    // values can't though
+                    match filters.get_sort() {
+                        Some("name") => prop_assert_eq!(
+                            from_sql.iter().map(|r| &r.name).collect::<Vec<_>>(),
+                            from_memory.iter().map(|r| &r.name).collect::<Vec<_>>()
+                        ),
+                        Some("score") => prop_assert_eq!(
+                            from_sql.iter().map(|r| r.score).collect::<Vec<_>>(),
+                            from_memory.iter().map(|r| r.score).collect::<Vec<_>>()
+                        ),
+                        _ => prop_assert_eq!(from_sql, from_memory),
+                    }
+                    Ok(())
+                })
+                .unwrap();
+        }
+    }
+}
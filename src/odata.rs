@@ -0,0 +1,714 @@
+//! An OData v4 front end, for `$filter=Age gt 20 and startswith(Name,'Jo')&$orderby=Age desc`
+//!
+//! `$filter` supports `eq`, `ne`, `gt`, `ge`, `lt`, `le`, `in (..)`, `and`, `or`, `not`,
+//! parentheses and the `contains`, `startswith` and `endswith` functions on text fields, over a
+//! whitelist of [`Field`]s like RSQL, nesting up to [`MAX_DEPTH`] levels of `not` and parentheses.
+//! Other functions and `null` are rejected. `$orderby` takes a single field from
+//! [`Filter::SORTABLE_FIELDS`], and `$top`/`$skip` become the limit and offset of the resulting
+//! [`QueryFilter`].
+
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::expr::{CompareOp, ExprFields, Field, FieldFilter, FieldKind, FilterExpr, MAX_DEPTH};
+use crate::filters::StringFilter;
+use crate::{Filter, QueryFilter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ODataError {
+    offset: usize,
+    message: String,
+}
+
+impl ODataError {
+    /// Where the error is in `$filter`, counted in characters
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ODataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ODataError {}
+
+/// Parse a `$filter` expression using the fields in `fields`
+pub fn parse_filter(
+    input: &str,
+    fields: &'static [Field],
+) -> Result<FilterExpr<'static>, ODataError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        depth: 0,
+        fields,
+    };
+    let expr = parser.or()?;
+    match parser.peek() {
+        (_, Token::End) => Ok(expr),
+        (offset, token) => Err(error(*offset, format!("unexpected {}", token))),
+    }
+}
+
+/// A `$filter` expression as a `QueryFilter`'s `filter`, over the fields listed by `F`
+pub struct OData<F> {
+    expr: FilterExpr<'static>,
+    fields: PhantomData<fn() -> F>,
+}
+
+impl<F> OData<F> {
+    pub fn expr(&self) -> &FilterExpr<'static> {
+        &self.expr
+    }
+
+    pub fn into_expr(self) -> FilterExpr<'static> {
+        self.expr
+    }
+}
+
+impl<F> fmt::Debug for OData<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OData").field(&self.expr).finish()
+    }
+}
+
+impl<F: ExprFields> FromStr for OData<F> {
+    type Err = ODataError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            expr: parse_filter(input, F::FIELDS)?,
+            fields: PhantomData,
+        })
+    }
+}
+
+impl<F: Filter> Filter for OData<F> {
+    const SORTABLE_FIELDS: &'static [&'static str] = F::SORTABLE_FIELDS;
+    const SEARCHABLE_FIELDS: &'static [&'static str] = F::SEARCHABLE_FIELDS;
+    const SELECTABLE_FIELDS: &'static [&'static str] = F::SELECTABLE_FIELDS;
+    const KEY_FIELDS: &'static [&'static str] = F::KEY_FIELDS;
+
+    fn get_max_limit() -> i32 {
+        F::get_max_limit()
+    }
+
+    fn get_first_page() -> i32 {
+        F::get_first_page()
+    }
+}
+
+#[cfg(feature = "seaq")]
+impl<F> crate::seaq::ToCond for OData<F> {
+    fn to_cond(&self) -> sea_query::Cond {
+        self.expr.to_cond()
+    }
+}
+
+/// The `$filter`, `$orderby`, `$top` and `$skip` options, deserialized into a `QueryFilter`
+///
+/// `$orderby` names a field of `F::FIELDS` or one of `F::SORTABLE_FIELDS` directly, and fails to
+/// deserialize when it's anything else or lists several fields.
+pub struct ODataQuery<F>(pub QueryFilter<OData<F>>);
+
+impl<F> fmt::Debug for ODataQuery<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ODataQuery").field(&self.0).finish()
+    }
+}
+
+impl<F> ODataQuery<F> {
+    pub fn into_inner(self) -> QueryFilter<OData<F>> {
+        self.0
+    }
+}
+
+impl<F> From<ODataQuery<F>> for QueryFilter<OData<F>> {
+    fn from(query: ODataQuery<F>) -> Self {
+        query.0
+    }
+}
+
+#[derive(Deserialize)]
+struct RawQuery {
+    #[serde(rename = "$filter")]
+    filter: Option<String>,
+    #[serde(rename = "$orderby")]
+    orderby: Option<String>,
+    #[serde(rename = "$top")]
+    top: Option<u32>,
+    #[serde(rename = "$skip")]
+    skip: Option<u32>,
+}
+
+impl<'de, F: ExprFields + Filter> Deserialize<'de> for ODataQuery<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawQuery::deserialize(deserializer)?;
+
+        let filter = raw
+            .filter
+            .map(|filter| filter.parse::<OData<F>>())
+            .transpose()
+            .map_err(|err| de::Error::custom(format_args!("invalid $filter: {}", err)))?;
+
+        let (sort, order) = match raw.orderby.as_deref().map(str::trim) {
+            Some(orderby) if !orderby.is_empty() => {
+                let (sort, order) = parse_orderby::<F>(orderby).map_err(de::Error::custom)?;
+                (Some(sort.to_owned()), order.map(str::to_owned))
+            }
+            _ => (None, None),
+        };
+
+        let start = raw.skip.map(clamp);
+        let end = raw
+            .top
+            .map(|top| clamp(raw.skip.unwrap_or_default().saturating_add(top)));
+
+        Ok(Self(QueryFilter {
+            start,
+            end,
+            sort,
+            order,
+            q: None,
+            page: None,
+            per_page: None,
+            fields: None,
+            filter,
+        }))
+    }
+}
+
+fn clamp(value: u32) -> i32 {
+    value.min(i32::MAX as u32) as i32
+}
+
+fn parse_orderby<F: ExprFields + Filter>(
+    orderby: &str,
+) -> Result<(&'static str, Option<&'static str>), String> {
+    if orderby.contains(',') {
+        return Err("$orderby supports a single field".to_owned());
+    }
+
+    let mut words = orderby.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let order = match words.next() {
+        None => None,
+        Some("asc") => Some("ASC"),
+        Some("desc") => Some("DESC"),
+        Some(other) => {
+            return Err(format!(
+                "invalid $orderby direction `{}`, expected `asc` or `desc`",
+                other
+            ))
+        }
+    };
+    if let Some(extra) = words.next() {
+        return Err(format!("unexpected `{}` in $orderby", extra));
+    }
+
+    let column = F::field(name).map_or(name, |field| field.column);
+    match F::validate_sortable_field(column) {
+        Some(sort) => Ok((sort, order)),
+        None => Err(format!("`{}` can't be used in $orderby", name)),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "`{}`", word),
+            Token::Str(value) => write!(f, "'{}'", value),
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+            Token::Comma => f.write_str("`,`"),
+            Token::End => f.write_str("the end"),
+        }
+    }
+}
+
+fn error(offset: usize, message: String) -> ODataError {
+    ODataError { offset, message }
+}
+
+/// Splits `$filter` into tokens, each with its offset in characters
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ODataError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().enumerate().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            // Quotes are escaped by doubling them, `'O''Neil'`
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) if chars.peek().map(|(_, c)| *c) == Some('\'') => {
+                            chars.next();
+                            value.push('\'');
+                        }
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => return Err(error(offset, "unterminated string".to_owned())),
+                    }
+                }
+                Token::Str(value)
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some((_, c)) = chars.peek() {
+                    if c.is_whitespace() || "(),'".contains(*c) {
+                        break;
+                    }
+                    word.push(*c);
+                    chars.next();
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push((offset, token));
+    }
+
+    tokens.push((input.chars().count(), Token::End));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    depth: usize,
+    fields: &'static [Field],
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<FilterExpr<'static>, ODataError> {
+        let mut exprs = vec![self.and()?];
+        while self.eat_word("or") {
+            exprs.push(self.and()?);
+        }
+        Ok(flatten(exprs, FilterExpr::Or))
+    }
+
+    fn and(&mut self) -> Result<FilterExpr<'static>, ODataError> {
+        let mut exprs = vec![self.unary()?];
+        while self.eat_word("and") {
+            exprs.push(self.unary()?);
+        }
+        Ok(flatten(exprs, FilterExpr::And))
+    }
+
+    fn unary(&mut self) -> Result<FilterExpr<'static>, ODataError> {
+        let offset = self.peek().0;
+        if self.eat_word("not") {
+            self.enter(offset)?;
+            let expr = self.unary()?;
+            self.depth -= 1;
+            return Ok(FilterExpr::Not(Box::new(expr)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<FilterExpr<'static>, ODataError> {
+        let (offset, token) = self.next();
+        match token {
+            Token::LParen => {
+                self.enter(offset)?;
+                let expr = self.or()?;
+                self.depth -= 1;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Token::Word(name) if self.peek().1 == Token::LParen => self.function(offset, &name),
+            Token::Word(name) => {
+                let field = self.field(offset, &name)?;
+                self.comparison(field)
+            }
+            token => Err(error(
+                offset,
+                format!("expected a field or a function, found {}", token),
+            )),
+        }
+    }
+
+    /// One more level of `not` or parentheses, see [`MAX_DEPTH`]
+    fn enter(&mut self, offset: usize) -> Result<(), ODataError> {
+        if self.depth == MAX_DEPTH {
+            return Err(error(
+                offset,
+                format!("nested deeper than {} levels", MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn comparison(&mut self, field: &'static Field) -> Result<FilterExpr<'static>, ODataError> {
+        let (offset, token) = self.next();
+        let op = match token {
+            Token::Word(op) => match op.as_str() {
+                "eq" => CompareOp::Eq,
+                "ne" => CompareOp::Ne,
+                "lt" => CompareOp::Lt,
+                "le" => CompareOp::Le,
+                "gt" => CompareOp::Gt,
+                "ge" => CompareOp::Ge,
+                "in" => return self.in_list(field),
+                "has" => {
+                    return Err(error(
+                        offset,
+                        "the `has` operator isn't supported".to_owned(),
+                    ))
+                }
+                _ => return Err(error(offset, format!("unknown operator `{}`", op))),
+            },
+            token => {
+                return Err(error(
+                    offset,
+                    format!("expected an operator, found {}", token),
+                ))
+            }
+        };
+
+        let (offset, value) = self.literal()?;
+        FilterExpr::compare(field, op, &value).map_err(|message| error(offset, message))
+    }
+
+    fn in_list(&mut self, field: &'static Field) -> Result<FilterExpr<'static>, ODataError> {
+        let offset = self.expect(Token::LParen)?;
+        let mut values = vec![self.literal()?.1];
+        while self.peek().1 == Token::Comma {
+            self.next();
+            values.push(self.literal()?.1);
+        }
+        self.expect(Token::RParen)?;
+        FilterExpr::one_of(field, &values, false).map_err(|message| error(offset, message))
+    }
+
+    fn function(&mut self, offset: usize, name: &str) -> Result<FilterExpr<'static>, ODataError> {
+        let filter: fn(String) -> StringFilter<'static> = match name {
+            "contains" => |value| StringFilter::Contains(value.into()),
+            "startswith" => |value| StringFilter::StartsWith(value.into()),
+            "endswith" => |value| StringFilter::EndsWith(value.into()),
+            _ => {
+                return Err(error(
+                    offset,
+                    format!(
+                        "unsupported function `{}`, only contains, startswith and endswith are",
+                        name
+                    ),
+                ))
+            }
+        };
+
+        self.expect(Token::LParen)?;
+        let (field_offset, token) = self.next();
+        let field = match token {
+            Token::Word(field) => self.field(field_offset, &field)?,
+            token => {
+                return Err(error(
+                    field_offset,
+                    format!("expected a field, found {}", token),
+                ))
+            }
+        };
+        if field.kind != FieldKind::String {
+            return Err(error(
+                field_offset,
+                format!("`{}` needs a text field, `{}` isn't one", name, field.name),
+            ));
+        }
+        self.expect(Token::Comma)?;
+        let value = match self.next() {
+            (_, Token::Str(value)) => value,
+            (offset, token) => {
+                return Err(error(
+                    offset,
+                    format!("expected a quoted string, found {}", token),
+                ))
+            }
+        };
+        self.expect(Token::RParen)?;
+
+        Ok(FilterExpr::Field(field, FieldFilter::String(filter(value))))
+    }
+
+    fn field(&self, offset: usize, name: &str) -> Result<&'static Field, ODataError> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| error(offset, format!("unknown field `{}`", name)))
+    }
+
+    /// A quoted string or a bare number, date, GUID and the like
+    fn literal(&mut self) -> Result<(usize, String), ODataError> {
+        match self.next() {
+            (offset, Token::Word(word)) if word == "null" => Err(error(
+                offset,
+                "comparing to null isn't supported".to_owned(),
+            )),
+            (offset, Token::Word(value) | Token::Str(value)) => Ok((offset, value)),
+            (offset, token) => Err(error(offset, format!("expected a value, found {}", token))),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<usize, ODataError> {
+        let (offset, token) = self.next();
+        if token == expected {
+            Ok(offset)
+        } else {
+            Err(error(
+                offset,
+                format!("expected {}, found {}", expected, token),
+            ))
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if matches!(&self.peek().1, Token::Word(w) if w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> &(usize, Token) {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> (usize, Token) {
+        let (offset, token) = &mut self.tokens[self.pos];
+        let offset = *offset;
+        if *token == Token::End {
+            return (offset, Token::End);
+        }
+        self.pos += 1;
+        (offset, std::mem::replace(token, Token::End))
+    }
+}
+
+fn flatten(
+    mut exprs: Vec<FilterExpr<'static>>,
+    group: fn(Vec<FilterExpr<'static>>) -> FilterExpr<'static>,
+) -> FilterExpr<'static> {
+    if exprs.len() == 1 {
+        exprs.remove(0)
+    } else {
+        group(exprs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::{parse_filter, ODataQuery};
+    use crate::expr::{ExprFields, Field, FieldFilter, FieldKind, FilterExpr};
+    use crate::filters::{NumberFilter, StringFilter};
+    use crate::Filter;
+
+    static FIELDS: &[Field] = &[
+        Field::new("Age", FieldKind::Number).column("age"),
+        Field::new("Name", FieldKind::String).column("name"),
+        Field::new("CreatedAt", FieldKind::DateTimeTz).column("created_at"),
+    ];
+
+    struct UserFields;
+
+    impl ExprFields for UserFields {
+        const FIELDS: &'static [Field] = FIELDS;
+    }
+
+    impl Filter for UserFields {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["age", "created_at"];
+    }
+
+    fn age(filter: NumberFilter) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[0], FieldFilter::Number(filter))
+    }
+
+    fn name(filter: StringFilter<'static>) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[1], FieldFilter::String(filter))
+    }
+
+    fn error(input: &str) -> (usize, String) {
+        let err = parse_filter(input, FIELDS).unwrap_err();
+        (err.offset(), err.message().to_owned())
+    }
+
+    #[test]
+    fn filter() {
+        assert_eq!(
+            parse_filter("Age gt 20 and startswith(Name,'Jo')", FIELDS).unwrap(),
+            FilterExpr::And(vec![
+                age(NumberFilter::GreaterThan(20)),
+                name(StringFilter::StartsWith("Jo".into())),
+            ])
+        );
+
+        assert_eq!(
+            parse_filter(
+                "not (Name eq 'O''Neil' or Age in (1, 2)) and Age le 60",
+                FIELDS
+            )
+            .unwrap(),
+            FilterExpr::And(vec![
+                FilterExpr::Not(Box::new(FilterExpr::Or(vec![
+                    name(StringFilter::Equals("O'Neil".into())),
+                    FilterExpr::Or(vec![
+                        age(NumberFilter::Equals(1)),
+                        age(NumberFilter::Equals(2)),
+                    ]),
+                ]))),
+                age(NumberFilter::LesserThanEqual(60)),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("tolower(Name) eq 'jo'"),
+            (
+                0,
+                "unsupported function `tolower`, only contains, startswith and endswith are"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            error("contains(Age,'1')"),
+            (
+                9,
+                "`contains` needs a text field, `Age` isn't one".to_owned()
+            )
+        );
+        assert_eq!(
+            error("Age gt 20 and Email eq 'x'"),
+            (14, "unknown field `Email`".to_owned())
+        );
+        assert_eq!(
+            error("Age eq null"),
+            (7, "comparing to null isn't supported".to_owned())
+        );
+        assert_eq!(
+            error("Age like 2"),
+            (4, "unknown operator `like`".to_owned())
+        );
+        assert_eq!(
+            error("(Age eq 1"),
+            (9, "expected `)`, found the end".to_owned())
+        );
+        assert_eq!(error("Name eq 'x"), (8, "unterminated string".to_owned()));
+        assert_eq!(
+            error("Name gt 'x'"),
+            (8, "`>` isn't supported on the text field `Name`".to_owned())
+        );
+    }
+
+    #[test]
+    fn depth() {
+        let nested = |depth: usize| format!("{}Age eq 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse_filter(&nested(32), FIELDS).is_ok());
+        assert_eq!(
+            error(&nested(33)),
+            (32, "nested deeper than 32 levels".to_owned())
+        );
+        assert_eq!(error(&nested(5000)).0, 32);
+
+        let negated = |depth: usize| format!("{}Age eq 1", "not ".repeat(depth));
+        assert!(parse_filter(&negated(32), FIELDS).is_ok());
+        assert_eq!(
+            error(&negated(5000)),
+            (128, "nested deeper than 32 levels".to_owned())
+        );
+    }
+
+    #[test]
+    fn query() {
+        let query = from_str::<ODataQuery<UserFields>>(
+            "$filter=Age%20gt%2020&$orderby=CreatedAt%20desc&$top=50&$skip=100",
+            ParseMode::Brackets,
+        )
+        .unwrap()
+        .into_inner();
+
+        assert_eq!(query.get_offset(), 100);
+        assert_eq!(query.get_limit(query.get_offset()), 50);
+        assert_eq!(query.get_sort(), Some("created_at"));
+        assert_eq!(query.get_order().as_str(), "DESC");
+        assert_eq!(
+            query.filter.unwrap().into_expr(),
+            age(NumberFilter::GreaterThan(20))
+        );
+
+        let err =
+            from_str::<ODataQuery<UserFields>>("$orderby=Name", ParseMode::Brackets).unwrap_err();
+        assert!(err.to_string().contains("`Name` can't be used in $orderby"));
+
+        let err = from_str::<ODataQuery<UserFields>>("$orderby=Age,Name", ParseMode::Brackets)
+            .unwrap_err();
+        assert!(err.to_string().contains("$orderby supports a single field"));
+
+        let err = from_str::<ODataQuery<UserFields>>(
+            "$filter=substringof('a',Name)",
+            ParseMode::Brackets,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("invalid $filter: unsupported function `substringof`"));
+    }
+
+    #[cfg(feature = "seaq")]
+    #[test]
+    fn apply() {
+        use sea_query::{Iden, PostgresQueryBuilder, Query};
+
+        use crate::seaq::ApplyFilters;
+
+        #[derive(Iden)]
+        struct User;
+        #[derive(Iden)]
+        struct Id;
+
+        let query = from_str::<ODataQuery<UserFields>>(
+            "$filter=Age%20gt%2020%20and%20startswith(Name,'Jo')&$orderby=Age&$top=20",
+            ParseMode::Brackets,
+        )
+        .unwrap()
+        .into_inner();
+
+        let q = Query::select()
+            .column(Id)
+            .from(User)
+            .apply_filters(&query)
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(
+            q,
+            "SELECT \"id\" FROM \"user\" WHERE \"age\" > 20 AND \"name\" LIKE 'Jo%' \
+             ORDER BY \"age\" ASC LIMIT 20 OFFSET 0"
+        );
+    }
+}
@@ -1,13 +1,21 @@
 use std::fmt;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Default, Deserialize, Debug, PartialEq)]
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq)]
 pub struct QueryFilter<T> {
     pub start: Option<i32>,
     pub end: Option<i32>,
     pub sort: Option<String>,
     pub order: Option<String>,
+    /// Free text for a global search box, looked up in [`Filter::SEARCHABLE_FIELDS`]
+    pub q: Option<String>,
+    /// Page number, counted from [`Filter::get_first_page`], used instead of `start`/`end` when
+    /// it or `per_page` is given
+    pub page: Option<i32>,
+    pub per_page: Option<i32>,
+    /// Comma separated columns to select, out of [`Filter::SELECTABLE_FIELDS`]
+    pub fields: Option<String>,
 
     pub filter: Option<T>,
 }
@@ -17,25 +25,45 @@ where
     T: Filter,
 {
     pub fn get_offset(&self) -> i32 {
-        // Check if start is more than 0
-        if let Some(offset) = self.start {
-            offset
-        } else {
-            0
+        if self.is_paged() {
+            let first = T::get_first_page();
+            let page = self.page.unwrap_or(first).saturating_sub(first);
+            return std::cmp::max(page, 0).saturating_mul(self.get_limit(0));
         }
+        std::cmp::max(self.start.unwrap_or_default(), 0)
     }
 
+    /// The page size, or `end - offset`, at least 1 and at most [`Filter::get_max_limit`]
     pub fn get_limit(&self, offset: i32) -> i32 {
-        if let Some(end) = self.end {
-            std::cmp::max(end - offset, 1)
+        let limit = if self.is_paged() {
+            self.per_page.unwrap_or(10)
+        } else if let Some(end) = self.end {
+            end.saturating_sub(offset)
         } else {
             10
-        }
+        };
+        limit.clamp(1, std::cmp::max(T::get_max_limit(), 1))
+    }
+
+    /// Whether the page number style, `page` and `per_page`, is used
+    pub fn is_paged(&self) -> bool {
+        self.page.is_some() || self.per_page.is_some()
+    }
+
+    /// How many pages `count` rows make with the current limit
+    pub fn total_pages(&self, count: u64) -> u64 {
+        count.div_ceil(self.get_limit(self.get_offset()) as u64)
+    }
+
+    /// Whether there are rows after the current page, out of `count`
+    pub fn has_next(&self, count: u64) -> bool {
+        let offset = self.get_offset();
+        (offset as u64).saturating_add(self.get_limit(offset) as u64) < count
     }
 
     pub fn get_sort(&self) -> Option<&'static str> {
         if let Some(ref field) = self.sort {
-            return T::validate_sortable_field(field);
+            T::validate_sortable_field(field)
         } else {
             None
         }
@@ -52,26 +80,63 @@ where
     pub fn get_filter(&self) -> Option<&T> {
         self.filter.as_ref()
     }
+
+    /// The columns asked for in `fields`, after [`Filter::KEY_FIELDS`]
+    ///
+    /// Unknown and repeated names are skipped, like an unknown `sort`. `None` when `fields` is
+    /// missing or names nothing selectable. `ApplyFilters` only uses it on a `SelectStatement`
+    /// selecting nothing yet, a sea-orm `Select<E>` has to be narrowed with it by hand.
+    pub fn get_fields(&self) -> Option<Vec<&'static str>> {
+        let mut fields = T::KEY_FIELDS.to_vec();
+        for field in self.fields.as_deref()?.split(',').map(str::trim) {
+            match T::validate_selectable_field(field) {
+                Some(field) if !fields.contains(&field) => fields.push(field),
+                _ => {}
+            }
+        }
+        (fields.len() > T::KEY_FIELDS.len()).then_some(fields)
+    }
+
+    /// The trimmed `q` parameter, if there's anything to search and anywhere to search it
+    pub fn get_q(&self) -> Option<&str> {
+        match self.q.as_deref().map(str::trim) {
+            Some(q) if !q.is_empty() && !T::SEARCHABLE_FIELDS.is_empty() => Some(q),
+            _ => None,
+        }
+    }
 }
 
 pub trait Filter {
     const SORTABLE_FIELDS: &'static [&'static str];
+    /// Columns the `q` parameter is matched against, a row matches if any of them contains it
+    const SEARCHABLE_FIELDS: &'static [&'static str] = &[];
+    /// Columns clients may pick with the `fields` parameter
+    const SELECTABLE_FIELDS: &'static [&'static str] = &[];
+    /// Columns always selected when `fields` is given, like the primary key
+    const KEY_FIELDS: &'static [&'static str] = &[];
 
     fn validate_sortable_field(field: &str) -> Option<&'static str> {
-        for f in Self::SORTABLE_FIELDS.iter() {
-            if f == &field {
-                return Some(f);
-            }
-        }
-        if Self::SORTABLE_FIELDS.iter().any(|f| f == &field) {}
-        None
+        Self::SORTABLE_FIELDS.iter().find(|f| *f == &field).copied()
+    }
+
+    fn validate_selectable_field(field: &str) -> Option<&'static str> {
+        Self::SELECTABLE_FIELDS
+            .iter()
+            .find(|f| *f == &field)
+            .copied()
     }
 
     fn get_max_limit() -> i32 {
         100
     }
+
+    /// The number of the first page for `page`, 1 unless overridden to count pages from 0
+    fn get_first_page() -> i32 {
+        1
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Order {
     Asc,
     Desc,
@@ -93,6 +158,12 @@ impl std::fmt::Display for Order {
     }
 }
 
+impl Serialize for Order {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 impl std::str::FromStr for Order {
     type Err = ();
     fn from_str(val: &str) -> Result<Self, Self::Err> {
@@ -142,6 +213,23 @@ mod openapi {
                         "order",
                         ObjectBuilder::new().schema_type(SchemaType::String),
                     )
+                    .property("q", ObjectBuilder::new().schema_type(SchemaType::String))
+                    .property(
+                        "page",
+                        ObjectBuilder::new()
+                            .schema_type(SchemaType::Integer)
+                            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32))),
+                    )
+                    .property(
+                        "per_page",
+                        ObjectBuilder::new()
+                            .schema_type(SchemaType::Integer)
+                            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32))),
+                    )
+                    .property(
+                        "fields",
+                        ObjectBuilder::new().schema_type(SchemaType::String),
+                    )
                     .property("filter", T::schema().1)
                     .into(),
             )
@@ -194,6 +282,42 @@ mod openapi {
                             .enum_values(Some(["ASC", "DESC"])),
                     ))
                     .build(),
+                ParameterBuilder::new()
+                    .name("q")
+                    .parameter_in(ParameterIn::Query)
+                    .style(Some(ParameterStyle::Form))
+                    .required(Required::False)
+                    .schema(Some(ObjectBuilder::new().schema_type(SchemaType::String)))
+                    .build(),
+                ParameterBuilder::new()
+                    .name("fields")
+                    .parameter_in(ParameterIn::Query)
+                    .style(Some(ParameterStyle::Form))
+                    .required(Required::False)
+                    .schema(Some(ObjectBuilder::new().schema_type(SchemaType::String)))
+                    .build(),
+                ParameterBuilder::new()
+                    .name("page")
+                    .parameter_in(ParameterIn::Query)
+                    .style(Some(ParameterStyle::Form))
+                    .required(Required::False)
+                    .schema(Some(
+                        ObjectBuilder::new()
+                            .schema_type(SchemaType::Integer)
+                            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32))),
+                    ))
+                    .build(),
+                ParameterBuilder::new()
+                    .name("per_page")
+                    .parameter_in(ParameterIn::Query)
+                    .style(Some(ParameterStyle::Form))
+                    .required(Required::False)
+                    .schema(Some(
+                        ObjectBuilder::new()
+                            .schema_type(SchemaType::Integer)
+                            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32))),
+                    ))
+                    .build(),
                 ParameterBuilder::new()
                     .name("filter")
                     .parameter_in(ParameterIn::Query)
@@ -210,7 +334,32 @@ mod openapi {
 
 #[cfg(feature = "seaq")]
 mod seaq {
-    use super::Order;
+    use sea_query::{Cond, Expr, Func, IntoCondition};
+
+    use super::{Filter, Order, QueryFilter};
+    use crate::{
+        seaq::{like_expr, IntoColumnRefStr},
+        syntax::escape_like,
+    };
+
+    impl<T: Filter> QueryFilter<T> {
+        /// `q` as a case-insensitive contains ORed over [`Filter::SEARCHABLE_FIELDS`]
+        ///
+        /// `%`, `_` and `\` in `q` are matched literally.
+        pub fn get_q_cond(&self) -> Option<Cond> {
+            let pattern = format!("%{}%", escape_like(&self.get_q()?.to_lowercase()));
+
+            let mut cond = Cond::any();
+            for field in T::SEARCHABLE_FIELDS {
+                cond = cond.add(
+                    Expr::expr(Func::lower(Expr::col(IntoColumnRefStr(field))))
+                        .like(like_expr(pattern.clone()))
+                        .into_condition(),
+                );
+            }
+            Some(cond)
+        }
+    }
 
     impl Order {
         pub fn to_seaquery(&self) -> sea_query::Order {
@@ -221,3 +370,93 @@ mod seaq {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{de::DeserializeOwned, Deserialize};
+
+    use super::{Filter, QueryFilter};
+
+    #[derive(Default, Deserialize)]
+    struct Items;
+
+    impl Filter for Items {
+        const SORTABLE_FIELDS: &'static [&'static str] = &[];
+
+        fn get_max_limit() -> i32 {
+            50
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct ZeroBased;
+
+    impl Filter for ZeroBased {
+        const SORTABLE_FIELDS: &'static [&'static str] = &[];
+
+        fn get_first_page() -> i32 {
+            0
+        }
+    }
+
+    fn range<T: Filter + DeserializeOwned>(query: &str) -> (i32, i32) {
+        let filters = serde_querystring::from_str::<QueryFilter<T>>(
+            query,
+            serde_querystring::de::ParseMode::Brackets,
+        )
+        .unwrap();
+        let offset = filters.get_offset();
+        (offset, filters.get_limit(offset))
+    }
+
+    #[test]
+    fn pages() {
+        assert_eq!(range::<Items>("page=3&per_page=25"), (50, 25));
+        assert_eq!(range::<Items>("page=1"), (0, 10));
+        assert_eq!(range::<Items>("per_page=20"), (0, 20));
+        assert_eq!(range::<Items>("page=0&per_page=20"), (0, 20));
+        assert_eq!(range::<Items>("page=2&per_page=500"), (50, 50));
+        assert_eq!(range::<Items>("page=2&per_page=0"), (1, 1));
+        assert_eq!(range::<Items>("page=-2147483648"), (0, 10));
+        assert_eq!(
+            range::<Items>("page=2147483647&per_page=50"),
+            (i32::MAX, 50)
+        );
+        // Page numbers win over start and end
+        assert_eq!(range::<Items>("start=5&end=10&page=2"), (10, 10));
+
+        assert_eq!(range::<ZeroBased>("page=0&per_page=25"), (0, 25));
+        assert_eq!(range::<ZeroBased>("page=3&per_page=25"), (75, 25));
+    }
+
+    #[test]
+    fn max_limit() {
+        // `start`/`end` ranges used to be taken as given (490 here), they are capped like pages now
+        assert_eq!(range::<Items>("start=10&end=500"), (10, 50));
+        assert_eq!(range::<Items>("start=10&end=5"), (10, 1));
+        assert_eq!(range::<Items>("start=-5&end=10"), (0, 10));
+        assert_eq!(range::<ZeroBased>("start=0&end=100"), (0, 100));
+    }
+
+    #[test]
+    fn metadata() {
+        let filters = QueryFilter::<Items> {
+            page: Some(3),
+            per_page: Some(25),
+            ..Default::default()
+        };
+        assert_eq!(filters.total_pages(0), 0);
+        assert_eq!(filters.total_pages(75), 3);
+        assert_eq!(filters.total_pages(76), 4);
+        assert!(!filters.has_next(75));
+        assert!(filters.has_next(76));
+
+        let filters = QueryFilter::<Items> {
+            start: Some(0),
+            end: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(filters.total_pages(95), 10);
+        assert!(filters.has_next(11));
+    }
+}
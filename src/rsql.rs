@@ -0,0 +1,613 @@
+//! An RSQL/FIQL front end, for clients sending `filter=age=ge=20;name==John*,status=in=(a,b)`
+//!
+//! `;` (or `and`) binds tighter than `,` (or `or`) and parentheses group. The operators are `==`,
+//! `!=`, `=lt=`/`<`, `=le=`/`<=`, `=gt=`/`>`, `=ge=`/`>=`, `=in=` and `=out=`. On text fields a
+//! `*` at the start or the end of an `==`/`!=` value turns it into an ends-with, starts-with or
+//! contains match. Values with reserved characters or spaces go in single or double quotes,
+//! with `\` escaping the quote. Parentheses nest up to [`MAX_DEPTH`] levels.
+//!
+//! Selectors are looked up in a whitelist of [`Field`]s, whose kind decides which `*Filter` the
+//! value is parsed into, and errors point at the offending character.
+
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::expr::{CompareOp, ExprFields, Field, FieldFilter, FieldKind, FilterExpr, MAX_DEPTH};
+use crate::filters::StringFilter;
+use crate::Filter;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsqlError {
+    offset: usize,
+    message: String,
+}
+
+impl RsqlError {
+    /// Where the error is in the input, counted in characters
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for RsqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for RsqlError {}
+
+/// Parse an RSQL expression using the fields in `fields`
+pub fn parse(input: &str, fields: &'static [Field]) -> Result<FilterExpr<'static>, RsqlError> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        depth: 0,
+        fields,
+    };
+    let expr = parser.or()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(expr),
+        Some(c) => Err(parser.error(format!("unexpected `{}`", c))),
+    }
+}
+
+/// An RSQL expression as a `QueryFilter`'s `filter`, over the fields listed by `F`
+///
+/// `F` usually is a unit struct implementing [`ExprFields`] for the whitelist and [`Filter`] for
+/// the sortable fields and the limit, both of which are forwarded.
+pub struct Rsql<F> {
+    expr: FilterExpr<'static>,
+    fields: PhantomData<fn() -> F>,
+}
+
+impl<F> Rsql<F> {
+    pub fn expr(&self) -> &FilterExpr<'static> {
+        &self.expr
+    }
+
+    pub fn into_expr(self) -> FilterExpr<'static> {
+        self.expr
+    }
+}
+
+impl<F> fmt::Debug for Rsql<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rsql").field(&self.expr).finish()
+    }
+}
+
+impl<F: ExprFields> FromStr for Rsql<F> {
+    type Err = RsqlError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            expr: parse(input, F::FIELDS)?,
+            fields: PhantomData,
+        })
+    }
+}
+
+impl<'de, F: ExprFields> Deserialize<'de> for Rsql<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        input.parse().map_err(de::Error::custom)
+    }
+}
+
+impl<F: Filter> Filter for Rsql<F> {
+    const SORTABLE_FIELDS: &'static [&'static str] = F::SORTABLE_FIELDS;
+    const SEARCHABLE_FIELDS: &'static [&'static str] = F::SEARCHABLE_FIELDS;
+    const SELECTABLE_FIELDS: &'static [&'static str] = F::SELECTABLE_FIELDS;
+    const KEY_FIELDS: &'static [&'static str] = F::KEY_FIELDS;
+
+    fn get_max_limit() -> i32 {
+        F::get_max_limit()
+    }
+
+    fn get_first_page() -> i32 {
+        F::get_first_page()
+    }
+}
+
+#[cfg(feature = "seaq")]
+impl<F> crate::seaq::ToCond for Rsql<F> {
+    fn to_cond(&self) -> sea_query::Cond {
+        self.expr.to_cond()
+    }
+}
+
+enum Operator {
+    Compare(CompareOp),
+    In,
+    Out,
+}
+
+enum Arguments {
+    One(String),
+    List(Vec<String>),
+}
+
+struct Parser<'i> {
+    input: &'i str,
+    pos: usize,
+    depth: usize,
+    fields: &'static [Field],
+}
+
+impl<'i> Parser<'i> {
+    fn or(&mut self) -> Result<FilterExpr<'static>, RsqlError> {
+        let mut exprs = vec![self.and()?];
+        while self.eat_separator(',', "or") {
+            exprs.push(self.and()?);
+        }
+        Ok(flatten(exprs, FilterExpr::Or))
+    }
+
+    fn and(&mut self) -> Result<FilterExpr<'static>, RsqlError> {
+        let mut exprs = vec![self.constraint()?];
+        while self.eat_separator(';', "and") {
+            exprs.push(self.constraint()?);
+        }
+        Ok(flatten(exprs, FilterExpr::And))
+    }
+
+    fn constraint(&mut self) -> Result<FilterExpr<'static>, RsqlError> {
+        self.skip_whitespace();
+        if self.eat('(') {
+            if self.depth == MAX_DEPTH {
+                let message = format!("parentheses nested deeper than {} levels", MAX_DEPTH);
+                return Err(self.error_at(self.pos - 1, message));
+            }
+            self.depth += 1;
+            let expr = self.or()?;
+            self.depth -= 1;
+            self.skip_whitespace();
+            if !self.eat(')') {
+                return Err(self.expected("`)`"));
+            }
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<FilterExpr<'static>, RsqlError> {
+        let start = self.pos;
+        let selector = self.unreserved();
+        if selector.is_empty() {
+            return Err(self.expected("a selector"));
+        }
+        let field = self
+            .fields
+            .iter()
+            .find(|field| field.name == selector)
+            .ok_or_else(|| self.error_at(start, format!("unknown selector `{}`", selector)))?;
+
+        self.skip_whitespace();
+        let operator = self.operator()?;
+        self.skip_whitespace();
+        let start = self.pos;
+        let arguments = self.arguments()?;
+
+        let expr = match (operator, arguments) {
+            (Operator::In, Arguments::One(value)) => FilterExpr::one_of(field, &[value], false),
+            (Operator::In, Arguments::List(values)) => FilterExpr::one_of(field, &values, false),
+            (Operator::Out, Arguments::One(value)) => FilterExpr::one_of(field, &[value], true),
+            (Operator::Out, Arguments::List(values)) => FilterExpr::one_of(field, &values, true),
+            (Operator::Compare(op), Arguments::One(value)) => match wildcard(field, op, &value) {
+                Some(expr) => expr,
+                None => FilterExpr::compare(field, op, &value),
+            },
+            (Operator::Compare(_), Arguments::List(_)) => {
+                Err("a list of values needs `=in=` or `=out=`".to_owned())
+            }
+        };
+        expr.map_err(|message| self.error_at(start, message))
+    }
+
+    fn operator(&mut self) -> Result<Operator, RsqlError> {
+        let start = self.pos;
+        let rest = &self.input[self.pos..];
+
+        for (symbol, op) in [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ] {
+            if rest.starts_with(symbol) {
+                self.pos += symbol.len();
+                return Ok(Operator::Compare(op));
+            }
+        }
+
+        if !self.eat('=') {
+            return Err(self.expected("an operator"));
+        }
+        let name_len = self.input[self.pos..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.input.len() - self.pos);
+        let name = &self.input[self.pos..self.pos + name_len];
+        self.pos += name_len;
+        if !self.eat('=') {
+            return Err(self.error_at(start, "unterminated operator".to_owned()));
+        }
+
+        Ok(match name {
+            "lt" => Operator::Compare(CompareOp::Lt),
+            "le" => Operator::Compare(CompareOp::Le),
+            "gt" => Operator::Compare(CompareOp::Gt),
+            "ge" => Operator::Compare(CompareOp::Ge),
+            "in" => Operator::In,
+            "out" => Operator::Out,
+            _ => {
+                return Err(self.error_at(start, format!("unknown operator `={}=`", name)));
+            }
+        })
+    }
+
+    fn arguments(&mut self) -> Result<Arguments, RsqlError> {
+        if !self.eat('(') {
+            return self.value().map(Arguments::One);
+        }
+
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+            values.push(self.value()?);
+            self.skip_whitespace();
+            if self.eat(')') {
+                return Ok(Arguments::List(values));
+            }
+            if !self.eat(',') {
+                return Err(self.expected("`,` or `)`"));
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<String, RsqlError> {
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                let start = self.pos;
+                self.pos += 1;
+                let mut value = String::new();
+                let mut chars = self.input[self.pos..].char_indices();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => match chars.next() {
+                            Some((_, escaped)) => value.push(escaped),
+                            None => break,
+                        },
+                        c if c == quote => {
+                            self.pos += i + 1;
+                            return Ok(value);
+                        }
+                        c => value.push(c),
+                    }
+                }
+                Err(self.error_at(start, "unterminated quoted value".to_owned()))
+            }
+            _ => {
+                let value = self.unreserved();
+                if value.is_empty() {
+                    return Err(self.expected("a value"));
+                }
+                Ok(value.to_owned())
+            }
+        }
+    }
+
+    fn unreserved(&mut self) -> &'i str {
+        let input = self.input;
+        let rest = &input[self.pos..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "\"'();,=!~<>".contains(c))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// `;`/`,`, or `and`/`or` surrounded by whitespace
+    fn eat_separator(&mut self, symbol: char, keyword: &str) -> bool {
+        let start = self.pos;
+        self.skip_whitespace();
+        if self.eat(symbol) {
+            return true;
+        }
+
+        let spaced = self.pos > start;
+        let rest = &self.input[self.pos..];
+        if spaced && rest.starts_with(keyword) {
+            let next = rest[keyword.len()..].chars().next();
+            if next.is_some_and(|c| c.is_whitespace() || c == '(') {
+                self.pos += keyword.len();
+                return true;
+            }
+        }
+
+        self.pos = start;
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expected(&self, what: &str) -> RsqlError {
+        match self.peek() {
+            Some(c) => self.error(format!("expected {}, found `{}`", what, c)),
+            None => self.error(format!("expected {}, found the end", what)),
+        }
+    }
+
+    fn error(&self, message: String) -> RsqlError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: String) -> RsqlError {
+        RsqlError {
+            offset: self.input[..pos].chars().count(),
+            message,
+        }
+    }
+}
+
+fn flatten(
+    mut exprs: Vec<FilterExpr<'static>>,
+    group: fn(Vec<FilterExpr<'static>>) -> FilterExpr<'static>,
+) -> FilterExpr<'static> {
+    if exprs.len() == 1 {
+        exprs.remove(0)
+    } else {
+        group(exprs)
+    }
+}
+
+/// `name==John*` and friends on text fields
+fn wildcard(
+    field: &'static Field,
+    op: CompareOp,
+    value: &str,
+) -> Option<Result<FilterExpr<'static>, String>> {
+    if field.kind != FieldKind::String || !value.contains('*') {
+        return None;
+    }
+    let negated = match op {
+        CompareOp::Eq => false,
+        CompareOp::Ne => true,
+        _ => return None,
+    };
+
+    let inner = value.trim_start_matches('*').trim_end_matches('*');
+    if inner.contains('*') {
+        return Some(Err(format!(
+            "`*` is only supported at the start or the end of `{}`",
+            value
+        )));
+    }
+
+    let inner = inner.to_owned().into();
+    let filter = match (value.starts_with('*'), value.ends_with('*')) {
+        (true, true) if negated => {
+            let filter = FieldFilter::String(StringFilter::NotContains(inner));
+            return Some(Ok(FilterExpr::Field(field, filter)));
+        }
+        (true, true) => StringFilter::Contains(inner),
+        (true, false) => StringFilter::EndsWith(inner),
+        _ => StringFilter::StartsWith(inner),
+    };
+    let expr = FilterExpr::Field(field, FieldFilter::String(filter));
+    Some(Ok(match negated {
+        false => expr,
+        true => FilterExpr::Not(Box::new(expr)),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, RsqlError};
+    use crate::expr::{Field, FieldFilter, FieldKind, FilterExpr};
+    use crate::filters::{NumberFilter, StringFilter};
+
+    static FIELDS: &[Field] = &[
+        Field::new("age", FieldKind::Number),
+        Field::new("name", FieldKind::String),
+        Field::new("status", FieldKind::String),
+        Field::new("birthday", FieldKind::Date),
+    ];
+
+    fn age(filter: NumberFilter) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[0], FieldFilter::Number(filter))
+    }
+
+    fn string(field: usize, filter: StringFilter<'static>) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[field], FieldFilter::String(filter))
+    }
+
+    fn error(input: &str) -> (usize, String) {
+        let RsqlError { offset, message } = parse(input, FIELDS).unwrap_err();
+        (offset, message)
+    }
+
+    #[test]
+    fn precedence() {
+        let expr = parse("age=ge=20;name==John*,status=in=(a,b)", FIELDS).unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Or(vec![
+                FilterExpr::And(vec![
+                    age(NumberFilter::GreaterThanEqual(20)),
+                    string(1, StringFilter::StartsWith("John".into())),
+                ]),
+                FilterExpr::Or(vec![
+                    string(2, StringFilter::Equals("a".into())),
+                    string(2, StringFilter::Equals("b".into())),
+                ]),
+            ])
+        );
+
+        let expr = parse("age > 20 and (name=='Jo Ann' or age<=18)", FIELDS).unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(vec![
+                age(NumberFilter::GreaterThan(20)),
+                FilterExpr::Or(vec![
+                    string(1, StringFilter::Equals("Jo Ann".into())),
+                    age(NumberFilter::LesserThanEqual(18)),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn wildcards() {
+        assert_eq!(
+            parse("name==*ann", FIELDS).unwrap(),
+            string(1, StringFilter::EndsWith("ann".into()))
+        );
+        assert_eq!(
+            parse("name!=*ann*", FIELDS).unwrap(),
+            string(1, StringFilter::NotContains("ann".into()))
+        );
+        assert_eq!(
+            parse("name!=\"Jo*\"", FIELDS).unwrap(),
+            FilterExpr::Not(Box::new(string(1, StringFilter::StartsWith("Jo".into()))))
+        );
+        assert_eq!(
+            parse("status=out=(\"a\\\"b\",c)", FIELDS).unwrap(),
+            FilterExpr::And(vec![
+                string(2, StringFilter::NotEquals("a\"b".into())),
+                string(2, StringFilter::NotEquals("c".into())),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("age=ge=20;nme==x"),
+            (10, "unknown selector `nme`".to_owned())
+        );
+        assert_eq!(
+            error("age=like=20"),
+            (3, "unknown operator `=like=`".to_owned())
+        );
+        assert_eq!(
+            error("age=ge=twenty"),
+            (7, "`twenty` is not an integer for field `age`".to_owned())
+        );
+        assert_eq!(
+            error("name==\"ünïcode\";age<(1,2)"),
+            (20, "a list of values needs `=in=` or `=out=`".to_owned())
+        );
+        assert_eq!(
+            error("(age==1"),
+            (7, "expected `)`, found the end".to_owned())
+        );
+        assert_eq!(error("age==1)"), (6, "unexpected `)`".to_owned()));
+        assert_eq!(
+            error("name==J*n"),
+            (
+                6,
+                "`*` is only supported at the start or the end of `J*n`".to_owned()
+            )
+        );
+        assert_eq!(
+            error("birthday=gt=2022-13-01"),
+            (
+                12,
+                "`2022-13-01` is not a date for field `birthday`".to_owned()
+            )
+        );
+        assert_eq!(
+            parse("age==1;", FIELDS).unwrap_err().to_string(),
+            "expected a selector, found the end at character 7"
+        );
+    }
+
+    #[test]
+    fn depth() {
+        let nested = |depth: usize| format!("{}age==1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(32), FIELDS).is_ok());
+        assert_eq!(
+            error(&nested(33)),
+            (32, "parentheses nested deeper than 32 levels".to_owned())
+        );
+        assert_eq!(error(&nested(5000)).0, 32);
+    }
+
+    #[cfg(feature = "seaq")]
+    #[test]
+    fn query_filter() {
+        use sea_query::{Iden, PostgresQueryBuilder, Query};
+        use serde_querystring::de::ParseMode;
+
+        use super::Rsql;
+        use crate::{expr::ExprFields, seaq::ApplyFilters, Filter, QueryFilter};
+
+        #[derive(Iden)]
+        struct User;
+        #[derive(Iden)]
+        struct Id;
+
+        struct UserFields;
+
+        impl ExprFields for UserFields {
+            const FIELDS: &'static [Field] = FIELDS;
+        }
+
+        impl Filter for UserFields {
+            const SORTABLE_FIELDS: &'static [&'static str] = &["age"];
+        }
+
+        let filters = serde_querystring::from_str::<QueryFilter<Rsql<UserFields>>>(
+            "filter=age%3Dge%3D20%3Bname%3D%3DJohn*%2Cstatus%3Din%3D(a%2Cb)&sort=age&order=DESC",
+            ParseMode::Brackets,
+        )
+        .unwrap();
+
+        let q = Query::select()
+            .column(Id)
+            .from(User)
+            .apply_filters(&filters)
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(
+            q,
+            "SELECT \"id\" FROM \"user\" WHERE \
+             (\"age\" >= 20 AND \"name\" LIKE 'John%') OR (\"status\" = 'a' OR \"status\" = 'b') \
+             ORDER BY \"age\" DESC LIMIT 10 OFFSET 0"
+        );
+
+        let err = serde_querystring::from_str::<QueryFilter<Rsql<UserFields>>>(
+            "filter=email%3D%3Dx",
+            ParseMode::Brackets,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unknown selector `email` at character 0"));
+    }
+}
@@ -0,0 +1,359 @@
+//! Impls for applying filters to sea-orm's query builders
+//!
+//! Entity columns implement sea-query's `Iden`, so `E::Column` variants can be handed to
+//! [`ToFieldCond::to_cond`](crate::ToFieldCond::to_cond) directly, which makes the compiler check
+//! every filter field against the entity.
+
+use std::str::FromStr;
+
+use sea_orm::{
+    DbBackend, DeleteMany, EntityTrait, QueryFilter as _, QueryOrder, QuerySelect, QueryTrait,
+    Select, UpdateMany,
+};
+use sea_query::{IntoColumnRef, IntoTableRef};
+
+use crate::seaq::{ApplyConds, ApplyDeleteFilters, ApplyFilters, Backend, ToCond};
+use crate::{Filter, QueryFilter};
+
+impl<E: EntityTrait, T: ToCond> ApplyConds<T> for Select<E> {
+    fn apply_conds(self, filters: &T) -> Self {
+        self.filter(filters.to_cond())
+    }
+}
+
+impl<E: EntityTrait, T: ToCond> ApplyConds<T> for UpdateMany<E> {
+    fn apply_conds(self, filters: &T) -> Self {
+        self.filter(filters.to_cond())
+    }
+}
+
+impl<E: EntityTrait, T: ToCond> ApplyConds<T> for DeleteMany<E> {
+    fn apply_conds(self, filters: &T) -> Self {
+        self.filter(filters.to_cond())
+    }
+}
+
+impl<E: EntityTrait, T: Filter + ToCond> ApplyFilters<T> for Select<E> {
+    fn apply_backend_filters(self, filters: &QueryFilter<T>, backend: Backend) -> Self {
+        let offset = filters.get_offset();
+        let limit = filters.get_limit(offset);
+        let order = filters.get_order();
+        let sort = filters.get_sort();
+
+        let mut select = self;
+
+        // `fields` isn't applied, a `Select<E>` already selects every column of its entity and the
+        // model needs all of them. Narrow it with `get_fields()`, `select_only` and `into_json`.
+
+        if let Some(filter) = &filters.filter {
+            select = select.filter(filter.to_backend_cond(backend));
+        }
+
+        if let Some(q) = filters.get_q_cond() {
+            select = select.filter(q);
+        }
+
+        select = select.offset(offset as u64).limit(limit as u64);
+
+        if let Some(field) = sort {
+            // Sortable fields which aren't columns of the entity are ignored, like unknown ones
+            if let Some(expr) = filters.get_sort_expr(field) {
+                select = select.order_by(expr, order.to_seaquery())
+            } else if let Ok(column) = E::Column::from_str(field) {
+                select = select.order_by(column, order.to_seaquery())
+            }
+        }
+
+        select
+    }
+}
+
+// Only MySQL accepts the ORDER BY/LIMIT these add, see `ApplyDeleteFilters` for the others
+impl<E: EntityTrait, T: Filter + ToCond> ApplyFilters<T> for UpdateMany<E> {
+    fn apply_backend_filters(mut self, filters: &QueryFilter<T>, backend: Backend) -> Self {
+        QueryTrait::query(&mut self).apply_backend_filters(filters, backend);
+        self
+    }
+}
+
+impl<E: EntityTrait, T: Filter + ToCond> ApplyFilters<T> for DeleteMany<E> {
+    fn apply_backend_filters(mut self, filters: &QueryFilter<T>, backend: Backend) -> Self {
+        QueryTrait::query(&mut self).apply_backend_filters(filters, backend);
+        self
+    }
+}
+
+impl<E: EntityTrait, T: Filter + ToCond> ApplyDeleteFilters<T> for DeleteMany<E> {
+    fn apply_delete_filters<R, C>(
+        mut self,
+        filters: &QueryFilter<T>,
+        backend: Backend,
+        table: R,
+        key: C,
+    ) -> Self
+    where
+        R: IntoTableRef,
+        C: IntoColumnRef,
+    {
+        QueryTrait::query(&mut self).apply_delete_filters(filters, backend, table, key);
+        self
+    }
+}
+
+impl From<DbBackend> for Backend {
+    fn from(backend: DbBackend) -> Self {
+        match backend {
+            DbBackend::Postgres => Self::Postgres,
+            DbBackend::MySql => Self::MySql,
+            DbBackend::Sqlite => Self::Sqlite,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sea_orm::{DbBackend, EntityTrait, QuerySelect, QueryTrait};
+    use sea_query::{Cond, Expr};
+    use serde::Deserialize;
+    use serde_querystring::de::ParseMode;
+
+    use crate::{
+        filters::{NumberFilterSet, StringFilterSet},
+        ApplyConds, ApplyDeleteFilters, ApplyFilters, Backend, Filter, QueryFilter, ToCond,
+        ToFieldCond,
+    };
+
+    mod user {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "user")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+            pub age: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[derive(Deserialize)]
+    struct UserFilters<'a> {
+        name: Option<StringFilterSet<'a>>,
+        age: Option<NumberFilterSet>,
+    }
+
+    impl<'a> ToCond for UserFilters<'a> {
+        fn to_cond(&self) -> Cond {
+            self.to_backend_cond(Backend::Postgres)
+        }
+
+        fn to_backend_cond(&self, backend: Backend) -> Cond {
+            let mut cond = Cond::all();
+            if let Some(name) = self.name.to_backend_cond(user::Column::Name, backend) {
+                cond = cond.add(name)
+            }
+            if let Some(age) = self.age.to_cond(user::Column::Age) {
+                cond = cond.add(age)
+            }
+            cond
+        }
+    }
+
+    impl<'a> Filter for UserFilters<'a> {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["name", "age", "unknown"];
+        const SELECTABLE_FIELDS: &'static [&'static str] = &["name", "age", "unknown"];
+        const KEY_FIELDS: &'static [&'static str] = &["id"];
+    }
+
+    fn query_filters(query: &str) -> QueryFilter<UserFilters<'_>> {
+        serde_querystring::from_str(query, ParseMode::Brackets).unwrap()
+    }
+
+    #[test]
+    fn test_select_conds() {
+        let filters = query_filters("filter[age][gte]=20&filter[name][contains]=John");
+
+        let q = user::Entity::find()
+            .apply_conds(filters.get_filter().unwrap())
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "SELECT \"user\".\"id\", \"user\".\"name\", \"user\".\"age\" FROM \"user\" \
+             WHERE \"name\" LIKE '%John%' AND \"age\" >= 20"
+        );
+    }
+
+    #[test]
+    fn test_select_filters() {
+        let filters = query_filters(
+            "filter[age][gte]=20&filter[name][contains]=John&start=10&end=100&sort=age&order=DESC",
+        );
+
+        let q = user::Entity::find()
+            .apply_filters(&filters)
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "SELECT \"user\".\"id\", \"user\".\"name\", \"user\".\"age\" FROM \"user\" \
+             WHERE \"name\" LIKE '%John%' AND \"age\" >= 20 \
+             ORDER BY \"user\".\"age\" DESC LIMIT 90 OFFSET 10"
+        );
+    }
+
+    #[test]
+    fn test_select_sort_not_a_column() {
+        let filters = query_filters("sort=unknown");
+
+        let q = user::Entity::find()
+            .apply_filters(&filters)
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "SELECT \"user\".\"id\", \"user\".\"name\", \"user\".\"age\" FROM \"user\" \
+             LIMIT 10 OFFSET 0"
+        );
+    }
+
+    #[test]
+    fn test_select_fields() {
+        let filters = query_filters("fields=age,unknown&sort=age");
+
+        // The entity's columns stay, so the model can still be read
+        let q = user::Entity::find()
+            .apply_filters(&filters)
+            .build(DbBackend::Postgres)
+            .to_string();
+        assert_eq!(
+            q,
+            "SELECT \"user\".\"id\", \"user\".\"name\", \"user\".\"age\" FROM \"user\" \
+             ORDER BY \"user\".\"age\" ASC LIMIT 10 OFFSET 0"
+        );
+
+        let columns = filters
+            .get_fields()
+            .unwrap()
+            .into_iter()
+            .filter_map(|field| user::Column::from_str(field).ok());
+        let q = user::Entity::find()
+            .select_only()
+            .columns(columns)
+            .apply_filters(&filters)
+            .build(DbBackend::Postgres)
+            .to_string();
+        assert_eq!(
+            q,
+            "SELECT \"user\".\"id\", \"user\".\"age\" FROM \"user\" \
+             ORDER BY \"user\".\"age\" ASC LIMIT 10 OFFSET 0"
+        );
+    }
+
+    #[test]
+    fn test_update_many_conds() {
+        let filters = query_filters("filter[age][lt]=20");
+
+        let q = user::Entity::update_many()
+            .col_expr(user::Column::Name, Expr::value("minor"))
+            .apply_conds(filters.get_filter().unwrap())
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "UPDATE \"user\" SET \"name\" = 'minor' WHERE \"age\" < 20"
+        );
+    }
+
+    #[test]
+    fn test_update_many_filters() {
+        let filters = query_filters("filter[age][lt]=20&end=5&sort=age");
+
+        let q = user::Entity::update_many()
+            .col_expr(user::Column::Name, Expr::value("minor"))
+            .apply_filters(&filters)
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "UPDATE `user` SET `name` = 'minor' WHERE `age` < 20 ORDER BY `age` ASC LIMIT 5"
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_select_backend_filters() {
+        let filters = query_filters("filter[name][iregex]=^jo");
+
+        let q = user::Entity::find()
+            .apply_backend_filters(&filters, DbBackend::MySql.into())
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "SELECT `user`.`id`, `user`.`name`, `user`.`age` FROM `user` \
+             WHERE `name` REGEXP '(?i)^jo' LIMIT 10 OFFSET 0"
+        );
+    }
+
+    #[test]
+    fn test_delete_many_conds() {
+        let filters = query_filters("filter[age][lt]=20");
+
+        let q = user::Entity::delete_many()
+            .apply_conds(filters.get_filter().unwrap())
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(q, "DELETE FROM \"user\" WHERE \"age\" < 20");
+    }
+
+    #[test]
+    fn test_delete_many_mysql_filters() {
+        let filters = query_filters("filter[age][lt]=20&end=5&sort=age");
+
+        let q = user::Entity::delete_many()
+            .apply_filters(&filters)
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "DELETE FROM `user` WHERE `age` < 20 ORDER BY `age` ASC LIMIT 5"
+        );
+    }
+
+    #[test]
+    fn test_delete_many_filters() {
+        let filters = query_filters("filter[age][lt]=20&end=5&sort=age");
+
+        let q = user::Entity::delete_many()
+            .apply_delete_filters(
+                &filters,
+                DbBackend::Postgres.into(),
+                user::Entity,
+                user::Column::Id,
+            )
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            q,
+            "DELETE FROM \"user\" WHERE \"id\" IN (SELECT \"id\" FROM \"user\" \
+             WHERE \"age\" < 20 ORDER BY \"age\" ASC LIMIT 5)"
+        );
+    }
+}
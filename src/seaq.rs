@@ -1,16 +1,25 @@
 //! A set of traits and impls for converting filters into seaquery conditions
 
-use sea_query::{Cond, Iden, IntoColumnRef};
-use sea_query::{DeleteStatement, SelectStatement};
+use sea_query::{Cond, Expr, Iden, IntoColumnRef, IntoTableRef, LikeExpr, Query, SimpleExpr};
+use sea_query::{DeleteStatement, SelectStatement, UpdateStatement};
 
 use super::QueryFilter;
 use crate::Filter;
 
 pub trait ToFieldCond {
     fn to_cond<I: IntoColumnRef>(&self, iden: I) -> Option<Cond>;
+
+    /// Like `to_cond`, for filters whose SQL differs between databases
+    ///
+    /// `to_cond` renders those for Postgres, `StringFilter`'s regex operators being the only ones
+    /// so far. [`ApplyFilters::apply_backend_filters`] reaches this through
+    /// [`ToCond::to_backend_cond`].
+    fn to_backend_cond<I: IntoColumnRef>(&self, iden: I, _backend: Backend) -> Option<Cond> {
+        self.to_cond(iden)
+    }
 }
 
-impl<'b, T> ToFieldCond for Option<T>
+impl<T> ToFieldCond for Option<T>
 where
     T: ToFieldCond,
 {
@@ -20,6 +29,13 @@ where
             None => None,
         }
     }
+
+    fn to_backend_cond<I: IntoColumnRef>(&self, iden: I, backend: Backend) -> Option<Cond> {
+        match self {
+            Some(val) => val.to_backend_cond(iden, backend),
+            None => None,
+        }
+    }
 }
 
 impl ToFieldCond for () {
@@ -29,7 +45,30 @@ impl ToFieldCond for () {
 }
 
 pub trait ToCond {
+    /// Sort fields which aren't columns, only sortable through [`ToCond::sort_expr`]
+    ///
+    /// When `sort_expr` has nothing for one of them, say `relevance` without a search filter,
+    /// `ApplyFilters` leaves the statement unordered instead of ordering by a missing column.
+    const EXPR_SORT_FIELDS: &'static [&'static str] = &[];
+
     fn to_cond(&self) -> Cond;
+
+    /// `to_cond` for the given backend, what [`ApplyFilters::apply_backend_filters`] uses
+    ///
+    /// `to_cond` is taken for Postgres' conditions. Implement this one too, calling
+    /// [`ToFieldCond::to_backend_cond`] for the fields, when some filter renders differently
+    /// elsewhere, like `StringFilter`'s regex operators.
+    fn to_backend_cond(&self, _backend: Backend) -> Cond {
+        self.to_cond()
+    }
+
+    /// An expression to sort by for sort fields which aren't plain columns, like a search rank
+    ///
+    /// `ApplyFilters` falls back to ordering by the column named `field` when this is `None`,
+    /// unless the field is one of [`ToCond::EXPR_SORT_FIELDS`].
+    fn sort_expr(&self, _field: &str) -> Option<SimpleExpr> {
+        None
+    }
 }
 
 pub trait ApplyConds<T> {
@@ -50,12 +89,25 @@ impl<T: ToCond> ApplyConds<T> for &mut DeleteStatement {
     }
 }
 
-pub trait ApplyFilters<T> {
-    fn apply_filters(self, filters: &QueryFilter<T>) -> Self;
+impl<T: ToCond> ApplyConds<T> for &mut UpdateStatement {
+    fn apply_conds(self, filters: &T) -> Self {
+        let conds = filters.to_cond();
+        self.cond_where(conds)
+    }
 }
 
-impl<T: Filter + ToCond> ApplyFilters<T> for &mut SelectStatement {
+pub trait ApplyFilters<T>: Sized {
+    /// Applies the filters for Postgres, see [`ApplyFilters::apply_backend_filters`]
     fn apply_filters(self, filters: &QueryFilter<T>) -> Self {
+        self.apply_backend_filters(filters, Backend::Postgres)
+    }
+
+    /// Applies the filters with the conditions [`ToCond::to_backend_cond`] gives for `backend`
+    fn apply_backend_filters(self, filters: &QueryFilter<T>, backend: Backend) -> Self;
+}
+
+impl<T: Filter + ToCond> ApplyFilters<T> for &mut SelectStatement {
+    fn apply_backend_filters(self, filters: &QueryFilter<T>, backend: Backend) -> Self {
         let offset = filters.get_offset();
         let limit = filters.get_limit(offset);
         let order = filters.get_order();
@@ -63,14 +115,32 @@ impl<T: Filter + ToCond> ApplyFilters<T> for &mut SelectStatement {
 
         let mut statement = self;
 
+        // `fields` only fills a statement selecting nothing yet, columns picked by the server, like
+        // those of a join, are kept. Without it every selectable column is taken.
+        let mut selects = 0;
+        statement.exprs_mut_for_each(|_| selects += 1);
+        if selects == 0 {
+            let fields = filters.get_fields().unwrap_or_else(|| {
+                let selectable = T::SELECTABLE_FIELDS.iter();
+                let mut fields = T::KEY_FIELDS.to_vec();
+                fields.extend(selectable.filter(|field| !T::KEY_FIELDS.contains(field)));
+                fields
+            });
+            statement = statement.columns(fields.into_iter().map(IntoColumnRefStr));
+        }
+
         if let Some(filter) = &filters.filter {
-            statement = statement.apply_conds(filter);
+            statement = statement.cond_where(filter.to_backend_cond(backend));
+        }
+
+        if let Some(q) = filters.get_q_cond() {
+            statement = statement.cond_where(q);
         }
 
         statement = statement.offset(offset as u64).limit(limit as u64);
 
-        if let Some(field) = sort {
-            statement = statement.order_by(IntoColumnRefStr(field), order.to_seaquery())
+        if let Some(expr) = sort.and_then(|field| filters.get_order_expr(field)) {
+            statement = statement.order_by_expr(expr, order.to_seaquery())
         }
 
         statement
@@ -78,7 +148,34 @@ impl<T: Filter + ToCond> ApplyFilters<T> for &mut SelectStatement {
 }
 
 impl<T: Filter + ToCond> ApplyFilters<T> for &mut DeleteStatement {
-    fn apply_filters(self, filters: &QueryFilter<T>) -> Self {
+    fn apply_backend_filters(self, filters: &QueryFilter<T>, backend: Backend) -> Self {
+        let limit = filters.get_limit(0);
+        let order = filters.get_order();
+        let sort = filters.get_sort();
+
+        let mut statement = self;
+
+        if let Some(filter) = &filters.filter {
+            statement = statement.cond_where(filter.to_backend_cond(backend));
+        }
+
+        if let Some(q) = filters.get_q_cond() {
+            statement = statement.cond_where(q);
+        }
+
+        statement = statement.limit(limit as u64);
+
+        if let Some(expr) = sort.and_then(|field| filters.get_order_expr(field)) {
+            statement = statement.order_by_expr(expr, order.to_seaquery())
+        }
+
+        statement
+    }
+}
+
+// Like deletes, only MySQL accepts ORDER BY/LIMIT on an UPDATE
+impl<T: Filter + ToCond> ApplyFilters<T> for &mut UpdateStatement {
+    fn apply_backend_filters(self, filters: &QueryFilter<T>, backend: Backend) -> Self {
         let limit = filters.get_limit(0);
         let order = filters.get_order();
         let sort = filters.get_sort();
@@ -86,19 +183,129 @@ impl<T: Filter + ToCond> ApplyFilters<T> for &mut DeleteStatement {
         let mut statement = self;
 
         if let Some(filter) = &filters.filter {
-            statement = statement.apply_conds(filter);
+            statement = statement.cond_where(filter.to_backend_cond(backend));
+        }
+
+        if let Some(q) = filters.get_q_cond() {
+            statement = statement.cond_where(q);
         }
 
         statement = statement.limit(limit as u64);
 
-        if let Some(field) = sort {
-            statement = statement.order_by(IntoColumnRefStr(field), order.to_seaquery())
+        if let Some(expr) = sort.and_then(|field| filters.get_order_expr(field)) {
+            statement = statement.order_by_expr(expr, order.to_seaquery())
         }
 
         statement
     }
 }
 
+/// The database backend a statement is going to be built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl Backend {
+    /// Whether the backend accepts `ORDER BY` and `LIMIT` on a `DELETE` statement
+    ///
+    /// SQLite only does so when compiled with `SQLITE_ENABLE_UPDATE_DELETE_LIMIT`, which most
+    /// distributions don't enable, so it's treated as unsupported.
+    pub fn supports_delete_limit(&self) -> bool {
+        matches!(self, Self::MySql)
+    }
+}
+
+pub trait ApplyDeleteFilters<T> {
+    /// Like [`ApplyFilters`], but emits SQL the given backend can actually run.
+    ///
+    /// Backends without `DELETE ... LIMIT` get a `WHERE key IN (SELECT key FROM table ...)`
+    /// subquery carrying the conditions, the order and the limit instead, which is why the table
+    /// and its key column are needed.
+    fn apply_delete_filters<R, C>(
+        self,
+        filters: &QueryFilter<T>,
+        backend: Backend,
+        table: R,
+        key: C,
+    ) -> Self
+    where
+        R: IntoTableRef,
+        C: IntoColumnRef;
+}
+
+impl<T: Filter + ToCond> ApplyDeleteFilters<T> for &mut DeleteStatement {
+    fn apply_delete_filters<R, C>(
+        self,
+        filters: &QueryFilter<T>,
+        backend: Backend,
+        table: R,
+        key: C,
+    ) -> Self
+    where
+        R: IntoTableRef,
+        C: IntoColumnRef,
+    {
+        if backend.supports_delete_limit() {
+            return self.apply_backend_filters(filters, backend);
+        }
+
+        let limit = filters.get_limit(0);
+        let order = filters.get_order();
+        let sort = filters.get_sort();
+
+        let key = key.into_column_ref();
+        let mut subquery = Query::select();
+        subquery.column(key.clone()).from(table);
+
+        if let Some(filter) = &filters.filter {
+            subquery.cond_where(filter.to_backend_cond(backend));
+        }
+
+        if let Some(q) = filters.get_q_cond() {
+            subquery.cond_where(q);
+        }
+
+        subquery.limit(limit as u64);
+
+        if let Some(expr) = sort.and_then(|field| filters.get_order_expr(field)) {
+            subquery.order_by_expr(expr, order.to_seaquery());
+        }
+
+        self.cond_where(Expr::col(key).in_subquery(subquery))
+    }
+}
+
+impl<T: ToCond> QueryFilter<T> {
+    pub(crate) fn get_sort_expr(&self, field: &str) -> Option<SimpleExpr> {
+        self.filter
+            .as_ref()
+            .and_then(|filter| filter.sort_expr(field))
+    }
+
+    /// What `ApplyFilters` orders by for `field`, see [`ToCond::EXPR_SORT_FIELDS`]
+    pub(crate) fn get_order_expr(&self, field: &'static str) -> Option<SimpleExpr> {
+        match self.get_sort_expr(field) {
+            Some(expr) => Some(expr),
+            None if T::EXPR_SORT_FIELDS.contains(&field) => None,
+            None => Some(SimpleExpr::Column(
+                IntoColumnRefStr(field).into_column_ref(),
+            )),
+        }
+    }
+}
+
+/// A `LIKE` pattern, with `ESCAPE '\'` when it has a `\` so SQLite reads it as Postgres does
+pub(crate) fn like_expr(pattern: String) -> LikeExpr {
+    if pattern.contains('\\') {
+        LikeExpr::new(pattern).escape('\\')
+    } else {
+        LikeExpr::new(pattern)
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct IntoColumnRefStr(pub &'static str);
 
@@ -110,7 +317,9 @@ impl Iden for IntoColumnRefStr {
 
 #[cfg(test)]
 mod tests {
-    use sea_query::{Cond, Iden, PostgresQueryBuilder, Query};
+    use sea_query::{
+        Cond, Expr, Iden, MysqlQueryBuilder, PostgresQueryBuilder, Query, SqliteQueryBuilder,
+    };
     use serde::Deserialize;
     use serde_querystring::de::ParseMode;
 
@@ -120,7 +329,7 @@ mod tests {
         Filter, QueryFilter,
     };
 
-    use super::ApplyFilters;
+    use super::{ApplyDeleteFilters, ApplyFilters, Backend};
 
     #[derive(Deserialize)]
     struct MyFilters<'a> {
@@ -132,6 +341,8 @@ mod tests {
     #[derive(Iden)]
     struct User;
     #[derive(Iden)]
+    struct Id;
+    #[derive(Iden)]
     struct Name;
     #[derive(Iden)]
     struct Age;
@@ -140,8 +351,12 @@ mod tests {
 
     impl<'a> ToCond for MyFilters<'a> {
         fn to_cond(&self) -> Cond {
+            self.to_backend_cond(Backend::Postgres)
+        }
+
+        fn to_backend_cond(&self, backend: Backend) -> Cond {
             let mut cond = Cond::all();
-            if let Some(name) = self.name.to_cond(Name) {
+            if let Some(name) = self.name.to_backend_cond(Name, backend) {
                 cond = cond.add(name)
             }
             if let Some(age) = self.age.to_cond(Age) {
@@ -156,6 +371,9 @@ mod tests {
 
     impl<'a> Filter for MyFilters<'a> {
         const SORTABLE_FIELDS: &'static [&'static str] = &["name", "age", "score"];
+        const SEARCHABLE_FIELDS: &'static [&'static str] = &["name", "nickname"];
+        const SELECTABLE_FIELDS: &'static [&'static str] = &["name", "age", "score"];
+        const KEY_FIELDS: &'static [&'static str] = &["id"];
 
         fn get_max_limit() -> i32 {
             100
@@ -202,4 +420,218 @@ mod tests {
              ORDER BY \"age\" DESC LIMIT 90 OFFSET 10"
         )
     }
+
+    #[test]
+    fn test_query_filters_q() {
+        let filters = serde_querystring::from_str::<QueryFilter<MyFilters>>(
+            "filter[age][gte]=20&q=JoHn",
+            ParseMode::Brackets,
+        )
+        .unwrap();
+
+        let q = Query::select()
+            .column(Age)
+            .from(User)
+            .apply_filters(&filters)
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(
+            q,
+            "SELECT \"age\" FROM \"user\" WHERE \"age\" >= 20 \
+             AND (LOWER(\"name\") LIKE '%john%' OR LOWER(\"nickname\") LIKE '%john%') \
+             LIMIT 10 OFFSET 0"
+        )
+    }
+
+    #[test]
+    fn test_query_filters_q_wildcards() {
+        let sql = |query: &str| {
+            let filters =
+                serde_querystring::from_str::<QueryFilter<MyFilters>>(query, ParseMode::Brackets)
+                    .unwrap();
+            Query::select()
+                .column(Age)
+                .from(User)
+                .apply_filters(&filters)
+                .to_string(SqliteQueryBuilder)
+        };
+
+        assert_eq!(
+            sql("q=%25"),
+            "SELECT \"age\" FROM \"user\" \
+             WHERE LOWER(\"name\") LIKE '%\\%%' ESCAPE '\\' OR LOWER(\"nickname\") LIKE '%\\%%' ESCAPE '\\' \
+             LIMIT 10 OFFSET 0"
+        );
+        assert_eq!(
+            sql("q=_"),
+            "SELECT \"age\" FROM \"user\" \
+             WHERE LOWER(\"name\") LIKE '%\\_%' ESCAPE '\\' OR LOWER(\"nickname\") LIKE '%\\_%' ESCAPE '\\' \
+             LIMIT 10 OFFSET 0"
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_query_filters_backend() {
+        let filters = serde_querystring::from_str::<QueryFilter<MyFilters>>(
+            "filter[name][regex]=^jo",
+            ParseMode::Brackets,
+        )
+        .unwrap();
+
+        let postgres = Query::select()
+            .column(Age)
+            .from(User)
+            .apply_filters(&filters)
+            .to_string(PostgresQueryBuilder);
+        assert_eq!(
+            postgres,
+            "SELECT \"age\" FROM \"user\" WHERE \"name\" ~ '^jo' LIMIT 10 OFFSET 0"
+        );
+
+        let mysql = Query::select()
+            .column(Age)
+            .from(User)
+            .apply_backend_filters(&filters, Backend::MySql)
+            .to_string(MysqlQueryBuilder);
+        assert_eq!(
+            mysql,
+            "SELECT `age` FROM `user` WHERE `name` REGEXP '(?-i)^jo' LIMIT 10 OFFSET 0"
+        );
+
+        let sqlite = Query::delete()
+            .from_table(User)
+            .apply_delete_filters(&filters, Backend::Sqlite, User, Id)
+            .to_string(SqliteQueryBuilder);
+        assert_eq!(
+            sqlite,
+            "DELETE FROM \"user\" WHERE \"id\" IN (SELECT \"id\" FROM \"user\" \
+             WHERE \"name\" REGEXP '(?-i)^jo' LIMIT 10)"
+        );
+    }
+
+    #[test]
+    fn test_query_filters_blank_q() {
+        let filters =
+            serde_querystring::from_str::<QueryFilter<MyFilters>>("q=%20", ParseMode::Brackets)
+                .unwrap();
+
+        let q = Query::select()
+            .column(Age)
+            .from(User)
+            .apply_filters(&filters)
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(q, "SELECT \"age\" FROM \"user\" LIMIT 10 OFFSET 0")
+    }
+
+    #[test]
+    fn test_query_filters_fields() {
+        let filters = |query: &str| {
+            serde_querystring::from_str::<QueryFilter<MyFilters>>(query, ParseMode::Brackets)
+                .unwrap()
+        };
+        let select = |query: &str| {
+            Query::select()
+                .from(User)
+                .apply_filters(&filters(query))
+                .to_string(PostgresQueryBuilder)
+        };
+
+        assert_eq!(
+            select("fields=score,%20name,password,name&filter[age][gte]=20"),
+            "SELECT \"id\", \"score\", \"name\" FROM \"user\" WHERE \"age\" >= 20 LIMIT 10 OFFSET 0"
+        );
+        // Nothing selectable, every selectable column is taken
+        assert_eq!(
+            select("fields=password"),
+            "SELECT \"id\", \"name\", \"age\", \"score\" FROM \"user\" LIMIT 10 OFFSET 0"
+        );
+        assert_eq!(
+            select(""),
+            "SELECT \"id\", \"name\", \"age\", \"score\" FROM \"user\" LIMIT 10 OFFSET 0"
+        );
+
+        // Columns the server picked, here across a join, are kept
+        #[derive(Iden)]
+        struct Team;
+        let q = Query::select()
+            .column((User, Name))
+            .column((Team, Name))
+            .from(User)
+            .inner_join(Team, Expr::col((User, Id)).equals((Team, Id)))
+            .apply_filters(&filters("fields=score&filter[age][gte]=20"))
+            .to_string(PostgresQueryBuilder);
+        assert_eq!(
+            q,
+            "SELECT \"user\".\"name\", \"team\".\"name\" FROM \"user\" \
+             INNER JOIN \"team\" ON \"user\".\"id\" = \"team\".\"id\" \
+             WHERE \"age\" >= 20 LIMIT 10 OFFSET 0"
+        );
+    }
+
+    fn delete_filters() -> QueryFilter<MyFilters<'static>> {
+        serde_querystring::from_str::<QueryFilter<MyFilters>>(
+            "filter[age][gte]=20&filter[name][contains]=John&end=100&sort=age&order=DESC",
+            ParseMode::Brackets,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_update_filters() {
+        let q = Query::update()
+            .table(User)
+            .value(Score, 0)
+            .apply_filters(&delete_filters())
+            .to_string(MysqlQueryBuilder);
+
+        assert_eq!(
+            q,
+            "UPDATE `user` SET `score` = 0 WHERE `name` LIKE '%John%' AND `age` >= 20 \
+             ORDER BY `age` DESC LIMIT 100"
+        )
+    }
+
+    #[test]
+    fn test_delete_filters_mysql() {
+        let q = Query::delete()
+            .from_table(User)
+            .apply_delete_filters(&delete_filters(), Backend::MySql, User, Id)
+            .to_string(MysqlQueryBuilder);
+
+        assert_eq!(
+            q,
+            "DELETE FROM `user` WHERE `name` LIKE '%John%' AND `age` >= 20 \
+             ORDER BY `age` DESC LIMIT 100"
+        )
+    }
+
+    #[test]
+    fn test_delete_filters_postgres() {
+        let q = Query::delete()
+            .from_table(User)
+            .apply_delete_filters(&delete_filters(), Backend::Postgres, User, Id)
+            .to_string(PostgresQueryBuilder);
+
+        assert_eq!(
+            q,
+            "DELETE FROM \"user\" WHERE \"id\" IN (SELECT \"id\" FROM \"user\" \
+             WHERE \"name\" LIKE '%John%' AND \"age\" >= 20 ORDER BY \"age\" DESC LIMIT 100)"
+        )
+    }
+
+    #[test]
+    fn test_delete_filters_sqlite() {
+        let q = Query::delete()
+            .from_table(User)
+            .apply_delete_filters(&delete_filters(), Backend::Sqlite, User, Id)
+            .to_string(SqliteQueryBuilder);
+
+        assert_eq!(
+            q,
+            "DELETE FROM \"user\" WHERE \"id\" IN (SELECT \"id\" FROM \"user\" \
+             WHERE \"name\" LIKE '%John%' AND \"age\" >= 20 ORDER BY \"age\" DESC LIMIT 100)"
+        )
+    }
 }
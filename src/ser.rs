@@ -0,0 +1,631 @@
+//! A serializer writing bracket-mode querystrings, the format the filters are read from
+//!
+//! Nested maps and structs become `key[sub]=value` pairs and sequences repeat their key, so the
+//! output of [`to_querystring`] parses back with `serde_querystring`'s `ParseMode::Brackets`.
+
+use std::fmt;
+
+use serde::ser::{self, Impossible, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Only maps and structs have keys to put the values under
+    TopLevelNotAMap,
+    /// The value has no querystring representation, like bytes or a map used as a key
+    Unsupported(&'static str),
+    Custom(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TopLevelNotAMap => f.write_str("only maps and structs can be serialized"),
+            Self::Unsupported(kind) => write!(f, "{kind} can't be serialized into a querystring"),
+            Self::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Serialize a value into a bracket-mode querystring, e.g. `filter[age][gte]=20&sort=age`
+pub fn to_querystring<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut pairs = Vec::new();
+    value.serialize(Serializer {
+        pairs: &mut pairs,
+        key: None,
+    })?;
+
+    Ok(pairs
+        .into_iter()
+        .map(|(key, value)| [key, value].join("="))
+        .collect::<Vec<_>>()
+        .join("&"))
+}
+
+pub(crate) fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => {
+                encoded.push('%');
+                encoded.push_str(&format!("{byte:02X}"));
+            }
+        }
+    }
+    encoded
+}
+
+pub(crate) fn sub_key(key: Option<&str>, sub: &str) -> String {
+    match key {
+        Some(key) => format!("{key}[{}]", encode(sub)),
+        None => encode(sub),
+    }
+}
+
+struct Serializer<'a> {
+    pairs: &'a mut Vec<(String, String)>,
+    key: Option<String>,
+}
+
+impl<'a> Serializer<'a> {
+    fn push(self, value: &str) -> Result<(), Error> {
+        let key = self.key.ok_or(Error::TopLevelNotAMap)?;
+        self.pairs.push((key, encode(value)));
+        Ok(())
+    }
+
+    fn nested(self, variant: &str) -> Serializer<'a> {
+        Serializer {
+            key: Some(sub_key(self.key.as_deref(), variant)),
+            pairs: self.pairs,
+        }
+    }
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = SeqSerializer<'a>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = MapSerializer<'a>;
+    type SerializeStructVariant = MapSerializer<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.push(if v { "true" } else { "false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.push(&v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.push(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.push(v)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        Err(Error::Unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.push(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self.nested(variant))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SeqSerializer<'a>, Error> {
+        let key = self.key.ok_or(Error::TopLevelNotAMap)?;
+        Ok(SeqSerializer {
+            pairs: self.pairs,
+            key,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer<'a>, Error> {
+        self.nested(variant).serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer<'a>, Error> {
+        Ok(MapSerializer {
+            pairs: self.pairs,
+            key: self.key,
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer<'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer<'a>, Error> {
+        self.nested(variant).serialize_map(Some(len))
+    }
+}
+
+struct SeqSerializer<'a> {
+    pairs: &'a mut Vec<(String, String)>,
+    key: String,
+}
+
+impl<'a> SeqSerializer<'a> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(Serializer {
+            pairs: self.pairs,
+            key: Some(self.key.clone()),
+        })
+    }
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTuple for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+struct MapSerializer<'a> {
+    pairs: &'a mut Vec<(String, String)>,
+    key: Option<String>,
+    next_key: Option<String>,
+}
+
+impl<'a> MapSerializer<'a> {
+    fn entry<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        value.serialize(Serializer {
+            pairs: self.pairs,
+            key: Some(sub_key(self.key.as_deref(), key)),
+        })
+    }
+}
+
+impl<'a> ser::SerializeMap for MapSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.next_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error::Custom("map value serialized before its key".into()))?;
+        self.entry(&key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for MapSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for MapSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Turns map keys into strings, only scalars are accepted
+struct KeySerializer;
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(Error::Unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(Error::Unsupported("an empty map key"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(Error::Unsupported("an empty map key"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(Error::Unsupported("an empty map key"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Unsupported("a compound map key"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+    use serde_querystring::de::ParseMode;
+
+    use super::{to_querystring, Error};
+    use crate::{
+        filters::{NumberFilter, NumberFilterSet},
+        Filter, QueryFilter,
+    };
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct Sample {
+        age: Option<NumberFilterSet>,
+        name: Option<String>,
+        tags: Vec<String>,
+    }
+
+    impl Filter for Sample {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["age"];
+    }
+
+    #[test]
+    fn serialize_nested() {
+        let mut age = NumberFilterSet::default();
+        age.push(NumberFilter::GreaterThanEqual(20));
+
+        let filters = QueryFilter {
+            start: Some(10),
+            end: Some(20),
+            sort: Some("age".into()),
+            order: Some("DESC".into()),
+            q: Some("jo".into()),
+            page: None,
+            per_page: None,
+            fields: None,
+            filter: Some(Sample {
+                age: Some(age),
+                name: Some("John Doe & co".into()),
+                tags: vec!["a".into(), "b".into()],
+            }),
+        };
+
+        let query = to_querystring(&filters).unwrap();
+        assert_eq!(
+            query,
+            "start=10&end=20&sort=age&order=DESC&q=jo\
+             &filter[age][gte]=20&filter[name]=John%20Doe%20%26%20co\
+             &filter[tags]=a&filter[tags]=b"
+        );
+
+        let res = serde_querystring::from_str::<QueryFilter<Sample>>(&query, ParseMode::Brackets)
+            .unwrap();
+        assert_eq!(res, filters);
+    }
+
+    #[test]
+    fn serialize_map_keys() {
+        let mut map = BTreeMap::new();
+        map.insert("a b", 1);
+        map.insert("c", 2);
+
+        assert_eq!(to_querystring(&map).unwrap(), "a%20b=1&c=2");
+    }
+
+    #[test]
+    fn serialize_top_level() {
+        assert_eq!(to_querystring(&10), Err(Error::TopLevelNotAMap));
+        assert_eq!(to_querystring(&[1, 2]), Err(Error::TopLevelNotAMap));
+    }
+}
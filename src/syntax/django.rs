@@ -0,0 +1,190 @@
+use super::{escape_like, number, page, pairs, Output, Syntax, SyntaxError};
+
+/// Django's double-underscore lookups, `age__gte=20&name__icontains=john&status__in=a,b`
+///
+/// A key without a lookup is `exact`. The lookups are `exact`, `iexact`, `contains`,
+/// `icontains`, `startswith`, `istartswith`, `endswith`, `iendswith`, `gt`, `gte`, `lt`, `lte`,
+/// `in` and `range` with comma separated values, `isnull`, `regex` and `iregex`. Like Django's,
+/// `exact`, `contains`, `startswith`, `endswith` and their `i` variants match the value literally,
+/// `exact` being an equality and the others `LIKE` patterns escaping its `%`, `_` and `\`.
+///
+/// `ordering=-age` becomes `sort` and `order`. Pages are read from Django REST framework's
+/// `limit`/`offset` or `page`/`page_size`, ten to a page when the size is missing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Django;
+
+impl Syntax for Django {
+    fn to_brackets(&self, query: &str) -> Result<String, SyntaxError> {
+        let mut output = Output::default();
+        let (mut limit, mut offset) = (None, None);
+        let (mut page_number, mut page_size) = (None, None);
+
+        for (key, value) in pairs(query)? {
+            match key.as_str() {
+                "ordering" => ordering(&mut output, &key, &value)?,
+                "limit" => limit = Some(number(&key, &value)?),
+                "offset" => offset = Some(number(&key, &value)?),
+                "page" => page_number = Some(number(&key, &value)?),
+                "page_size" => page_size = Some(number(&key, &value)?),
+                _ => lookup(&mut output, &key, &value)?,
+            }
+        }
+
+        if page_number.is_some() || page_size.is_some() {
+            (offset, limit) = page("page", page_number.or(Some(1)), page_size.unwrap_or(10))?;
+        }
+        output.range(offset, limit);
+
+        Ok(output.finish())
+    }
+}
+
+fn lookup(output: &mut Output, key: &str, value: &str) -> Result<(), SyntaxError> {
+    let (field, lookup) = key.rsplit_once("__").unwrap_or((key, "exact"));
+    let invalid = |reason: &str| SyntaxError::InvalidValue {
+        key: key.to_owned(),
+        reason: reason.to_owned(),
+    };
+
+    let path = |operator| ["filter", field, operator];
+    match lookup {
+        "exact" => output.push(&path("eq"), value),
+        "gt" | "gte" | "lt" | "lte" | "regex" | "iregex" => output.push(&path(lookup), value),
+        "contains" => output.push(&path("like"), &format!("%{}%", escape_like(value))),
+        "startswith" => output.push(&path("like"), &format!("{}%", escape_like(value))),
+        "endswith" => output.push(&path("like"), &format!("%{}", escape_like(value))),
+        "iexact" => output.push(&path("ilike"), &escape_like(value)),
+        "icontains" => output.push(&path("ilike"), &format!("%{}%", escape_like(value))),
+        "istartswith" => output.push(&path("ilike"), &format!("{}%", escape_like(value))),
+        "iendswith" => output.push(&path("ilike"), &format!("%{}", escape_like(value))),
+        "in" => {
+            for value in value.split(',') {
+                output.push(&path("in"), value);
+            }
+        }
+        "range" => {
+            let (low, high) = value
+                .split_once(',')
+                .ok_or_else(|| invalid("ranges are written `low,high`"))?;
+            output.push(&path("gte"), low);
+            output.push(&path("lte"), high);
+        }
+        "isnull" => match value {
+            "true" | "True" | "1" => output.push(&path("null"), "true"),
+            "false" | "False" | "0" => output.push(&path("null"), "false"),
+            _ => return Err(invalid("`isnull` takes `true` or `false`")),
+        },
+        lookup => {
+            return Err(SyntaxError::UnknownOperator {
+                key: field.to_owned(),
+                operator: lookup.to_owned(),
+            })
+        }
+    }
+    Ok(())
+}
+
+/// `age` or `-age`
+fn ordering(output: &mut Output, key: &str, value: &str) -> Result<(), SyntaxError> {
+    if value.contains(',') {
+        return Err(SyntaxError::InvalidValue {
+            key: key.to_owned(),
+            reason: "only one ordering field is supported".to_owned(),
+        });
+    }
+    match value.strip_prefix('-') {
+        Some(column) => output.sort(column, true),
+        None => output.sort(value, false),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::Django;
+    use crate::{
+        filters::DateFilter,
+        syntax::{
+            tests::{assert_same_as_brackets, birthday, ID},
+            Syntax, SyntaxError,
+        },
+    };
+
+    #[test]
+    fn rewrite() {
+        let query = Django
+            .to_brackets(
+                "age__range=18,65&name__istartswith=jo&status=active&birthday__isnull=False\
+                 &ordering=-age&page=3&page_size=20",
+            )
+            .unwrap();
+
+        assert_eq!(
+            query,
+            "filter[age][gte]=18&filter[age][lte]=65&filter[name][ilike]=jo%25\
+             &filter[status][eq]=active&filter[birthday][null]=false\
+             &sort=age&order=DESC&start=40&end=60"
+        );
+    }
+
+    #[test]
+    fn same_filters_as_brackets() {
+        let query = Django
+            .to_brackets(&format!(
+                "age__gte=20&name__icontains=john&status__in=a,b&birthday__lt=2000-01-01\
+                 &parent__in={ID}&ordering=age&limit=5&offset=0"
+            ))
+            .unwrap();
+        assert_same_as_brackets(&query);
+    }
+
+    #[test]
+    fn date_comparisons() {
+        let day = |day| NaiveDate::from_ymd_opt(2000, 1, day).unwrap();
+        let query = Django.to_brackets("birthday__gt=2000-01-01").unwrap();
+        assert_eq!(birthday(&query), [DateFilter::GreaterThan(day(1))]);
+
+        let query = Django
+            .to_brackets("birthday__range=2000-01-01,2000-01-31")
+            .unwrap();
+        assert_eq!(
+            birthday(&query),
+            [
+                DateFilter::LesserThanEqual(day(31)),
+                DateFilter::GreaterThanEqual(day(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        let query = Django
+            .to_brackets("name__iexact=50%25_off&status__contains=a%5Cb&name__istartswith=_")
+            .unwrap();
+        assert_eq!(
+            query,
+            "filter[name][ilike]=50%5C%25%5C_off&filter[status][like]=%25a%5C%5Cb%25\
+             &filter[name][ilike]=%5C_%25"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Django.to_brackets("author__name=john").unwrap_err(),
+            SyntaxError::UnknownOperator {
+                key: "author".to_owned(),
+                operator: "name".to_owned()
+            }
+        );
+        assert_eq!(
+            Django.to_brackets("age__range=18").unwrap_err().to_string(),
+            "invalid value for `age__range`: ranges are written `low,high`"
+        );
+        assert!(Django.to_brackets("deleted__isnull=maybe").is_err());
+        assert!(Django.to_brackets("ordering=-age,name").is_err());
+        assert!(Django.to_brackets("page=0").is_err());
+    }
+}
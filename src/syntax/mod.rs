@@ -0,0 +1,239 @@
+//! Adapters rewriting other querystring conventions into the bracket mode the filters read
+//!
+//! Filter sets deserialize from `filter[age][gte]=20`, but clients written against other backends
+//! put the operator elsewhere. A [`Syntax`] rewrites their querystring into bracket mode, which
+//! is then deserialized as usual, e.g. with `serde_querystring`'s `ParseMode::Brackets`, so one
+//! `QueryFilter<T>` endpoint can serve all of them.
+
+mod django;
+mod postgrest;
+mod strapi;
+
+use std::fmt;
+
+pub use django::Django;
+pub use postgrest::PostgRest;
+pub use strapi::Strapi;
+
+use crate::ser::{encode, sub_key};
+
+pub trait Syntax {
+    /// Rewrite `query`, without its leading `?`, into a bracket-mode querystring
+    fn to_brackets(&self, query: &str) -> Result<String, SyntaxError>;
+}
+
+/// The bracket mode itself, for endpoints picking their syntax at runtime
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Brackets;
+
+impl Syntax for Brackets {
+    fn to_brackets(&self, query: &str) -> Result<String, SyntaxError> {
+        Ok(query.to_owned())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxError {
+    /// A `%` not followed by two hex digits, or an escape which isn't UTF-8
+    Encoding(String),
+    UnknownOperator {
+        key: String,
+        operator: String,
+    },
+    InvalidValue {
+        key: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encoding(part) => write!(f, "`{part}` isn't properly percent-encoded"),
+            Self::UnknownOperator { key, operator } => {
+                write!(f, "unknown operator `{operator}` for `{key}`")
+            }
+            Self::InvalidValue { key, reason } => write!(f, "invalid value for `{key}`: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// The decoded `key=value` pairs of a querystring, in order
+pub(crate) fn pairs(query: &str) -> Result<Vec<(String, String)>, SyntaxError> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Ok((decode(key)?, decode(value)?))
+        })
+        .collect()
+}
+
+fn decode(part: &str) -> Result<String, SyntaxError> {
+    let invalid = || SyntaxError::Encoding(part.to_owned());
+
+    let mut bytes = Vec::with_capacity(part.len());
+    let mut iter = part.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [
+                    iter.next().ok_or_else(invalid)?,
+                    iter.next().ok_or_else(invalid)?,
+                ];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Bracket-mode pairs being written
+#[derive(Default)]
+pub(crate) struct Output(Vec<String>);
+
+impl Output {
+    /// `path[0][path[1]]...=value`, each part encoded
+    pub(crate) fn push(&mut self, path: &[&str], value: &str) {
+        let key = path
+            .iter()
+            .fold(None, |key: Option<String>, part| {
+                Some(sub_key(key.as_deref(), part))
+            })
+            .unwrap_or_default();
+        self.0.push(format!("{key}={}", encode(value)));
+    }
+
+    /// `sort` and `order` for a single column
+    pub(crate) fn sort(&mut self, column: &str, descending: bool) {
+        self.push(&["sort"], column);
+        self.push(&["order"], if descending { "DESC" } else { "ASC" });
+    }
+
+    /// `start` and `end` from an offset and a page size
+    pub(crate) fn range(&mut self, offset: Option<u32>, limit: Option<u32>) {
+        if let Some(offset) = offset {
+            self.push(&["start"], &offset.to_string());
+        }
+        if let Some(limit) = limit {
+            let end = offset.unwrap_or_default().saturating_add(limit);
+            self.push(&["end"], &end.min(i32::MAX as u32).to_string());
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.0.join("&")
+    }
+}
+
+/// `value` as a `LIKE` pattern matching it literally, `\` escaping `%`, `_` and itself
+pub(crate) fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// A pagination parameter
+pub(crate) fn number(key: &str, value: &str) -> Result<u32, SyntaxError> {
+    value.parse().map_err(|_| SyntaxError::InvalidValue {
+        key: key.to_owned(),
+        reason: format!("`{value}` isn't a positive integer"),
+    })
+}
+
+/// `offset` and `limit` of a 1-based page
+pub(crate) fn page(
+    key: &str,
+    page: Option<u32>,
+    size: u32,
+) -> Result<(Option<u32>, Option<u32>), SyntaxError> {
+    match page {
+        Some(0) => Err(SyntaxError::InvalidValue {
+            key: key.to_owned(),
+            reason: "pages start at 1".to_owned(),
+        }),
+        Some(page) => Ok((Some((page - 1).saturating_mul(size)), Some(size))),
+        None => Ok((None, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_querystring::de::{from_str, ParseMode};
+
+    use super::{pairs, Output, SyntaxError};
+    use crate::{
+        filters::{DateFilter, DateFilterSet, NumberFilterSet, StringFilterSet, UuidFilterSet},
+        QueryFilter,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    pub(super) struct Filters<'a> {
+        age: Option<NumberFilterSet>,
+        #[serde(borrow)]
+        name: Option<StringFilterSet<'a>>,
+        status: Option<StringFilterSet<'a>>,
+        birthday: Option<DateFilterSet>,
+        parent: Option<UuidFilterSet>,
+    }
+
+    pub(super) const ID: &str = "23191e01-8af8-4381-848c-f9387116d132";
+
+    /// The fixture every syntax rewrites its own spelling of
+    const BRACKETS: &str = "filter[age][gte]=20&filter[name][ilike]=%25john%25\
+         &filter[status][in]=a&filter[status][in]=b\
+         &filter[birthday][before]=2000-01-01\
+         &filter[parent][in]=23191e01-8af8-4381-848c-f9387116d132\
+         &sort=age&order=ASC&start=0&end=5";
+
+    pub(super) fn assert_same_as_brackets(rewritten: &str) {
+        let expected = from_str::<QueryFilter<Filters>>(BRACKETS, ParseMode::Brackets).unwrap();
+        let actual = from_str::<QueryFilter<Filters>>(rewritten, ParseMode::Brackets).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    /// The `birthday` filters a rewritten query ends up with, in a stable order
+    pub(super) fn birthday(rewritten: &str) -> Vec<DateFilter> {
+        let filter = from_str::<QueryFilter<Filters>>(rewritten, ParseMode::Brackets).unwrap();
+        let mut filters = filter.filter.unwrap().birthday.unwrap().0;
+        filters.sort();
+        filters
+    }
+
+    #[test]
+    fn decode_pairs() {
+        assert_eq!(
+            pairs("a=1&&b%5Bc%5D=x+y%2Cz&flag").unwrap(),
+            vec![
+                ("a".to_owned(), "1".to_owned()),
+                ("b[c]".to_owned(), "x y,z".to_owned()),
+                ("flag".to_owned(), "".to_owned()),
+            ]
+        );
+        assert_eq!(
+            pairs("a=%E2%82").unwrap_err(),
+            SyntaxError::Encoding("%E2%82".to_owned())
+        );
+        assert!(pairs("a=%2").is_err());
+    }
+
+    #[test]
+    fn output() {
+        let mut output = Output::default();
+        output.push(&["filter", "name", "contains"], "a&b");
+        output.push(&["sort"], "age");
+        assert_eq!(output.finish(), "filter[name][contains]=a%26b&sort=age");
+    }
+}
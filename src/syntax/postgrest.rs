@@ -0,0 +1,207 @@
+use super::{number, pairs, Output, Syntax, SyntaxError};
+
+/// PostgREST's operator-prefixed values, `age=gte.20&name=ilike.*john*&status=in.(a,b)`
+///
+/// Filters are nested under `filter`, `order=age.desc` becomes `sort` and `order`, and `limit`
+/// and `offset` become `start` and `end`. The operators are `eq`, `neq`, `gt`, `gte`, `lt`,
+/// `lte`, `like` and `ilike` with `*` as the wildcard, `in.(..)` with double quotes around values
+/// containing commas, and `is.null`. `not.` negates `eq`, `in` and `is`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostgRest;
+
+impl Syntax for PostgRest {
+    fn to_brackets(&self, query: &str) -> Result<String, SyntaxError> {
+        let mut output = Output::default();
+        let (mut limit, mut offset) = (None, None);
+
+        for (key, value) in pairs(query)? {
+            match key.as_str() {
+                "order" => order(&mut output, &key, &value)?,
+                "limit" => limit = Some(number(&key, &value)?),
+                "offset" => offset = Some(number(&key, &value)?),
+                _ => filter(&mut output, &key, &value)?,
+            }
+        }
+
+        output.range(offset, limit);
+        Ok(output.finish())
+    }
+}
+
+fn filter(output: &mut Output, key: &str, value: &str) -> Result<(), SyntaxError> {
+    let unknown = |operator: &str| SyntaxError::UnknownOperator {
+        key: key.to_owned(),
+        operator: operator.to_owned(),
+    };
+
+    let (operator, value) = value.split_once('.').ok_or_else(|| unknown(value))?;
+    let (negated, operator, value) = match operator {
+        "not" => {
+            let (operator, value) = value.split_once('.').ok_or_else(|| unknown(value))?;
+            (true, operator, value)
+        }
+        _ => (false, operator, value),
+    };
+
+    let path = |operator| ["filter", key, operator];
+    match (negated, operator) {
+        (false, "eq" | "neq" | "gt" | "gte" | "lt" | "lte") => output.push(&path(operator), value),
+        (true, "eq") => output.push(&path("neq"), value),
+        (false, "like" | "ilike") => output.push(&path(operator), &value.replace('*', "%")),
+        (_, "in") => {
+            let operator = if negated { "nin" } else { "in" };
+            for value in list(key, value)? {
+                output.push(&path(operator), &value);
+            }
+        }
+        (_, "is") if value == "null" => {
+            output.push(&path("null"), if negated { "false" } else { "true" })
+        }
+        (_, "is") => {
+            return Err(SyntaxError::InvalidValue {
+                key: key.to_owned(),
+                reason: format!("`is.{value}` isn't supported, only `is.null` is"),
+            })
+        }
+        (true, operator) => return Err(unknown(&format!("not.{operator}"))),
+        (false, operator) => return Err(unknown(operator)),
+    }
+    Ok(())
+}
+
+/// `(a,"b,c")`
+fn list(key: &str, value: &str) -> Result<Vec<String>, SyntaxError> {
+    let invalid = |reason: &str| SyntaxError::InvalidValue {
+        key: key.to_owned(),
+        reason: reason.to_owned(),
+    };
+
+    let inner = value
+        .strip_prefix('(')
+        .and_then(|value| value.strip_suffix(')'))
+        .ok_or_else(|| invalid("lists are written in parentheses, `in.(a,b)`"))?;
+
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in inner.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid("unterminated quote"));
+    }
+    values.push(current);
+    Ok(values)
+}
+
+/// `age`, `age.asc` or `age.desc`
+fn order(output: &mut Output, key: &str, value: &str) -> Result<(), SyntaxError> {
+    let invalid = |reason: String| SyntaxError::InvalidValue {
+        key: key.to_owned(),
+        reason,
+    };
+
+    if value.contains(',') {
+        return Err(invalid("only one order column is supported".to_owned()));
+    }
+    let (column, direction) = value.split_once('.').unwrap_or((value, "asc"));
+    match direction {
+        "asc" => output.sort(column, false),
+        "desc" => output.sort(column, true),
+        other => return Err(invalid(format!("unknown direction `{other}`"))),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::PostgRest;
+    use crate::{
+        filters::DateFilter,
+        syntax::{
+            tests::{assert_same_as_brackets, birthday, ID},
+            Syntax, SyntaxError,
+        },
+    };
+
+    #[test]
+    fn rewrite() {
+        let query = PostgRest
+            .to_brackets(
+                "age=gte.20&name=ilike.*john*&status=in.(a,%22b,c%22)\
+                 &birthday=lt.2000-01-01&parent=not.is.null&parent=not.eq.23191e01-8af8-4381-848c-f9387116d132\
+                 &order=age.desc&limit=10&offset=20",
+            )
+            .unwrap();
+
+        assert_eq!(
+            query,
+            "filter[age][gte]=20&filter[name][ilike]=%25john%25\
+             &filter[status][in]=a&filter[status][in]=b%2Cc\
+             &filter[birthday][lt]=2000-01-01\
+             &filter[parent][null]=false&filter[parent][neq]=23191e01-8af8-4381-848c-f9387116d132\
+             &sort=age&order=DESC&start=20&end=30"
+        );
+    }
+
+    #[test]
+    fn same_filters_as_brackets() {
+        let query = PostgRest
+            .to_brackets(&format!(
+                "age=gte.20&name=ilike.*john*&status=in.(a,b)&birthday=lt.2000-01-01\
+                 &parent=in.({ID})&order=age&limit=5&offset=0"
+            ))
+            .unwrap();
+        assert_same_as_brackets(&query);
+    }
+
+    #[test]
+    fn date_comparisons() {
+        let day = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let query = PostgRest
+            .to_brackets("birthday=gt.2000-01-01&birthday=gte.2000-01-01&birthday=lte.2000-01-01")
+            .unwrap();
+        assert_eq!(
+            birthday(&query),
+            [
+                DateFilter::LesserThanEqual(day),
+                DateFilter::GreaterThan(day),
+                DateFilter::GreaterThanEqual(day),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            PostgRest.to_brackets("age=between.1.2").unwrap_err(),
+            SyntaxError::UnknownOperator {
+                key: "age".to_owned(),
+                operator: "between".to_owned()
+            }
+        );
+        assert_eq!(
+            PostgRest.to_brackets("name=not.like.a*").unwrap_err(),
+            SyntaxError::UnknownOperator {
+                key: "name".to_owned(),
+                operator: "not.like".to_owned()
+            }
+        );
+        assert_eq!(
+            PostgRest
+                .to_brackets("status=in.a,b")
+                .unwrap_err()
+                .to_string(),
+            "invalid value for `status`: lists are written in parentheses, `in.(a,b)`"
+        );
+        assert!(PostgRest.to_brackets("order=age.desc,name").is_err());
+        assert!(PostgRest.to_brackets("limit=-1").is_err());
+        assert!(PostgRest.to_brackets("age=20").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use super::{escape_like, number, page, pairs, Output, Syntax, SyntaxError};
+
+/// Strapi's `$`-prefixed operators, `filters[age][$gte]=20&filters[status][$in][0]=a`
+///
+/// The operators are `$eq`, `$eqi`, `$ne`, `$lt`, `$lte`, `$gt`, `$gte`, `$in`, `$notIn`,
+/// `$contains`, `$notContains`, `$containsi`, `$startsWith`, `$startsWithi`, `$endsWith`,
+/// `$endsWithi`, `$between`, `$null` and `$notNull`. `$and`, `$or`, `$not` and filters on
+/// relations are rejected. `$eqi` and the `$contains`, `$startsWith` and `$endsWith` families match
+/// the value literally, the `LIKE` patterns they become escaping its `%`, `_` and `\`.
+///
+/// `sort=age:desc` becomes `sort` and `order`, and `pagination[page]`/`pagination[pageSize]` or
+/// `pagination[start]`/`pagination[limit]` become `start` and `end`, with Strapi's 25 to a page
+/// when the size is missing. Other parameters, like `populate`, are passed through.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Strapi;
+
+impl Syntax for Strapi {
+    fn to_brackets(&self, query: &str) -> Result<String, SyntaxError> {
+        let mut output = Output::default();
+        let (mut limit, mut offset) = (None, None);
+        let (mut page_number, mut page_size) = (None, None);
+
+        for (key, value) in pairs(query)? {
+            let path = segments(&key).ok_or_else(|| SyntaxError::InvalidValue {
+                key: key.clone(),
+                reason: "unbalanced brackets".to_owned(),
+            })?;
+            match path.as_slice() {
+                ["filters", rest @ ..] => filter(&mut output, &key, rest, &value)?,
+                ["sort"] | ["sort", _] => sort(&mut output, &key, &value)?,
+                ["pagination", "page"] => page_number = Some(number(&key, &value)?),
+                ["pagination", "pageSize"] => page_size = Some(number(&key, &value)?),
+                ["pagination", "start"] => offset = Some(number(&key, &value)?),
+                ["pagination", "limit"] => limit = Some(number(&key, &value)?),
+                _ => output.push(&path, &value),
+            }
+        }
+
+        if page_number.is_some() || page_size.is_some() {
+            (offset, limit) = page(
+                "pagination[page]",
+                page_number.or(Some(1)),
+                page_size.unwrap_or(25),
+            )?;
+        }
+        output.range(offset, limit);
+
+        Ok(output.finish())
+    }
+}
+
+/// `filters[age][$gte]` into `["filters", "age", "$gte"]`
+fn segments(key: &str) -> Option<Vec<&str>> {
+    let (head, mut rest) = match key.find('[') {
+        Some(index) => key.split_at(index),
+        None => return Some(vec![key]),
+    };
+
+    let mut path = vec![head];
+    while !rest.is_empty() {
+        let (segment, tail) = rest.strip_prefix('[')?.split_once(']')?;
+        path.push(segment);
+        rest = tail;
+    }
+    Some(path)
+}
+
+/// `[field, operator]`, with an index after the list operators
+fn filter(output: &mut Output, key: &str, path: &[&str], value: &str) -> Result<(), SyntaxError> {
+    let invalid = |reason: &str| SyntaxError::InvalidValue {
+        key: key.to_owned(),
+        reason: reason.to_owned(),
+    };
+
+    let (field, operator, index) = match path {
+        [logical, ..] if logical.starts_with('$') => {
+            return Err(SyntaxError::UnknownOperator {
+                key: "filters".to_owned(),
+                operator: (*logical).to_owned(),
+            })
+        }
+        [field, operator] => (*field, *operator, None),
+        [field, operator, index] if operator.starts_with('$') => (*field, *operator, Some(*index)),
+        [_, _, ..] => return Err(invalid("filters on relations aren't supported")),
+        _ => return Err(invalid("filters are written `filters[field][$operator]`")),
+    };
+    let unknown = || SyntaxError::UnknownOperator {
+        key: field.to_owned(),
+        operator: operator.to_owned(),
+    };
+
+    let path = |operator| ["filter", field, operator];
+    match (operator, index) {
+        ("$in", _) => output.push(&path("in"), value),
+        ("$notIn", _) => output.push(&path("nin"), value),
+        ("$between", Some("0")) => output.push(&path("gte"), value),
+        ("$between", Some("1")) => output.push(&path("lte"), value),
+        ("$between", _) => return Err(invalid("`$between` takes two values, `[0]` and `[1]`")),
+        (_, Some(_)) => return Err(unknown()),
+        ("$eq", _) => output.push(&path("eq"), value),
+        ("$eqi", _) => output.push(&path("ilike"), &escape_like(value)),
+        ("$ne", _) => output.push(&path("neq"), value),
+        ("$lt" | "$lte" | "$gt" | "$gte", _) => output.push(&path(&operator[1..]), value),
+        ("$contains", _) => output.push(&path("like"), &format!("%{}%", escape_like(value))),
+        ("$notContains", _) => output.push(&path("notcontains"), &escape_like(value)),
+        ("$containsi", _) => output.push(&path("ilike"), &format!("%{}%", escape_like(value))),
+        ("$startsWith", _) => output.push(&path("like"), &format!("{}%", escape_like(value))),
+        ("$startsWithi", _) => output.push(&path("ilike"), &format!("{}%", escape_like(value))),
+        ("$endsWith", _) => output.push(&path("like"), &format!("%{}", escape_like(value))),
+        ("$endsWithi", _) => output.push(&path("ilike"), &format!("%{}", escape_like(value))),
+        ("$null" | "$notNull", _) => {
+            let null = match value {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => return Err(invalid("takes `true` or `false`")),
+            };
+            let null = if operator == "$null" { null } else { !null };
+            output.push(&path("null"), if null { "true" } else { "false" });
+        }
+        _ => return Err(unknown()),
+    }
+    Ok(())
+}
+
+/// `age`, `age:asc` or `age:desc`
+fn sort(output: &mut Output, key: &str, value: &str) -> Result<(), SyntaxError> {
+    let invalid = |reason: String| SyntaxError::InvalidValue {
+        key: key.to_owned(),
+        reason,
+    };
+
+    if value.contains(',') || key != "sort" && !key.ends_with("[0]") {
+        return Err(invalid("only one sort field is supported".to_owned()));
+    }
+    let (column, direction) = value.split_once(':').unwrap_or((value, "asc"));
+    match direction.to_ascii_lowercase().as_str() {
+        "asc" => output.sort(column, false),
+        "desc" => output.sort(column, true),
+        other => return Err(invalid(format!("unknown direction `{other}`"))),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{segments, Strapi};
+    use crate::{
+        filters::DateFilter,
+        syntax::{
+            tests::{assert_same_as_brackets, birthday, ID},
+            Syntax, SyntaxError,
+        },
+    };
+
+    #[test]
+    fn split_segments() {
+        assert_eq!(
+            segments("filters[age][$in][0]"),
+            Some(vec!["filters", "age", "$in", "0"])
+        );
+        assert_eq!(segments("populate"), Some(vec!["populate"]));
+        assert_eq!(segments("filters[age"), None);
+        assert_eq!(segments("filters[age]x"), None);
+    }
+
+    #[test]
+    fn rewrite() {
+        let query = Strapi
+            .to_brackets(
+                "filters[age][$between][0]=18&filters[age][$between][1]=65\
+                 &filters[name][$startsWithi]=jo&filters[status][$ne]=closed\
+                 &filters[birthday][$notNull]=true&populate=author\
+                 &sort[0]=age:desc&pagination[page]=3&pagination[pageSize]=20",
+            )
+            .unwrap();
+
+        assert_eq!(
+            query,
+            "filter[age][gte]=18&filter[age][lte]=65&filter[name][ilike]=jo%25\
+             &filter[status][neq]=closed&filter[birthday][null]=false&populate=author\
+             &sort=age&order=DESC&start=40&end=60"
+        );
+    }
+
+    #[test]
+    fn same_filters_as_brackets() {
+        let query = Strapi
+            .to_brackets(&format!(
+                "filters[age][$gte]=20&filters[name][$containsi]=john\
+                 &filters[status][$in][0]=a&filters[status][$in][1]=b\
+                 &filters[birthday][$lt]=2000-01-01&filters[parent][$in][0]={ID}\
+                 &sort=age&pagination[start]=0&pagination[limit]=5"
+            ))
+            .unwrap();
+        assert_same_as_brackets(&query);
+    }
+
+    #[test]
+    fn date_comparisons() {
+        let day = |day| NaiveDate::from_ymd_opt(2000, 1, day).unwrap();
+        let query = Strapi
+            .to_brackets("filters[birthday][$gt]=2000-01-01")
+            .unwrap();
+        assert_eq!(birthday(&query), [DateFilter::GreaterThan(day(1))]);
+
+        let query = Strapi
+            .to_brackets(
+                "filters[birthday][$between][0]=2000-01-01&filters[birthday][$between][1]=2000-01-31",
+            )
+            .unwrap();
+        assert_eq!(
+            birthday(&query),
+            [
+                DateFilter::LesserThanEqual(day(31)),
+                DateFilter::GreaterThanEqual(day(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        let query = Strapi
+            .to_brackets(
+                "filters[name][$eqi]=50%25_off&filters[status][$contains]=a%5Cb\
+                 &filters[name][$endsWithi]=_&filters[name][$notContains]=5%25",
+            )
+            .unwrap();
+        assert_eq!(
+            query,
+            "filter[name][ilike]=50%5C%25%5C_off&filter[status][like]=%25a%5C%5Cb%25\
+             &filter[name][ilike]=%25%5C_&filter[name][notcontains]=5%5C%25"
+        );
+    }
+
+    #[cfg(feature = "seaq")]
+    #[test]
+    fn escapes_like_wildcards_on_sqlite() {
+        use sea_query::{tests_cfg::*, Query, SqliteQueryBuilder};
+        use serde::Deserialize;
+        use serde_querystring::de::{from_str, ParseMode};
+
+        use crate::{filters::StringFilterSet, ToFieldCond};
+
+        #[derive(Deserialize)]
+        struct Filters<'a> {
+            #[serde(borrow)]
+            name: StringFilterSet<'a>,
+        }
+
+        #[derive(Deserialize)]
+        struct Params<'a> {
+            #[serde(borrow)]
+            filter: Filters<'a>,
+        }
+
+        let query = Strapi
+            .to_brackets("filters[name][$contains]=50%25&filters[name][$notContains]=a_b")
+            .unwrap();
+        let filters = from_str::<Params>(&query, ParseMode::Brackets).unwrap();
+
+        // `to_cond`, and so `ApplyFilters`, adds the `ESCAPE` SQLite needs to read the escapes
+        let sql = Query::select()
+            .column(Glyph::Image)
+            .from(Glyph::Table)
+            .cond_where(filters.filter.name.to_cond(Glyph::Aspect).unwrap())
+            .to_string(SqliteQueryBuilder);
+        assert_eq!(
+            sql,
+            r#"SELECT "image" FROM "glyph" WHERE "aspect" LIKE '%50\%%' ESCAPE '\' AND "aspect" NOT LIKE '%a\_b%' ESCAPE '\'"#
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Strapi
+                .to_brackets("filters[$or][0][age][$gte]=20")
+                .unwrap_err(),
+            SyntaxError::UnknownOperator {
+                key: "filters".to_owned(),
+                operator: "$or".to_owned()
+            }
+        );
+        assert_eq!(
+            Strapi.to_brackets("filters[age][$gte][0]=20").unwrap_err(),
+            SyntaxError::UnknownOperator {
+                key: "age".to_owned(),
+                operator: "$gte".to_owned()
+            }
+        );
+        assert_eq!(
+            Strapi
+                .to_brackets("filters[author][name][$eq]=john")
+                .unwrap_err()
+                .to_string(),
+            "invalid value for `filters[author][name][$eq]`: filters on relations aren't supported"
+        );
+        assert!(Strapi.to_brackets("filters[age]=20").is_err());
+        assert!(Strapi.to_brackets("sort[1]=name").is_err());
+        assert!(Strapi.to_brackets("pagination[page]=0").is_err());
+    }
+}
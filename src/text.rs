@@ -0,0 +1,1074 @@
+//! A filter language for people, `age >= 20 and (name ~ "john" or status in [active, trial])`
+//!
+//! `and` binds tighter than `or`, `not` negates what follows it and parentheses group, nesting up
+//! to [`MAX_DEPTH`] levels. The comparisons are `=`, `!=`, `<`, `<=`, `>` and `>=`, `in [..]` and
+//! `not in [..]`, and `is null`/`is not null` and `^=` for a hex prefix on UUID fields. Text fields
+//! also have `~` and `!~` for contains, `^=` and `$=` for starts and ends with, `like` and `ilike`
+//! taking a `%` pattern, and with the `regex` feature `matches`, `imatches` and `not matches`.
+//! Keywords are case-insensitive.
+//!
+//! Values are bare words, like `20`, `2000-01-01` or `active`, or double-quoted strings with `\`
+//! escaping the quote. Fields are looked up in a whitelist of [`Field`]s, as for RSQL.
+//!
+//! [`format`] prints an expression back, and [`format_filters`] a bracket-mode filter struct, so
+//! saved searches can be stored as readable strings.
+
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::expr::{CompareOp, ExprFields, Field, FieldFilter, FieldKind, FilterExpr, MAX_DEPTH};
+use crate::filters::{
+    DateFilter, DateTimeFilter, DateTimeTzFilter, NumberFilter, StringFilter, UuidFilter,
+    UuidPrefix,
+};
+use crate::{ser, Filter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextError {
+    offset: usize,
+    message: String,
+}
+
+impl TextError {
+    /// Where the error is in the input, counted in characters
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for TextError {}
+
+/// Parse an expression using the fields in `fields`
+pub fn parse(input: &str, fields: &'static [Field]) -> Result<FilterExpr<'static>, TextError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        depth: 0,
+        fields,
+    };
+    let expr = parser.or()?;
+    match parser.peek() {
+        (_, Token::End) => Ok(expr),
+        (offset, token) => Err(TextError {
+            offset: *offset,
+            message: format!("unexpected {}", token),
+        }),
+    }
+}
+
+/// Print an expression, [`parse`] reads it back into the same tree
+///
+/// Nested groups are always parenthesized. Groups of a single expression print as that
+/// expression, and empty ones, which no parser produces, as `()`.
+pub fn format(expr: &FilterExpr) -> String {
+    let mut out = String::new();
+    write_expr(&mut out, expr, false);
+    out
+}
+
+/// Print a bracket-mode filter struct, like `{ age: [gte: 20], name: [contains: "jo"] }`, as
+/// `age >= 20 and name ~ jo`
+///
+/// The struct goes through [`to_querystring`](crate::to_querystring), so every field holding a
+/// `*FilterSet` of text, numbers, UUIDs or dates is printed, and an error is returned for
+/// operators this syntax doesn't have.
+pub fn format_filters<T: Serialize + ?Sized>(filters: &T) -> Result<String, ser::Error> {
+    let query = ser::to_querystring(filters)?;
+    let pairs = crate::syntax::pairs(&query).map_err(|err| ser::Error::Custom(err.to_string()))?;
+
+    let mut conditions: Vec<(String, String, Vec<String>)> = Vec::new();
+    for (key, value) in pairs {
+        let (field, operator) = key
+            .strip_suffix(']')
+            .and_then(|key| key.split_once('['))
+            .filter(|(_, operator)| !operator.contains('['))
+            .ok_or_else(|| ser::Error::Custom(format!("`{key}` isn't a field and operator")))?;
+
+        match conditions.last_mut() {
+            Some((last_field, last_operator, values))
+                if last_field == field
+                    && last_operator == operator
+                    && matches!(operator, "in" | "nin") =>
+            {
+                values.push(value)
+            }
+            _ => conditions.push((field.to_owned(), operator.to_owned(), vec![value])),
+        }
+    }
+
+    let mut out = Vec::with_capacity(conditions.len());
+    for (field, operator, values) in conditions {
+        let symbol = match operator.as_str() {
+            "eq" => "=",
+            "neq" => "!=",
+            "lt" | "before" => "<",
+            "lte" => "<=",
+            "gt" | "after" => ">",
+            "gte" => ">=",
+            "contains" => "~",
+            "notcontains" => "!~",
+            "startswith" => "^=",
+            "endswith" => "$=",
+            "like" => "like",
+            "ilike" => "ilike",
+            "regex" => "matches",
+            "iregex" => "imatches",
+            "notregex" => "not matches",
+            "in" | "nin" => {
+                let not = if operator == "nin" { "not " } else { "" };
+                out.push(format!("{field} {not}in {}", list(&values)));
+                continue;
+            }
+            "null" => {
+                let not = if values[0] == "false" { "not " } else { "" };
+                out.push(format!("{field} is {not}null"));
+                continue;
+            }
+            _ => {
+                return Err(ser::Error::Custom(format!(
+                    "`{operator}` on `{field}` has no text form"
+                )))
+            }
+        };
+        out.push(format!("{field} {symbol} {}", quote(&values[0])));
+    }
+    Ok(out.join(" and "))
+}
+
+/// An expression in this syntax as a `QueryFilter`'s `filter`, over the fields listed by `F`
+///
+/// Like [`Rsql`](crate::rsql::Rsql), `F` implements [`ExprFields`] and [`Filter`]. It also
+/// serializes back into its text, for storing saved searches.
+pub struct Text<F> {
+    expr: FilterExpr<'static>,
+    fields: PhantomData<fn() -> F>,
+}
+
+impl<F> Text<F> {
+    pub fn expr(&self) -> &FilterExpr<'static> {
+        &self.expr
+    }
+
+    pub fn into_expr(self) -> FilterExpr<'static> {
+        self.expr
+    }
+}
+
+impl<F> fmt::Debug for Text<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Text").field(&self.expr).finish()
+    }
+}
+
+impl<F> fmt::Display for Text<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format(&self.expr))
+    }
+}
+
+impl<F: ExprFields> FromStr for Text<F> {
+    type Err = TextError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            expr: parse(input, F::FIELDS)?,
+            fields: PhantomData,
+        })
+    }
+}
+
+impl<'de, F: ExprFields> Deserialize<'de> for Text<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        input.parse().map_err(de::Error::custom)
+    }
+}
+
+impl<F> Serialize for Text<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format(&self.expr))
+    }
+}
+
+impl<F: Filter> Filter for Text<F> {
+    const SORTABLE_FIELDS: &'static [&'static str] = F::SORTABLE_FIELDS;
+    const SEARCHABLE_FIELDS: &'static [&'static str] = F::SEARCHABLE_FIELDS;
+    const SELECTABLE_FIELDS: &'static [&'static str] = F::SELECTABLE_FIELDS;
+    const KEY_FIELDS: &'static [&'static str] = F::KEY_FIELDS;
+
+    fn get_max_limit() -> i32 {
+        F::get_max_limit()
+    }
+
+    fn get_first_page() -> i32 {
+        F::get_first_page()
+    }
+}
+
+#[cfg(feature = "seaq")]
+impl<F> crate::seaq::ToCond for Text<F> {
+    fn to_cond(&self) -> sea_query::Cond {
+        self.expr.to_cond()
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "and", "or", "not", "in", "is", "null", "like", "ilike", "matches", "imatches",
+];
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// A field name, a keyword or a bare value
+    Word(String),
+    Str(String),
+    Symbol(&'static str),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "`{}`", word),
+            Token::Str(value) => write!(f, "\"{}\"", value),
+            Token::Symbol(symbol) => write!(f, "`{}`", symbol),
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+            Token::LBracket => f.write_str("`[`"),
+            Token::RBracket => f.write_str("`]`"),
+            Token::Comma => f.write_str("`,`"),
+            Token::End => f.write_str("the end"),
+        }
+    }
+}
+
+const SYMBOLS: &[&str] = &["==", "!=", "<=", ">=", "!~", "^=", "$=", "=", "<", ">", "~"];
+
+fn is_bare(c: char) -> bool {
+    c.is_alphanumeric() || "_-.:+@/".contains(c)
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, TextError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().enumerate().peekable();
+    let error = |offset, message: String| TextError { offset, message };
+
+    while let Some((offset, (i, c))) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next().map(|(_, (_, c))| c) {
+                        Some('\\') => match chars.next() {
+                            Some((_, (_, escaped))) => value.push(escaped),
+                            None => return Err(error(offset, "unterminated string".to_owned())),
+                        },
+                        Some('"') => break Token::Str(value),
+                        Some(c) => value.push(c),
+                        None => return Err(error(offset, "unterminated string".to_owned())),
+                    }
+                }
+            }
+            c if is_bare(c) => {
+                let mut word = c.to_string();
+                while let Some((_, (_, c))) = chars.next_if(|(_, (_, c))| is_bare(*c)) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            _ => {
+                let rest = &input[i..];
+                let symbol = SYMBOLS
+                    .iter()
+                    .find(|symbol| rest.starts_with(**symbol))
+                    .ok_or_else(|| error(offset, format!("unexpected `{}`", c)))?;
+                for _ in 1..symbol.len() {
+                    chars.next();
+                }
+                Token::Symbol(symbol)
+            }
+        };
+        tokens.push((offset, token));
+    }
+
+    tokens.push((input.chars().count(), Token::End));
+    Ok(tokens)
+}
+
+enum Operator {
+    Compare(CompareOp),
+    Text(&'static str),
+    In { negated: bool },
+    Null { negated: bool },
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    depth: usize,
+    fields: &'static [Field],
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<FilterExpr<'static>, TextError> {
+        let mut exprs = vec![self.and()?];
+        while self.eat_keyword("or") {
+            exprs.push(self.and()?);
+        }
+        Ok(flatten(exprs, FilterExpr::Or))
+    }
+
+    fn and(&mut self) -> Result<FilterExpr<'static>, TextError> {
+        let mut exprs = vec![self.unary()?];
+        while self.eat_keyword("and") {
+            exprs.push(self.unary()?);
+        }
+        Ok(flatten(exprs, FilterExpr::And))
+    }
+
+    fn unary(&mut self) -> Result<FilterExpr<'static>, TextError> {
+        let offset = self.peek().0;
+        if self.eat_keyword("not") {
+            self.enter(offset)?;
+            let expr = self.unary()?;
+            self.depth -= 1;
+            return Ok(FilterExpr::Not(Box::new(expr)));
+        }
+        if self.eat(&Token::LParen) {
+            self.enter(offset)?;
+            let expr = self.or()?;
+            self.depth -= 1;
+            self.expect(&Token::RParen, "`)`")?;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    /// One more level of `not` or parentheses, see [`MAX_DEPTH`]
+    fn enter(&mut self, offset: usize) -> Result<(), TextError> {
+        if self.depth == MAX_DEPTH {
+            return Err(TextError {
+                offset,
+                message: format!("nested deeper than {} levels", MAX_DEPTH),
+            });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn comparison(&mut self) -> Result<FilterExpr<'static>, TextError> {
+        let (start, name) = match self.next() {
+            (start, Token::Word(name)) if !is_keyword(&name) => (start, name),
+            (offset, token) => {
+                return Err(TextError {
+                    offset,
+                    message: format!("expected a field, found {}", token),
+                })
+            }
+        };
+        let field = self
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| TextError {
+                offset: start,
+                message: format!("unknown field `{}`", name),
+            })?;
+
+        let operator = self.operator()?;
+        let start = self.peek().0;
+        let expr = match operator {
+            Operator::Compare(op) => {
+                let value = self.value()?;
+                FilterExpr::compare(field, op, &value)
+            }
+            Operator::Text(operator) => {
+                let value = self.value()?;
+                text(field, operator, value)
+            }
+            Operator::In { negated } => {
+                let values = self.list()?;
+                FilterExpr::in_list(field, values, negated)
+            }
+            Operator::Null { negated } => match field.kind {
+                FieldKind::Uuid => Ok(FilterExpr::Field(
+                    field,
+                    FieldFilter::Uuid(UuidFilter::Null(!negated)),
+                )),
+                _ => Err(format!(
+                    "`is null` is only supported on UUID fields, not `{}`",
+                    field.name
+                )),
+            },
+        };
+        expr.map_err(|message| TextError {
+            offset: start,
+            message,
+        })
+    }
+
+    fn operator(&mut self) -> Result<Operator, TextError> {
+        let (offset, token) = self.next();
+        let operator = match token {
+            Token::Symbol("=" | "==") => Operator::Compare(CompareOp::Eq),
+            Token::Symbol("!=") => Operator::Compare(CompareOp::Ne),
+            Token::Symbol("<") => Operator::Compare(CompareOp::Lt),
+            Token::Symbol("<=") => Operator::Compare(CompareOp::Le),
+            Token::Symbol(">") => Operator::Compare(CompareOp::Gt),
+            Token::Symbol(">=") => Operator::Compare(CompareOp::Ge),
+            Token::Symbol(symbol) => Operator::Text(symbol),
+            Token::Word(word) => match word.to_ascii_lowercase().as_str() {
+                "like" => Operator::Text("like"),
+                "ilike" => Operator::Text("ilike"),
+                "matches" => Operator::Text("matches"),
+                "imatches" => Operator::Text("imatches"),
+                "in" => Operator::In { negated: false },
+                "not" if self.eat_keyword("in") => Operator::In { negated: true },
+                "not" if self.eat_keyword("matches") => Operator::Text("not matches"),
+                "is" => {
+                    let negated = self.eat_keyword("not");
+                    if !self.eat_keyword("null") {
+                        return Err(self.expected("`null`"));
+                    }
+                    Operator::Null { negated }
+                }
+                _ => {
+                    return Err(TextError {
+                        offset,
+                        message: format!("unknown operator `{}`", word),
+                    })
+                }
+            },
+            token => {
+                return Err(TextError {
+                    offset,
+                    message: format!("expected an operator, found {}", token),
+                })
+            }
+        };
+        Ok(operator)
+    }
+
+    fn value(&mut self) -> Result<String, TextError> {
+        match self.peek() {
+            (_, Token::Str(_)) | (_, Token::Word(_)) => match self.next() {
+                (_, Token::Str(value) | Token::Word(value)) => Ok(value),
+                _ => unreachable!(),
+            },
+            _ => Err(self.expected("a value")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Vec<String>, TextError> {
+        self.expect(&Token::LBracket, "`[`")?;
+        let mut values = Vec::new();
+        if self.eat(&Token::RBracket) {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.value()?);
+            if self.eat(&Token::RBracket) {
+                return Ok(values);
+            }
+            self.expect(&Token::Comma, "`,` or `]`")?;
+        }
+    }
+
+    fn peek(&self) -> &(usize, Token) {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> (usize, Token) {
+        let pos = self.pos;
+        if pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        let (offset, token) = &mut self.tokens[pos];
+        (*offset, std::mem::replace(token, Token::End))
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if &self.peek().1 == token {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match &self.peek().1 {
+            Token::Word(word) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: &Token, what: &str) -> Result<(), TextError> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.expected(what)),
+        }
+    }
+
+    fn expected(&self, what: &str) -> TextError {
+        let (offset, token) = self.peek();
+        TextError {
+            offset: *offset,
+            message: format!("expected {}, found {}", what, token),
+        }
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+fn flatten(
+    mut exprs: Vec<FilterExpr<'static>>,
+    group: fn(Vec<FilterExpr<'static>>) -> FilterExpr<'static>,
+) -> FilterExpr<'static> {
+    if exprs.len() == 1 {
+        exprs.remove(0)
+    } else {
+        group(exprs)
+    }
+}
+
+/// The operators only text fields have
+fn text(
+    field: &'static Field,
+    operator: &str,
+    value: String,
+) -> Result<FilterExpr<'static>, String> {
+    if field.kind == FieldKind::Uuid && operator == "^=" {
+        let prefix = UuidPrefix::new(&value)
+            .ok_or_else(|| format!("`{}` isn't the start of a UUID", value))?;
+        return Ok(FilterExpr::Field(
+            field,
+            FieldFilter::Uuid(UuidFilter::Prefix(prefix)),
+        ));
+    }
+    if field.kind != FieldKind::String {
+        return Err(format!(
+            "`{}` is only supported on text fields, not `{}`",
+            operator, field.name
+        ));
+    }
+
+    let value = value.into();
+    let filter = match operator {
+        "~" => StringFilter::Contains(value),
+        "!~" => StringFilter::NotContains(value),
+        "^=" => StringFilter::StartsWith(value),
+        "$=" => StringFilter::EndsWith(value),
+        "like" => StringFilter::Like(value),
+        "ilike" => StringFilter::ILike(value),
+        #[cfg(feature = "regex")]
+        "matches" | "imatches" | "not matches" => {
+            let pattern = crate::filters::RegexPattern::new(value)
+                .map_err(|err| format!("invalid pattern for `{}`: {}", field.name, err))?;
+            match operator {
+                "matches" => StringFilter::Regex(pattern),
+                "imatches" => StringFilter::IRegex(pattern),
+                _ => StringFilter::NotRegex(pattern),
+            }
+        }
+        _ => return Err(format!("`{}` needs the `regex` feature", operator)),
+    };
+    Ok(FilterExpr::Field(field, FieldFilter::String(filter)))
+}
+
+fn write_expr(out: &mut String, expr: &FilterExpr, nested: bool) {
+    let (exprs, separator) = match expr {
+        FilterExpr::Field(field, filter) => return write_leaf(out, field, filter),
+        FilterExpr::Not(expr) => {
+            out.push_str("not ");
+            return write_expr(out, expr, true);
+        }
+        FilterExpr::And(exprs) => (exprs, " and "),
+        FilterExpr::Or(exprs) => (exprs, " or "),
+    };
+
+    if let [expr] = exprs.as_slice() {
+        return write_expr(out, expr, nested);
+    }
+    let parenthesized = nested || exprs.is_empty();
+    if parenthesized {
+        out.push('(');
+    }
+    for (i, expr) in exprs.iter().enumerate() {
+        if i > 0 {
+            out.push_str(separator);
+        }
+        write_expr(out, expr, true);
+    }
+    if parenthesized {
+        out.push(')');
+    }
+}
+
+fn write_leaf(out: &mut String, field: &Field, filter: &FieldFilter) {
+    let (operator, value) = match filter {
+        FieldFilter::String(filter) => match filter {
+            StringFilter::Equals(value) => ("=", quote(value)),
+            StringFilter::NotEquals(value) => ("!=", quote(value)),
+            StringFilter::Contains(value) => ("~", quote(value)),
+            StringFilter::NotContains(value) => ("!~", quote(value)),
+            StringFilter::StartsWith(value) => ("^=", quote(value)),
+            StringFilter::EndsWith(value) => ("$=", quote(value)),
+            StringFilter::Like(value) => ("like", quote(value)),
+            StringFilter::ILike(value) => ("ilike", quote(value)),
+            StringFilter::In(values) => ("in", list(values)),
+            #[cfg(feature = "regex")]
+            StringFilter::Regex(pattern) => ("matches", quote(pattern.as_str())),
+            #[cfg(feature = "regex")]
+            StringFilter::IRegex(pattern) => ("imatches", quote(pattern.as_str())),
+            #[cfg(feature = "regex")]
+            StringFilter::NotRegex(pattern) => ("not matches", quote(pattern.as_str())),
+        },
+        FieldFilter::Number(filter) => match filter {
+            NumberFilter::Equals(value) => ("=", value.to_string()),
+            NumberFilter::NotEquals(value) => ("!=", value.to_string()),
+            NumberFilter::LesserThan(value) => ("<", value.to_string()),
+            NumberFilter::LesserThanEqual(value) => ("<=", value.to_string()),
+            NumberFilter::GreaterThan(value) => (">", value.to_string()),
+            NumberFilter::GreaterThanEqual(value) => (">=", value.to_string()),
+            NumberFilter::In(values) => ("in", list(values)),
+        },
+        FieldFilter::Uuid(filter) => match filter {
+            UuidFilter::Equals(value) => ("=", value.to_string()),
+            UuidFilter::NotEquals(value) => ("!=", value.to_string()),
+            UuidFilter::LesserThan(value) => ("<", value.to_string()),
+            UuidFilter::LesserThanEqual(value) => ("<=", value.to_string()),
+            UuidFilter::GreaterThan(value) => (">", value.to_string()),
+            UuidFilter::GreaterThanEqual(value) => (">=", value.to_string()),
+            UuidFilter::In(values) => ("in", list(values)),
+            UuidFilter::NotIn(values) => ("not in", list(values)),
+            UuidFilter::Null(true) => ("is", "null".to_owned()),
+            UuidFilter::Null(false) => ("is not", "null".to_owned()),
+            UuidFilter::Prefix(prefix) => ("^=", quote(prefix.as_str())),
+        },
+        FieldFilter::Date(filter) => match filter {
+            DateFilter::Before(value) => ("<", value.to_string()),
+            DateFilter::After(value) => (">=", value.to_string()),
+            DateFilter::Equals(value) => ("=", value.to_string()),
+            DateFilter::NotEquals(value) => ("!=", value.to_string()),
+            DateFilter::LesserThanEqual(value) => ("<=", value.to_string()),
+            DateFilter::GreaterThan(value) => (">", value.to_string()),
+            DateFilter::GreaterThanEqual(value) => (">=", value.to_string()),
+        },
+        FieldFilter::DateTime(filter) => {
+            let format =
+                |value: &chrono::NaiveDateTime| value.format("%Y-%m-%dT%H:%M:%S%.f").to_string();
+            match filter {
+                DateTimeFilter::Before(value) => ("<", format(value)),
+                DateTimeFilter::After(value) => (">=", format(value)),
+                DateTimeFilter::Equals(value) => ("=", format(value)),
+                DateTimeFilter::NotEquals(value) => ("!=", format(value)),
+                DateTimeFilter::LesserThanEqual(value) => ("<=", format(value)),
+                DateTimeFilter::GreaterThan(value) => (">", format(value)),
+                DateTimeFilter::GreaterThanEqual(value) => (">=", format(value)),
+            }
+        }
+        FieldFilter::DateTimeTz(filter) => match filter {
+            DateTimeTzFilter::Before(value) => ("<", value.to_rfc3339()),
+            DateTimeTzFilter::After(value) => (">=", value.to_rfc3339()),
+            DateTimeTzFilter::Equals(value) => ("=", value.to_rfc3339()),
+            DateTimeTzFilter::NotEquals(value) => ("!=", value.to_rfc3339()),
+            DateTimeTzFilter::LesserThanEqual(value) => ("<=", value.to_rfc3339()),
+            DateTimeTzFilter::GreaterThan(value) => (">", value.to_rfc3339()),
+            DateTimeTzFilter::GreaterThanEqual(value) => (">=", value.to_rfc3339()),
+        },
+    };
+    out.push_str(&format!("{} {} {}", field.name, operator, value));
+}
+
+/// A bare word when it reads back as one, a quoted string otherwise
+fn quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(is_bare) && !is_keyword(value) {
+        return value.to_owned();
+    }
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
+
+fn list<T: ToString>(values: &[T]) -> String {
+    let values = values
+        .iter()
+        .map(|value| quote(&value.to_string()))
+        .collect::<Vec<_>>();
+    format!("[{}]", values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use chrono::{DateTime, NaiveDate};
+    use proptest::prelude::*;
+    use serde::Serialize;
+
+    use super::{format, format_filters, parse, Text, TextError};
+    use crate::expr::{ExprFields, Field, FieldFilter, FieldKind, FilterExpr};
+    use crate::filters::{
+        DateFilter, DateTimeFilter, DateTimeTzFilter, NumberFilter, NumberFilterSet, StringFilter,
+        StringFilterSet, UuidFilter, UuidFilterSet, UuidPrefix,
+    };
+
+    static FIELDS: &[Field] = &[
+        Field::new("age", FieldKind::Number),
+        Field::new("name", FieldKind::String),
+        Field::new("status", FieldKind::String),
+        Field::new("parent", FieldKind::Uuid),
+        Field::new("birthday", FieldKind::Date),
+        Field::new("seen_at", FieldKind::DateTime),
+        Field::new("created_at", FieldKind::DateTimeTz),
+    ];
+
+    fn age(filter: NumberFilter) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[0], FieldFilter::Number(filter))
+    }
+
+    fn string(field: usize, filter: StringFilter<'static>) -> FilterExpr<'static> {
+        FilterExpr::Field(&FIELDS[field], FieldFilter::String(filter))
+    }
+
+    fn error(input: &str) -> (usize, String) {
+        let TextError { offset, message } = parse(input, FIELDS).unwrap_err();
+        (offset, message)
+    }
+
+    #[test]
+    fn precedence() {
+        let expr = parse(
+            "age >= 20 and (name ~ \"john\" or status in [active, trial])",
+            FIELDS,
+        )
+        .unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::And(vec![
+                age(NumberFilter::GreaterThanEqual(20)),
+                FilterExpr::Or(vec![
+                    string(1, StringFilter::Contains("john".into())),
+                    string(2, StringFilter::In(vec!["active".into(), "trial".into()])),
+                ]),
+            ])
+        );
+
+        let expr = parse("NOT age<18 OR name = \"and\" AND status !~ x", FIELDS).unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Or(vec![
+                FilterExpr::Not(Box::new(age(NumberFilter::LesserThan(18)))),
+                FilterExpr::And(vec![
+                    string(1, StringFilter::Equals("and".into())),
+                    string(2, StringFilter::NotContains("x".into())),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(
+            parse("status not in [a, \"b, c\"]", FIELDS).unwrap(),
+            FilterExpr::Not(Box::new(string(
+                2,
+                StringFilter::In(vec!["a".into(), "b, c".into()])
+            )))
+        );
+        assert_eq!(
+            parse("age in [1, -2]", FIELDS).unwrap(),
+            age(NumberFilter::In(vec![1, -2]))
+        );
+        assert_eq!(
+            parse("parent is not null", FIELDS).unwrap(),
+            FilterExpr::Field(&FIELDS[3], FieldFilter::Uuid(UuidFilter::Null(false)))
+        );
+        let prefix = parse("parent ^= 0190a30c1", FIELDS).unwrap();
+        assert_eq!(
+            prefix,
+            FilterExpr::Field(
+                &FIELDS[3],
+                FieldFilter::Uuid(UuidFilter::Prefix(UuidPrefix::new("0190a30c1").unwrap()))
+            )
+        );
+        assert_eq!(format(&prefix), "parent ^= 0190a30c-1");
+        assert!(parse("parent ^= nope", FIELDS).is_err());
+        assert_eq!(
+            parse("name ilike \"%jo\\\"hn%\"", FIELDS).unwrap(),
+            string(1, StringFilter::ILike("%jo\"hn%".into()))
+        );
+        assert_eq!(
+            parse("birthday in [2000-01-01]", FIELDS).unwrap(),
+            FilterExpr::Or(vec![FilterExpr::Field(
+                &FIELDS[4],
+                FieldFilter::Date(DateFilter::Equals(
+                    NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()
+                ))
+            )])
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex() {
+        let expr = parse("name not matches \"^jo(hn)?$\"", FIELDS).unwrap();
+        assert_eq!(format(&expr), "name not matches \"^jo(hn)?$\"");
+        assert_eq!(
+            error("name matches \"(a+)+\""),
+            (
+                13,
+                "invalid pattern for `name`: regex is too complex: nested repetition".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn depth() {
+        let nested = |depth: usize| format!("{}age = 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(32), FIELDS).is_ok());
+        assert_eq!(
+            error(&nested(33)),
+            (32, "nested deeper than 32 levels".to_owned())
+        );
+        assert_eq!(error(&nested(5000)).0, 32);
+
+        let negated = |depth: usize| format!("{}age = 1", "not ".repeat(depth));
+        assert!(parse(&negated(32), FIELDS).is_ok());
+        assert_eq!(
+            error(&negated(5000)),
+            (128, "nested deeper than 32 levels".to_owned())
+        );
+
+        let mixed = |depth: usize| format!("{}age = 1{}", "not (".repeat(depth), ")".repeat(depth));
+        assert!(parse(&mixed(16), FIELDS).is_ok());
+        assert_eq!(
+            error(&mixed(17)),
+            (80, "nested deeper than 32 levels".to_owned())
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("age >= 20 and nme = x"),
+            (14, "unknown field `nme`".to_owned())
+        );
+        assert_eq!(
+            error("age ~ 20"),
+            (
+                6,
+                "`~` is only supported on text fields, not `age`".to_owned()
+            )
+        );
+        assert_eq!(
+            error("age = twenty"),
+            (6, "`twenty` is not an integer for field `age`".to_owned())
+        );
+        assert_eq!(
+            error("name is null"),
+            (
+                12,
+                "`is null` is only supported on UUID fields, not `name`".to_owned()
+            )
+        );
+        assert_eq!(
+            error("name between x"),
+            (5, "unknown operator `between`".to_owned())
+        );
+        assert_eq!(
+            error("(age = 1"),
+            (8, "expected `)`, found the end".to_owned())
+        );
+        assert_eq!(error("age = 1)"), (7, "unexpected `)`".to_owned()));
+        assert_eq!(error("name = \"ünï"), (7, "unterminated string".to_owned()));
+        assert_eq!(
+            error("status in [a b]"),
+            (13, "expected `,` or `]`, found `b`".to_owned())
+        );
+        assert_eq!(error("age & 1"), (4, "unexpected `&`".to_owned()));
+        assert_eq!(
+            parse("and = 1", FIELDS).unwrap_err().to_string(),
+            "expected a field, found `and` at character 0"
+        );
+    }
+
+    #[test]
+    fn format_expr() {
+        let input = "age >= 20 and (name ~ john or status in [active, \"on trial\"]) \
+                     and not (parent is null or birthday < 2000-01-01)";
+        let expr = parse(input, FIELDS).unwrap();
+        assert_eq!(format(&expr), input);
+
+        assert_eq!(
+            format(&string(1, StringFilter::Equals("say \"hi\"".into()))),
+            "name = \"say \\\"hi\\\"\""
+        );
+        assert_eq!(format(&FilterExpr::And(vec![])), "()");
+    }
+
+    #[test]
+    fn format_filter_struct() {
+        #[derive(Serialize)]
+        struct Filters<'a> {
+            age: Option<NumberFilterSet>,
+            #[serde(borrow)]
+            name: Option<StringFilterSet<'a>>,
+            parent: Option<UuidFilterSet>,
+            status: Option<StringFilterSet<'a>>,
+        }
+
+        let id = uuid::uuid!("23191e01-8af8-4381-848c-f9387116d132");
+        let filters = Filters {
+            age: Some(NumberFilterSet::new().gte(20).is_in([1, 2])),
+            name: Some(StringFilterSet::new().contains("jo hn")),
+            parent: Some(UuidFilterSet::new().not_in([id]).is_null(false)),
+            status: None,
+        };
+
+        let text = format_filters(&filters).unwrap();
+        assert_eq!(
+            text,
+            "age >= 20 and age in [1, 2] and name ~ \"jo hn\" \
+             and parent not in [23191e01-8af8-4381-848c-f9387116d132] and parent is not null"
+        );
+        assert_eq!(
+            parse(&text, FIELDS).unwrap(),
+            FilterExpr::And(vec![
+                age(NumberFilter::GreaterThanEqual(20)),
+                age(NumberFilter::In(vec![1, 2])),
+                string(1, StringFilter::Contains("jo hn".into())),
+                FilterExpr::Field(&FIELDS[3], FieldFilter::Uuid(UuidFilter::NotIn(vec![id]))),
+                FilterExpr::Field(&FIELDS[3], FieldFilter::Uuid(UuidFilter::Null(false))),
+            ])
+        );
+    }
+
+    #[test]
+    fn text_wrapper() {
+        struct UserFields;
+
+        impl ExprFields for UserFields {
+            const FIELDS: &'static [Field] = FIELDS;
+        }
+
+        let text = "status = active or age < 18"
+            .parse::<Text<UserFields>>()
+            .unwrap();
+        assert_eq!(text.to_string(), "status = active or age < 18");
+        assert_eq!(
+            crate::to_querystring(
+                &[("filter", &text)]
+                    .into_iter()
+                    .collect::<std::collections::BTreeMap<_, _>>()
+            )
+            .unwrap(),
+            "filter=status%20%3D%20active%20or%20age%20%3C%2018"
+        );
+    }
+
+    fn leaf() -> impl Strategy<Value = FilterExpr<'static>> {
+        let text = || "\\PC{0,8}".prop_map(Cow::Owned);
+        let strings = prop_oneof![
+            text().prop_map(StringFilter::Equals),
+            text().prop_map(StringFilter::NotEquals),
+            text().prop_map(StringFilter::Contains),
+            text().prop_map(StringFilter::NotContains),
+            text().prop_map(StringFilter::StartsWith),
+            text().prop_map(StringFilter::EndsWith),
+            text().prop_map(StringFilter::Like),
+            text().prop_map(StringFilter::ILike),
+            prop::collection::vec(text(), 0..3).prop_map(StringFilter::In),
+        ];
+        let number = prop_oneof![
+            any::<i64>().prop_map(NumberFilter::Equals),
+            any::<i64>().prop_map(NumberFilter::LesserThanEqual),
+            any::<i64>().prop_map(NumberFilter::GreaterThan),
+            prop::collection::vec(any::<i64>(), 0..3).prop_map(NumberFilter::In),
+        ];
+        let uuid = || any::<u128>().prop_map(uuid::Uuid::from_u128);
+        let uuid = prop_oneof![
+            uuid().prop_map(UuidFilter::NotEquals),
+            uuid().prop_map(UuidFilter::GreaterThanEqual),
+            prop::collection::vec(uuid(), 0..3).prop_map(UuidFilter::NotIn),
+            any::<bool>().prop_map(UuidFilter::Null),
+        ];
+        let date = (0..3_000_000i64).prop_map(|days| {
+            NaiveDate::from_ymd_opt(1, 1, 1).unwrap() + chrono::Duration::days(days)
+        });
+        let datetime = (0..i32::MAX as i64, 0..1_000_000_000u32)
+            .prop_map(|(secs, nanos)| DateTime::from_timestamp(secs, nanos).unwrap().naive_utc());
+        let datetime_tz = (0..i32::MAX as i64, -86_399..86_400i32).prop_map(|(secs, offset)| {
+            let offset = chrono::FixedOffset::east_opt(offset / 60 * 60).unwrap();
+            DateTime::from_timestamp(secs, 0)
+                .unwrap()
+                .with_timezone(&offset)
+        });
+
+        prop_oneof![
+            number.prop_map(age),
+            (1..3usize, strings).prop_map(|(field, filter)| string(field, filter)),
+            uuid.prop_map(|filter| FilterExpr::Field(&FIELDS[3], FieldFilter::Uuid(filter))),
+            date.prop_map(|date| FilterExpr::Field(
+                &FIELDS[4],
+                FieldFilter::Date(DateFilter::NotEquals(date))
+            )),
+            datetime.prop_map(|datetime| FilterExpr::Field(
+                &FIELDS[5],
+                FieldFilter::DateTime(DateTimeFilter::After(datetime))
+            )),
+            datetime_tz.prop_map(|datetime| FilterExpr::Field(
+                &FIELDS[6],
+                FieldFilter::DateTimeTz(DateTimeTzFilter::Before(datetime))
+            )),
+        ]
+    }
+
+    fn expr() -> impl Strategy<Value = FilterExpr<'static>> {
+        leaf().prop_recursive(4, 32, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 2..4).prop_map(FilterExpr::And),
+                prop::collection::vec(inner.clone(), 2..4).prop_map(FilterExpr::Or),
+                inner.prop_map(|expr| FilterExpr::Not(Box::new(expr))),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(expr in expr()) {
+            let text = format(&expr);
+            let parsed = parse(&text, FIELDS).unwrap();
+            prop_assert_eq!(&parsed, &expr, "{}", text);
+            prop_assert_eq!(format(&parsed), text);
+        }
+    }
+}